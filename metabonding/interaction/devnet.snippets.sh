@@ -3,9 +3,12 @@ PROXY="https://devnet-gateway.elrond.com"
 CHAIN_ID="D"
 
 METABONDING_WASM_PATH="/home/elrond/Github/sc-metabonding-rs/metabonding/output/metabonding.wasm"
+METABONDING_VIEW_WASM_PATH="/home/elrond/Github/sc-metabonding-rs/metabonding/output/metabonding-view.wasm"
 ESDT_ISSUE_ADDRESS="erd1qqqqqqqqqqqqqqqpqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqzllls8a5w6u"
 
 CONTRACT_ADDRESS="erd1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq6gq4hu"
+# address of the external view contract, as returned by deployMetabondingViewSC
+VIEW_CONTRACT_ADDRESS=""
 
 SIGNER_PUB_KEY=0x52773868c13654355cca16adb389b09201fabf5d9d4b795ebbdae5b361b46f20
 deployMetabondingSC() {
@@ -18,6 +21,18 @@ deployMetabondingSC() {
         --send || return
 }
 
+# the external view contract reads the storage of the contract given on deploy
+deployMetabondingViewSC() {
+    metabonding_address="0x$(erdpy wallet bech32 --decode ${CONTRACT_ADDRESS})"
+    erdpy --verbose contract deploy --recall-nonce \
+        --bytecode=${METABONDING_VIEW_WASM_PATH} \
+        --pem=${WALLET_PEM} \
+        --gas-limit=50000000 \
+        --proxy=${PROXY} --chain=${CHAIN_ID} \
+        --arguments $metabonding_address \
+        --send || return
+}
+
 upgradeMetabondingSC() {
     erdpy --verbose contract upgrade ${CONTRACT_ADDRESS} --recall-nonce \
         --bytecode=${METABONDING_WASM_PATH} \
@@ -161,6 +176,15 @@ getProjectById() {
         --arguments $1
 }
 
+###PARAMS
+#1 - Week
+getActiveProjects() {
+    erdpy --verbose contract query ${VIEW_CONTRACT_ADDRESS} \
+        --proxy=${PROXY} \
+        --function="getActiveProjects" \
+        --arguments $1
+}
+
 getAllProjectIds() {
     erdpy --verbose contract query ${CONTRACT_ADDRESS} \
         --proxy=${PROXY} \
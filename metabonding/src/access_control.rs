@@ -1,14 +1,122 @@
 elrond_wasm::imports!();
 
+use crate::{admin::Role, audit_log::AdminActionKind, project::Epoch};
+
 #[elrond_wasm::module]
-pub trait AccessControlModule: crate::common_storage::CommonStorageModule {
-    fn require_caller_owner_or_signer(&self) {
+pub trait AccessControlModule:
+    crate::admin::AdminModule
+    + crate::audit_log::AuditLogModule
+    + crate::common_storage::CommonStorageModule
+{
+    /// Adds a signer whose signatures are accepted for claims and checkpoints.
+    /// Re-adding a removed signer cancels its grace period.
+    #[only_owner]
+    #[endpoint(addSigner)]
+    fn add_signer(&self, signer: ManagedAddress) {
+        self.require_no_timelock();
+
+        self.apply_add_signer(signer);
+    }
+
+    /// Removes a signer. Its signatures are still accepted during the signer grace period,
+    /// so claims signed with the old key remain valid while the new key is rolled out.
+    #[only_owner]
+    #[endpoint(removeSigner)]
+    fn remove_signer(&self, signer: ManagedAddress) {
+        self.require_no_timelock();
+
+        self.apply_remove_signer(signer);
+    }
+
+    fn apply_add_signer(&self, signer: ManagedAddress) {
+        require!(
+            self.signers().insert(signer.clone()),
+            "Signer already added"
+        );
+
+        let _ = self.retired_signers().remove(&signer);
+        self.log_admin_action(AdminActionKind::SignerAdded(signer));
+    }
+
+    fn apply_remove_signer(&self, signer: ManagedAddress) {
+        require!(self.signers().swap_remove(&signer), "Unknown signer");
+        require!(
+            self.claim_signature_threshold().get() <= self.signers().len(),
+            "Not enough signers left for threshold"
+        );
+
+        self.retire_signer(signer.clone());
+        self.log_admin_action(AdminActionKind::SignerRemoved(signer));
+    }
+
+    /// Replaces all the signers with the given one. The replaced signers are kept valid during the grace period.
+    fn apply_change_signer(&self, new_signer: ManagedAddress) {
+        let mut old_signers = ManagedVec::<Self::Api, ManagedAddress>::new();
+        for old_signer in self.signers().iter() {
+            old_signers.push(old_signer);
+        }
+        for old_signer in old_signers.iter() {
+            let _ = self.signers().swap_remove(&old_signer);
+            if *old_signer != new_signer {
+                self.retire_signer(old_signer.clone_value());
+            }
+        }
+
+        let _ = self.retired_signers().remove(&new_signer);
+        let _ = self.signers().insert(new_signer.clone());
+        require!(
+            self.claim_signature_threshold().get() <= 1,
+            "Not enough signers left for threshold"
+        );
+
+        self.log_admin_action(AdminActionKind::SignerChanged(new_signer));
+    }
+
+    /// Sets the number of distinct signers that have to sign each claim.
+    /// If higher than 1, claims have to go through claimRewardsMultiSig.
+    #[only_owner]
+    #[endpoint(setClaimSignatureThreshold)]
+    fn set_claim_signature_threshold(&self, threshold: usize) {
+        self.require_no_timelock();
+
+        self.apply_claim_signature_threshold(threshold);
+    }
+
+    fn apply_claim_signature_threshold(&self, threshold: usize) {
+        require!(
+            threshold <= self.signers().len(),
+            "Threshold higher than number of signers"
+        );
+
+        self.claim_signature_threshold().set(threshold);
+        self.log_admin_action(AdminActionKind::ClaimSignatureThresholdChanged(threshold));
+    }
+
+    #[only_owner]
+    #[endpoint(setSignerGracePeriodEpochs)]
+    fn set_signer_grace_period_epochs(&self, grace_period_epochs: Epoch) {
+        self.signer_grace_period_epochs().set(grace_period_epochs);
+        self.log_admin_action(AdminActionKind::SignerGracePeriodChanged(
+            grace_period_epochs,
+        ));
+    }
+
+    fn retire_signer(&self, signer: ManagedAddress) {
+        let grace_period_epochs = self.signer_grace_period_epochs().get();
+        if grace_period_epochs > 0 {
+            let current_epoch = self.blockchain().get_block_epoch();
+            let _ = self
+                .retired_signers()
+                .insert(signer, current_epoch + grace_period_epochs);
+        }
+    }
+
+    /// Removed signers may no longer call signer-only endpoints, even during their grace period.
+    fn require_caller_checkpoint_submitter_or_signer(&self) {
         let caller = self.blockchain().get_caller();
-        let owner = self.blockchain().get_owner_address();
-        let signer = self.signer().get();
         require!(
-            caller == owner || caller == signer,
-            "Only owner or signer may call this function"
+            self.has_role(&caller, Role::CheckpointSubmitter) || self.signers().contains(&caller),
+            "Only checkpoint submitter or signer may call this function"
         );
     }
 }
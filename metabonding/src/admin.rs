@@ -0,0 +1,112 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::audit_log::AdminActionKind;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Clone, Copy)]
+pub enum Role {
+    /// May add and finalize checkpoints
+    CheckpointSubmitter,
+    /// May add, approve and manage projects
+    ProjectManager,
+    /// May pause and unpause the SC
+    Pauser,
+    /// May sweep expired rewards and manage the keeper and cleanup bounties
+    Treasurer,
+}
+
+/// Admins handle the day-to-day operations, i.e. checkpoints and project management.
+/// Single operations may also be delegated through roles, without making the address an admin.
+/// Only the owner may change the admins, roles, signers and fees.
+#[elrond_wasm::module]
+pub trait AdminModule:
+    elrond_wasm_modules::pause::PauseModule
+    + crate::audit_log::AuditLogModule
+    + crate::common_storage::CommonStorageModule
+{
+    #[only_owner]
+    #[endpoint(addAdmins)]
+    fn add_admins(&self, admins: MultiValueEncoded<ManagedAddress>) {
+        self.require_no_timelock();
+
+        self.apply_add_admins(admins.to_vec());
+    }
+
+    #[only_owner]
+    #[endpoint(removeAdmins)]
+    fn remove_admins(&self, admins: MultiValueEncoded<ManagedAddress>) {
+        self.require_no_timelock();
+
+        self.apply_remove_admins(admins.to_vec());
+    }
+
+    #[only_owner]
+    #[endpoint(grantRole)]
+    fn grant_role(&self, address: ManagedAddress, role: Role) {
+        self.require_no_timelock();
+
+        self.apply_grant_role(address, role);
+    }
+
+    #[only_owner]
+    #[endpoint(revokeRole)]
+    fn revoke_role(&self, address: ManagedAddress, role: Role) {
+        self.require_no_timelock();
+
+        self.apply_revoke_role(address, role);
+    }
+
+    fn apply_add_admins(&self, admins: ManagedVec<ManagedAddress>) {
+        let mut mapper = self.admins();
+        for admin in admins.iter() {
+            let _ = mapper.insert(admin.clone_value());
+        }
+
+        self.log_admin_action(AdminActionKind::AdminsAdded(admins));
+    }
+
+    fn apply_remove_admins(&self, admins: ManagedVec<ManagedAddress>) {
+        let mut mapper = self.admins();
+        for admin in admins.iter() {
+            let _ = mapper.swap_remove(&admin);
+        }
+
+        self.log_admin_action(AdminActionKind::AdminsRemoved(admins));
+    }
+
+    fn apply_grant_role(&self, address: ManagedAddress, role: Role) {
+        require!(self.roles(&address).insert(role), "Role already granted");
+        self.log_admin_action(AdminActionKind::RoleGranted(address, role));
+    }
+
+    fn apply_revoke_role(&self, address: ManagedAddress, role: Role) {
+        require!(self.roles(&address).swap_remove(&role), "Role not granted");
+        self.log_admin_action(AdminActionKind::RoleRevoked(address, role));
+    }
+
+    /// The owner is always considered an admin.
+    fn is_admin(&self, address: &ManagedAddress) -> bool {
+        address == &self.blockchain().get_owner_address() || self.admins().contains(address)
+    }
+
+    /// Admins implicitly have all the roles.
+    fn has_role(&self, address: &ManagedAddress, role: Role) -> bool {
+        self.is_admin(address) || self.roles(address).contains(&role)
+    }
+
+    fn require_caller_role(&self, role: Role) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.has_role(&caller, role),
+            "Caller does not have the required role"
+        );
+    }
+
+    #[view(getAdmins)]
+    #[storage_mapper("admins")]
+    fn admins(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[view(getRoles)]
+    #[storage_mapper("roles")]
+    fn roles(&self, address: &ManagedAddress) -> UnorderedSetMapper<Role>;
+}
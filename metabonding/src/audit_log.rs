@@ -0,0 +1,149 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::{
+    admin::Role,
+    project::{Epoch, ProjectId},
+    rewards::Week,
+};
+
+pub const MAX_AUDIT_LOG_PAGE_SIZE: usize = 50;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub enum AdminActionKind<M: ManagedTypeApi> {
+    ProjectAdded(ProjectId<M>),
+    ProjectRemoved(ProjectId<M>),
+    CheckpointCorrected(Week),
+    SignerAdded(ManagedAddress<M>),
+    SignerRemoved(ManagedAddress<M>),
+    SignerChanged(ManagedAddress<M>),
+    ProjectClaimFeeUpdated(ProjectId<M>, u64),
+    ListingFeeUpdated(EgldOrEsdtTokenIdentifier<M>, BigUint<M>),
+    WeekScheduleChanged(Epoch, Epoch),
+    TreasuryAddressChanged(ManagedAddress<M>),
+    ClaimSignatureThresholdChanged(usize),
+    SigCheckDisabledChanged(bool),
+    ClaimExpirationWeeksChanged(Week),
+    EmergencyWithdrawal(EgldOrEsdtTokenIdentifier<M>, u64, BigUint<M>),
+    TokenRescued(
+        EgldOrEsdtTokenIdentifier<M>,
+        u64,
+        BigUint<M>,
+        ManagedAddress<M>,
+    ),
+    AdminsAdded(ManagedVec<M, ManagedAddress<M>>),
+    AdminsRemoved(ManagedVec<M, ManagedAddress<M>>),
+    RoleGranted(ManagedAddress<M>, Role),
+    RoleRevoked(ManagedAddress<M>, Role),
+    OraclesAdded(ManagedVec<M, ManagedAddress<M>>),
+    OraclesRemoved(ManagedVec<M, ManagedAddress<M>>),
+    OracleQuorumChanged(usize),
+    CheckpointSourcesChanged(ManagedAddress<M>, ManagedAddress<M>),
+    GloballyPaused,
+    GloballyUnpaused,
+    ProjectClaimsPaused(ProjectId<M>),
+    ProjectClaimsResumed(ProjectId<M>),
+    ProjectMaxRewardPerUserPerWeekChanged(ProjectId<M>, BigUint<M>),
+    ProjectRewardsLockEpochsChanged(ProjectId<M>, u64),
+    MaxActiveProjectsChanged(usize),
+    MaxProjectsPerClaimChanged(usize),
+    WeekMerkleRootSet(Week),
+    SignerGracePeriodChanged(Epoch),
+    MinPayoutAmountChanged(TokenIdentifier<M>, BigUint<M>),
+    RewardsGraceWeeksChanged(Week),
+    CompoundFarmAdded(ManagedAddress<M>, TokenIdentifier<M>),
+    CompoundFarmRemoved(ManagedAddress<M>),
+    EnergyFactoryAddressChanged(ManagedAddress<M>),
+    EnergyLookupEnabledChanged(bool),
+    KeepersAdded(ManagedVec<M, ManagedAddress<M>>),
+    KeepersRemoved(ManagedVec<M, ManagedAddress<M>>),
+    KeeperBountyChanged(EgldOrEsdtTokenIdentifier<M>, BigUint<M>),
+    KeeperBountyWithdrawn(BigUint<M>),
+    CleanupBountyChanged(EgldOrEsdtTokenIdentifier<M>, BigUint<M>),
+    CleanupBountyWithdrawn(BigUint<M>),
+    RewardTokenWhitelistEnabledChanged(bool),
+    RewardTokenWhitelisted(TokenIdentifier<M>),
+    RewardTokenRemovedFromWhitelist(TokenIdentifier<M>),
+    CheckpointDisputeEpochsChanged(Epoch),
+    DepositDeadlineEpochsChanged(Epoch),
+    StakeProvidersAdded(ManagedVec<M, ManagedAddress<M>>),
+    StakeProvidersRemoved(ManagedVec<M, ManagedAddress<M>>),
+    TimelockDelayChanged(Epoch),
+    TimelockActionScheduled(u64),
+    TimelockActionCancelled(u64),
+    NewOwnerProposed(ManagedAddress<M>),
+    OwnershipTransferred(ManagedAddress<M>),
+    OwnershipTransferCancelled,
+    ClaimedWeeksMigrated(Week),
+    CheckpointAdded(Week),
+    CheckpointFinalized(Week),
+    CheckpointTotalEnergySet(Week),
+    CheckpointSqrtSumsSet(Week),
+    BonusWeekSet(Week, u64),
+    WeekDataHashSet(Week),
+    CheckpointsPruned(Week),
+    ExpiredRewardsSwept(ProjectId<M>, BigUint<M>),
+    /// Any other change of a project's settings, by the name of the endpoint that changed it
+    ProjectUpdated(ProjectId<M>, ManagedBuffer<M>),
+    ProjectProposalRejected(ProjectId<M>),
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub struct AdminAction<M: ManagedTypeApi> {
+    pub caller: ManagedAddress<M>,
+    pub timestamp: u64,
+    pub kind: AdminActionKind<M>,
+}
+
+/// Append-only log of the administrative changes, so they can be audited without an external indexer.
+#[elrond_wasm::module]
+pub trait AuditLogModule {
+    fn log_admin_action(&self, kind: AdminActionKind<Self::Api>) {
+        self.log_admin_action_by(self.blockchain().get_caller(), kind);
+    }
+
+    /// In callbacks, the caller is the called SC, so the address that started the action is given instead
+    fn log_admin_action_by(&self, caller: ManagedAddress, kind: AdminActionKind<Self::Api>) {
+        let _ = self.audit_log().push(&AdminAction {
+            caller,
+            timestamp: self.blockchain().get_block_timestamp(),
+            kind,
+        });
+    }
+
+    fn log_project_update(&self, project_id: &ProjectId<Self::Api>, endpoint_name: &[u8]) {
+        self.log_admin_action(AdminActionKind::ProjectUpdated(
+            project_id.clone(),
+            ManagedBuffer::from(endpoint_name),
+        ));
+    }
+
+    /// Returns up to page_size entries, starting from the given (1-based) index.
+    #[label("metabonding-external-view")]
+    #[view(getAuditLog)]
+    fn get_audit_log(
+        &self,
+        from_index: usize,
+        page_size: usize,
+    ) -> MultiValueEncoded<AdminAction<Self::Api>> {
+        require!(from_index > 0, "Invalid index");
+        require!(page_size <= MAX_AUDIT_LOG_PAGE_SIZE, "Page size too large");
+
+        let mapper = self.audit_log();
+        let mut result = MultiValueEncoded::new();
+        let last_index = core::cmp::min(from_index + page_size, mapper.len() + 1);
+        for index in from_index..last_index {
+            result.push(mapper.get(index));
+        }
+
+        result
+    }
+
+    #[view(getAuditLogLength)]
+    fn get_audit_log_length(&self) -> usize {
+        self.audit_log().len()
+    }
+
+    #[storage_mapper("auditLog")]
+    fn audit_log(&self) -> VecMapper<AdminAction<Self::Api>>;
+}
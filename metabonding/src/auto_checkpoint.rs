@@ -0,0 +1,147 @@
+elrond_wasm::imports!();
+
+use crate::{audit_log::AdminActionKind, rewards::Week};
+
+pub mod delegation_proxy {
+    elrond_wasm::imports!();
+
+    #[elrond_wasm::proxy]
+    pub trait DelegationProxy {
+        #[view(getTotalActiveStake)]
+        fn get_total_active_stake(&self) -> BigUint;
+    }
+}
+
+pub mod lkmex_staking_proxy {
+    elrond_wasm::imports!();
+
+    #[elrond_wasm::proxy]
+    pub trait LkmexStakingProxy {
+        #[view(getTotalLockedAssetSupply)]
+        fn get_total_locked_asset_supply(&self) -> BigUint;
+    }
+}
+
+#[elrond_wasm::module]
+pub trait AutoCheckpointModule:
+    elrond_wasm_modules::pause::PauseModule
+    + crate::project::ProjectModule
+    + crate::access_control::AccessControlModule
+    + crate::common_storage::CommonStorageModule
+    + crate::math::MathModule
+    + crate::validation::ValidationModule
+    + crate::events::EventsModule
+    + crate::oracle::OracleModule
+    + crate::rewards::RewardsModule
+{
+    /// Sets the contracts that are queried for the checkpoint totals:
+    /// - delegation_address - the system delegation SC, which is on the metachain
+    /// - lkmex_staking_address - the Metabonding-Staking SC, which has to be in the same shard
+    #[only_owner]
+    #[endpoint(setCheckpointSources)]
+    fn set_checkpoint_sources(
+        &self,
+        delegation_address: ManagedAddress,
+        lkmex_staking_address: ManagedAddress,
+    ) {
+        require!(
+            self.blockchain().is_smart_contract(&delegation_address)
+                && self.blockchain().is_smart_contract(&lkmex_staking_address),
+            "Invalid SC address"
+        );
+
+        self.delegation_address().set(&delegation_address);
+        self.lkmex_staking_address().set(&lkmex_staking_address);
+        self.log_admin_action(AdminActionKind::CheckpointSourcesChanged(
+            delegation_address,
+            lkmex_staking_address,
+        ));
+    }
+
+    /// Adds the checkpoint for the current week, using the totals from the delegation
+    /// and Metabonding-Staking SCs, instead of totals computed off-chain.
+    /// The LKMEX total is read synchronously, while the delegation total is read through an async call,
+    /// so the checkpoint is only stored in the callback.
+    /// Only a checkpoint submitter or a signer may request checkpoints.
+    #[endpoint(requestRewardsCheckpoint)]
+    fn request_rewards_checkpoint(&self) {
+        self.require_caller_checkpoint_submitter_or_signer();
+        require!(
+            !self.delegation_address().is_empty() && !self.lkmex_staking_address().is_empty(),
+            "Checkpoint sources not set"
+        );
+        require!(
+            self.pending_auto_checkpoint_week().is_empty(),
+            "Checkpoint request already in progress"
+        );
+
+        // totals are read now, so they are recorded for the current week
+        let week = self.get_current_week();
+        require!(
+            week > self.get_last_checkpoint_week(),
+            "Invalid checkpoint week"
+        );
+
+        let lkmex_staking_address = self.lkmex_staking_address().get();
+        let total_lkmex_staked: BigUint = self
+            .lkmex_staking_proxy(lkmex_staking_address)
+            .get_total_locked_asset_supply()
+            .execute_on_dest_context();
+
+        self.pending_auto_checkpoint_week().set(week);
+
+        let delegation_address = self.delegation_address().get();
+        self.delegation_proxy(delegation_address)
+            .get_total_active_stake()
+            .async_call()
+            .with_callback(
+                self.callbacks()
+                    .auto_checkpoint_callback(week, total_lkmex_staked),
+            )
+            .call_and_exit()
+    }
+
+    /// The checkpoint is not stored if another one was added for the same or a later week in the meantime.
+    #[callback]
+    fn auto_checkpoint_callback(
+        &self,
+        week: Week,
+        total_lkmex_staked: BigUint,
+        #[call_result] result: ManagedAsyncCallResult<BigUint>,
+    ) {
+        self.pending_auto_checkpoint_week().clear();
+
+        if let ManagedAsyncCallResult::Ok(total_delegation_supply) = result {
+            if week > self.get_last_checkpoint_week() {
+                let current_week = self.get_current_week();
+                self.add_checkpoint(
+                    week,
+                    total_delegation_supply,
+                    total_lkmex_staked,
+                    current_week,
+                );
+            }
+        }
+    }
+
+    #[proxy]
+    fn delegation_proxy(&self, sc_address: ManagedAddress) -> delegation_proxy::Proxy<Self::Api>;
+
+    #[proxy]
+    fn lkmex_staking_proxy(
+        &self,
+        sc_address: ManagedAddress,
+    ) -> lkmex_staking_proxy::Proxy<Self::Api>;
+
+    #[view(getDelegationAddress)]
+    #[storage_mapper("delegationAddress")]
+    fn delegation_address(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getLkmexStakingAddress)]
+    #[storage_mapper("lkmexStakingAddress")]
+    fn lkmex_staking_address(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getPendingAutoCheckpointWeek)]
+    #[storage_mapper("pendingAutoCheckpointWeek")]
+    fn pending_auto_checkpoint_week(&self) -> SingleValueMapper<Week>;
+}
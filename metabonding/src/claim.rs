@@ -1,21 +1,59 @@
 elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
 
 use crate::{
-    project::PROJECT_EXPIRATION_WEEKS,
-    rewards::{RewardsCheckpoint, Week},
-    validation::Signature,
+    audit_log::AdminActionKind,
+    common_storage::{MAX_BONUS_BPS, MAX_FEE_BPS},
+    project::{Epoch, Project, ProjectId},
+    rewards::{RewardsCheckpoint, RoundingRemainder, Week, WeeklyReward},
+    validation::{MerkleHash, Signature, SignerSignature},
 };
 
 const MAX_CLAIM_ARG_PAIRS: usize = 5;
-const CLAIM_NR_ARGS_PER_PAIR: usize = 4;
+const CLAIM_NR_ARGS_PER_PAIR: usize = 5;
+const SIMULATION_NR_ARGS_PER_WEEK: usize = 3;
+pub const DEFAULT_MAX_PROJECTS_PER_CLAIM: usize = 10;
+const MIN_GAS_PER_CLAIMED_PROJECT: u64 = 5_000_000;
 
-pub type ClaimArgPair<M> = MultiValue4<Week, BigUint<M>, BigUint<M>, Signature<M>>;
+pub type ClaimArgPair<M> = MultiValue5<Week, BigUint<M>, BigUint<M>, Epoch, Signature<M>>;
 
 pub struct ClaimArgsWrapper<M: ManagedTypeApi> {
     pub week: Week,
     pub user_delegation_amount: BigUint<M>,
     pub user_lkmex_staked_amount: BigUint<M>,
     pub checkpoint: RewardsCheckpoint<M>,
+    pub partially_claimed: bool,
+}
+
+pub type ClaimArgsArray<M> = ArrayVec<ClaimArgsWrapper<M>, MAX_CLAIM_ARG_PAIRS>;
+
+/// The sent payments, whether projects are left to be claimed for the week,
+/// and the number of those projects
+pub type ClaimBatchResultType<M> = MultiValue3<ManagedVec<M, EsdtTokenPayment<M>>, bool, usize>;
+
+/// A week's reward for a project, including the bonuses, kept until the rewards are distributed,
+/// when it is known whether they are paid or parked
+#[derive(ManagedVecItem)]
+pub struct WeeklyClaim<M: ManagedTypeApi> {
+    pub week: Week,
+    pub amount: BigUint<M>,
+}
+
+/// The consecutive weeks, ending with last_week, in which a user claimed a project's rewards
+#[derive(TypeAbi, TopEncode, TopDecode, Default)]
+pub struct ClaimStreak {
+    pub last_week: Week,
+    pub nr_weeks: Week,
+}
+
+/// Lifetime claim statistics of a user.
+/// The first and last claim weeks are the lowest and highest claimed weeks, or 0 if the user never claimed.
+#[derive(TypeAbi, TopEncode)]
+pub struct UserStats<M: ManagedTypeApi> {
+    pub nr_weeks_claimed: usize,
+    pub first_claim_week: Week,
+    pub last_claim_week: Week,
+    pub total_claimed: ManagedVec<M, EsdtTokenPayment<M>>,
 }
 
 #[elrond_wasm::module]
@@ -27,6 +65,9 @@ pub trait ClaimModule:
     + crate::math::MathModule
     + crate::validation::ValidationModule
     + crate::rewards::RewardsModule
+    + crate::claimed_weeks::ClaimedWeeksModule
+    + crate::energy_factory::EnergyFactoryModule
+    + crate::events::EventsModule
     + sc_whitelist_module::SCWhitelistModule
 {
     /// Claims rewards for the given user.
@@ -34,10 +75,12 @@ pub trait ClaimModule:
     /// If the user performs their own claim, this address should be their own.
     ///
     /// Claims rewards for the given weeks. Maximum of MAX_CLAIM_ARG_PAIRS weeks can be claimed per call.
+    /// Rewards of projects sharing the same token are merged into a single payment.
     /// Arguments are pairs of:
     /// week: number,
     /// user_delegation_amount: BigUint,
     /// user_lkmex_staked_amount: BigUint,
+    /// valid_until_epoch: number - the last epoch in which the signature is accepted,
     /// signature: 120 bytes
     #[endpoint(claimRewards)]
     fn claim_rewards(
@@ -45,116 +88,1614 @@ pub trait ClaimModule:
         original_caller: ManagedAddress,
         claim_args: MultiValueEncoded<ClaimArgPair<Self::Api>>,
     ) -> ManagedVec<EsdtTokenPayment> {
-        require!(self.not_paused(), "May not claim rewards while paused");
-        require!(
-            claim_args.raw_len() / CLAIM_NR_ARGS_PER_PAIR <= MAX_CLAIM_ARG_PAIRS,
-            "Too many arguments"
-        );
+        self.require_claim_allowed(&claim_args);
 
         let caller = self.blockchain().get_caller();
         if caller != original_caller {
             self.require_sc_address_whitelisted(&caller);
         }
 
+        let current_week = self.get_current_week();
+        let args = self.collect_claim_args(&original_caller, current_week, claim_args);
+
+        if caller != original_caller {
+            self.send_claimed_rewards(&original_caller, &caller, current_week, &args)
+        } else {
+            self.send_claimed_rewards_to_destination(&original_caller, current_week, &args)
+        }
+    }
+
+    /// Sets the address where the caller's rewards will be sent on claim.
+    /// Setting the caller's own address removes the destination.
+    /// Does not apply to claims performed through whitelisted proxy contracts.
+    #[endpoint(setRewardDestination)]
+    fn set_reward_destination(&self, destination: ManagedAddress) {
+        let caller = self.blockchain().get_caller();
+        if caller == destination {
+            self.reward_destination(&caller).clear();
+        } else {
+            self.reward_destination(&caller).set(&destination);
+        }
+    }
+
+    /// Sets an address which may claim rewards on behalf of the caller.
+    /// Rewards claimed by the delegate are always sent to the caller, or their reward destination.
+    #[endpoint(setClaimDelegate)]
+    fn set_claim_delegate(&self, delegate: ManagedAddress) {
+        let caller = self.blockchain().get_caller();
+        require!(caller != delegate, "Cannot delegate to self");
+
+        self.claim_delegate(&caller).set(&delegate);
+    }
+
+    #[endpoint(removeClaimDelegate)]
+    fn remove_claim_delegate(&self) {
+        let caller = self.blockchain().get_caller();
+        self.claim_delegate(&caller).clear();
+    }
+
+    /// Claims rewards on behalf of the given user. Only the user's registered delegate may call this.
+    /// The rewards are sent to the user (or their reward destination), not to the delegate.
+    /// Arguments have the same format as for claimRewards.
+    #[endpoint(claimForUser)]
+    fn claim_for_user(
+        &self,
+        user: ManagedAddress,
+        claim_args: MultiValueEncoded<ClaimArgPair<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        self.require_claim_allowed(&claim_args);
+
+        let caller = self.blockchain().get_caller();
+        let delegate_mapper = self.claim_delegate(&user);
+        require!(
+            !delegate_mapper.is_empty() && delegate_mapper.get() == caller,
+            "Not the claim delegate for this user"
+        );
+
+        let current_week = self.get_current_week();
+        let args = self.collect_claim_args(&user, current_week, claim_args);
+
+        self.send_claimed_rewards_to_destination(&user, current_week, &args)
+    }
+
+    /// Resumable version of claimRewards, meant for users with many unclaimed weeks.
+    /// Processes at most max_weeks weeks, starting from the caller's claim cursor,
+    /// and moves the cursor after the last processed week.
+    /// Weeks before the cursor, weeks without a checkpoint, already claimed weeks and expired weeks
+    /// are skipped instead of failing, so the same arguments can be sent again to continue the claim.
+    /// Weeks after the last checkpoint are left for a later call.
+    ///
+    /// Arguments have the same format as for claimRewards, and should be in ascending week order.
+    #[endpoint(claimPending)]
+    fn claim_pending(
+        &self,
+        max_weeks: usize,
+        claim_args: MultiValueEncoded<ClaimArgPair<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        self.require_claims_not_paused();
+        require!(
+            max_weeks > 0 && max_weeks <= MAX_CLAIM_ARG_PAIRS,
+            "Invalid max weeks"
+        );
+
+        let caller = self.blockchain().get_caller();
         let current_week = self.get_current_week();
         let last_checkpoint_week = self.get_last_checkpoint_week();
         let rewards_nr_first_grace_weeks = self.rewards_nr_first_grace_weeks().get();
 
-        let mut args = ArrayVec::<ClaimArgsWrapper<Self::Api>, MAX_CLAIM_ARG_PAIRS>::new();
+        let cursor_mapper = self.claim_cursor(&caller);
+        let mut cursor = cursor_mapper.get();
+        let mut args = ClaimArgsArray::new();
         for arg in claim_args {
-            let (week, user_delegation_amount, user_lkmex_staked_amount, signature) =
-                arg.into_tuple();
+            if args.len() == max_weeks {
+                break;
+            }
+
+            let (
+                week,
+                user_delegation_amount,
+                user_lkmex_staked_amount,
+                valid_until_epoch,
+                signature,
+            ) = arg.into_tuple();
+            if week < cursor {
+                continue;
+            }
+
+            // weeks with pending or future checkpoints are left for a later call
+            if week > last_checkpoint_week
+                || (self.has_checkpoint(week) && !self.is_checkpoint_finalized(week))
+            {
+                break;
+            }
+
+            // a week skipped by the checkpoints, or pruned, can never be claimed
+            cursor = week + 1;
+            if !self.has_checkpoint(week)
+                || self.is_week_claimed(&caller, week)
+                || !self.is_claim_in_time(week, current_week, rewards_nr_first_grace_weeks)
+            {
+                continue;
+            }
+
+            let claim_arg = self.validate_and_mark_claim(
+                &caller,
+                last_checkpoint_week,
+                week,
+                user_delegation_amount,
+                user_lkmex_staked_amount,
+                valid_until_epoch,
+                &signature,
+            );
+            args.push(claim_arg);
+        }
+
+        if !args.is_empty() {
+            self.increment_claim_nonce(&caller);
+        }
+        cursor_mapper.set(cursor);
+
+        self.send_claimed_rewards_to_destination(&caller, current_week, &args)
+    }
+
+    /// Claims rewards for a single week on behalf of the user. May be called by anyone (i.e. a relayer),
+    /// but the rewards are always sent to the user (or their reward destination).
+    /// Arguments:
+    /// - user
+    /// - week
+    /// - user_delegation_amount
+    /// - user_lkmex_staked_amount
+    /// - valid_until_epoch - the last epoch in which the signature is accepted
+    /// - signature - the signer's signature, same as for claimRewards
+    /// - user_signature - the user's signature over the relayed claim intent,
+    ///     which includes the user's current relayed claim nonce
+    #[allow(clippy::too_many_arguments)]
+    #[endpoint(relayedClaim)]
+    fn relayed_claim(
+        &self,
+        user: ManagedAddress,
+        week: Week,
+        user_delegation_amount: BigUint,
+        user_lkmex_staked_amount: BigUint,
+        valid_until_epoch: Epoch,
+        signature: Signature<Self::Api>,
+        user_signature: Signature<Self::Api>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        self.require_claims_not_paused();
+
+        let nonce_mapper = self.relayed_claim_nonce(&user);
+        let nonce = nonce_mapper.get();
+        self.verify_relayed_claim_intent(
+            &user,
+            nonce,
+            week,
+            &user_delegation_amount,
+            &user_lkmex_staked_amount,
+            &user_signature,
+        );
+        nonce_mapper.set(nonce + 1);
+
+        let current_week = self.get_current_week();
+        let mut args = ClaimArgsArray::new();
+        args.push(self.collect_single_claim_arg(
+            &user,
+            current_week,
+            self.get_last_checkpoint_week(),
+            self.rewards_nr_first_grace_weeks().get(),
+            week,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+            valid_until_epoch,
+            &signature,
+        ));
+        self.increment_claim_nonce(&user);
+
+        self.send_claimed_rewards_to_destination(&user, current_week, &args)
+    }
+
+    /// Claims rewards for a single week, using a merkle proof instead of a signature.
+    /// The merkle root for the week has to be set beforehand by a checkpoint submitter or signer.
+    /// Arguments:
+    /// - week
+    /// - user_delegation_amount
+    /// - user_lkmex_staked_amount
+    /// - proof - the sibling hashes from the leaf up to the root
+    #[endpoint(claimWithProof)]
+    fn claim_with_proof(
+        &self,
+        week: Week,
+        user_delegation_amount: BigUint,
+        user_lkmex_staked_amount: BigUint,
+        proof: MultiValueEncoded<MerkleHash<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        self.require_claims_not_paused();
+
+        let caller = self.blockchain().get_caller();
+        let current_week = self.get_current_week();
+        let rewards_nr_first_grace_weeks = self.rewards_nr_first_grace_weeks().get();
+        require!(
+            !self.is_week_claimed(&caller, week),
+            "Already claimed rewards for this week"
+        );
+        require!(
+            self.is_claim_in_time(week, current_week, rewards_nr_first_grace_weeks),
+            "Claiming too late"
+        );
+        require!(
+            week <= self.get_last_checkpoint_week(),
+            "No checkpoint for week yet"
+        );
+
+        self.verify_merkle_proof(
+            week,
+            &caller,
+            &user_delegation_amount,
+            &user_lkmex_staked_amount,
+            proof,
+        );
+
+        let mut args = ClaimArgsArray::new();
+        args.push(self.mark_week_claimed(
+            &caller,
+            week,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+        ));
+
+        self.send_claimed_rewards_to_destination(&caller, current_week, &args)
+    }
+
+    /// Claims the rewards that were kept for the caller while the given projects' claims were paused.
+    #[endpoint(claimPausedProjectRewards)]
+    fn claim_paused_project_rewards(
+        &self,
+        project_ids: MultiValueEncoded<ProjectId<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        self.require_claims_not_paused();
+
+        let caller = self.blockchain().get_caller();
+        let mut payments = ManagedVec::new();
+        for project_id in project_ids {
+            require!(
+                !self.project_claims_paused(&project_id).get(),
+                "Project claims are paused"
+            );
+
+            let pending_rewards = self.paused_project_rewards(&caller, &project_id).take();
+            let pending_partner_rewards = self.paused_partner_rewards(&caller, &project_id).take();
+            if pending_rewards > 0 {
+                self.total_paused_project_rewards(&project_id)
+                    .update(|total| *total -= &pending_rewards);
+
+                let project = self.get_project_or_panic(&project_id);
+                let reward_token_nonce = self.project_reward_token_nonce(&project_id).get();
+                self.pay_or_lock_project_rewards(
+                    &caller,
+                    &project_id,
+                    project.reward_token,
+                    reward_token_nonce,
+                    pending_rewards,
+                    &mut payments,
+                );
+            }
+            self.pay_partner_rewards(&project_id, &pending_partner_rewards, &mut payments, false);
+        }
+
+        let payments = self.pay_rewards_to_destination(&caller, payments);
+
+        payments
+    }
+
+    /// Locks the caller's claimed rewards of the given project through the energy factory,
+    /// which sends the locked tokens to the caller's reward destination.
+    /// Locked rewards are claimed one project at a time, apart from the other rewards,
+    /// so an unavailable energy factory can't block the regular claims. They are never kept as dust.
+    #[endpoint(claimLockedRewards)]
+    fn claim_locked_rewards(&self, project_id: ProjectId<Self::Api>) -> EsdtTokenPayment {
+        self.require_claims_not_paused();
+
+        let caller = self.blockchain().get_caller();
+        let pending_rewards = self.pending_locked_rewards(&caller, &project_id).take();
+        require!(pending_rewards > 0, "No locked rewards to claim");
+        self.total_pending_locked_rewards(&project_id)
+            .update(|total| *total -= &pending_rewards);
+
+        let project = self.get_project_or_panic(&project_id);
+        let reward_token_nonce = self.project_reward_token_nonce(&project_id).get();
+        let destination = self.get_reward_destination(&caller);
+        let locked_tokens = self.lock_rewards_for_user(
+            &caller,
+            &destination,
+            &project_id,
+            project.reward_token.clone(),
+            reward_token_nonce,
+            pending_rewards.clone(),
+            self.project_rewards_lock_epochs(&project_id).get(),
+        );
+
+        let claimed =
+            EsdtTokenPayment::new(project.reward_token, reward_token_nonce, pending_rewards);
+        self.record_user_claimed_payments(&caller, &ManagedVec::from_single_item(claimed));
+
+        locked_tokens
+    }
+
+    /// Claims rewards for a single week, with the signatures of several signers,
+    /// as required when the claim signature threshold is higher than 1.
+    /// Arguments:
+    /// - week
+    /// - user_delegation_amount
+    /// - user_lkmex_staked_amount
+    /// - valid_until_epoch - the last epoch in which the signatures are accepted
+    /// - signatures - pairs of (signer, signature), each signature being the same as for claimRewards
+    #[endpoint(claimRewardsMultiSig)]
+    fn claim_rewards_multi_sig(
+        &self,
+        week: Week,
+        user_delegation_amount: BigUint,
+        user_lkmex_staked_amount: BigUint,
+        valid_until_epoch: Epoch,
+        signatures: MultiValueEncoded<SignerSignature<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        self.require_claims_not_paused();
+
+        let caller = self.blockchain().get_caller();
+        let current_week = self.get_current_week();
+        let rewards_nr_first_grace_weeks = self.rewards_nr_first_grace_weeks().get();
+        require!(
+            !self.is_week_claimed(&caller, week),
+            "Already claimed rewards for this week"
+        );
+        require!(
+            self.is_claim_in_time(week, current_week, rewards_nr_first_grace_weeks),
+            "Claiming too late"
+        );
+        require!(
+            week <= self.get_last_checkpoint_week(),
+            "No checkpoint for week yet"
+        );
+
+        self.verify_signatures(
+            week,
+            &caller,
+            &user_delegation_amount,
+            &user_lkmex_staked_amount,
+            valid_until_epoch,
+            signatures,
+        );
 
+        let mut args = ClaimArgsArray::new();
+        args.push(self.mark_week_claimed(
+            &caller,
+            week,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+        ));
+
+        self.send_claimed_rewards_to_destination(&caller, current_week, &args)
+    }
+
+    /// Claims rewards for a single week, but only for the given projects.
+    /// The rest of the week's rewards can be claimed later, either through this endpoint
+    /// or through any of the regular claim endpoints.
+    /// Arguments:
+    /// - week
+    /// - user_delegation_amount
+    /// - user_lkmex_staked_amount
+    /// - valid_until_epoch - the last epoch in which the signature is accepted
+    /// - signature - same as for claimRewards
+    /// - project_ids - the projects for which rewards are claimed, which must be active in the given week
+    #[endpoint(claimRewardsForProjects)]
+    fn claim_rewards_for_projects(
+        &self,
+        week: Week,
+        user_delegation_amount: BigUint,
+        user_lkmex_staked_amount: BigUint,
+        valid_until_epoch: Epoch,
+        signature: Signature<Self::Api>,
+        project_ids: MultiValueEncoded<ProjectId<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        self.require_claims_not_paused();
+
+        let caller = self.blockchain().get_caller();
+        let current_week = self.get_current_week();
+        self.start_partial_claim(
+            &caller,
+            current_week,
+            week,
+            &user_delegation_amount,
+            &user_lkmex_staked_amount,
+            valid_until_epoch,
+            &signature,
+        );
+
+        let checkpoint = self.get_checkpoint_or_panic(week);
+        let week_project_ids = self.get_week_project_ids(week);
+        let mut payments = ManagedVec::new();
+        for project_id in project_ids {
             require!(
-                !self.rewards_claimed(&caller, week).get(),
-                "Already claimed rewards for this week"
+                week_project_ids.contains(&project_id),
+                "Project not active in the given week"
             );
-            require!(week <= last_checkpoint_week, "No checkpoint for week yet");
+
+            let claimed_mapper = self.project_rewards_claimed(&caller, week, &project_id);
             require!(
-                self.is_claim_in_time(week, current_week, rewards_nr_first_grace_weeks),
-                "Claiming too late"
+                !claimed_mapper.get(),
+                "Already claimed rewards for this project"
             );
+            claimed_mapper.set(true);
 
-            let checkpoint: RewardsCheckpoint<Self::Api> = self.rewards_checkpoints().get(week);
-            self.verify_signature(
+            let project = self.get_project_or_panic(&project_id);
+            self.claim_project_week_rewards(
+                &caller,
+                current_week,
                 week,
-                &original_caller,
+                &project_id,
+                project,
                 &user_delegation_amount,
                 &user_lkmex_staked_amount,
-                &signature,
+                &checkpoint,
+                &mut payments,
+            );
+        }
+
+        let payments = self.pay_rewards_to_destination(&caller, payments);
+
+        payments
+    }
+
+    /// Claims the rewards for a single week, processing at most the configured number of projects per call
+    /// (DEFAULT_MAX_PROJECTS_PER_CLAIM by default), and stopping early if the gas left is too low.
+    /// The claimed projects are remembered, so the endpoint can be called again with the same arguments
+    /// and a signature for the next nonce until no more projects remain, at which point the week is marked as claimed.
+    /// Arguments are the same as for claimRewardsForProjects, without the project IDs.
+    ///
+    /// Returns the sent payments, whether there are more projects to be claimed, and their number.
+    #[endpoint(claimRewardsBatch)]
+    fn claim_rewards_batch(
+        &self,
+        week: Week,
+        user_delegation_amount: BigUint,
+        user_lkmex_staked_amount: BigUint,
+        valid_until_epoch: Epoch,
+        signature: Signature<Self::Api>,
+    ) -> ClaimBatchResultType<Self::Api> {
+        self.require_claims_not_paused();
+
+        let caller = self.blockchain().get_caller();
+        let current_week = self.get_current_week();
+        self.start_partial_claim(
+            &caller,
+            current_week,
+            week,
+            &user_delegation_amount,
+            &user_lkmex_staked_amount,
+            valid_until_epoch,
+            &signature,
+        );
+
+        let checkpoint = self.get_checkpoint_or_panic(week);
+        let max_projects = self.get_max_projects_per_claim();
+        let mut payments = ManagedVec::new();
+        let mut nr_claimed_projects = 0;
+        let mut nr_remaining_projects = 0;
+        for project_id in &self.get_week_project_ids(week) {
+            let claimed_mapper = self.project_rewards_claimed(&caller, week, &project_id);
+            if claimed_mapper.get() {
+                continue;
+            }
+
+            let project = match self.projects().get(&project_id) {
+                Some(project) => project,
+                None => continue,
+            };
+
+            let gas_left = self.blockchain().get_gas_left();
+            if nr_claimed_projects == max_projects || gas_left < MIN_GAS_PER_CLAIMED_PROJECT {
+                nr_remaining_projects += 1;
+                continue;
+            }
+
+            claimed_mapper.set(true);
+            self.claim_project_week_rewards(
+                &caller,
+                current_week,
+                week,
+                &project_id,
+                project,
+                &user_delegation_amount,
+                &user_lkmex_staked_amount,
+                &checkpoint,
+                &mut payments,
+            );
+            nr_claimed_projects += 1;
+        }
+
+        if nr_remaining_projects == 0 {
+            self.set_week_claimed(&caller, week);
+            self.partially_claimed_week(&caller, week).clear();
+        }
+
+        let payments = self.pay_rewards_to_destination(&caller, payments);
+
+        (payments, nr_remaining_projects > 0, nr_remaining_projects).into()
+    }
+
+    /// Sets the maximum number of projects processed by a single claimRewardsBatch call
+    #[only_owner]
+    #[endpoint(setMaxProjectsPerClaim)]
+    fn set_max_projects_per_claim(&self, max_projects_per_claim: usize) {
+        require!(max_projects_per_claim > 0, "Invalid max projects per claim");
+
+        self.max_projects_per_claim().set(max_projects_per_claim);
+        self.log_admin_action(AdminActionKind::MaxProjectsPerClaimChanged(
+            max_projects_per_claim,
+        ));
+    }
+
+    #[view(getMaxProjectsPerClaim)]
+    fn get_max_projects_per_claim(&self) -> usize {
+        let mapper = self.max_projects_per_claim();
+        if mapper.is_empty() {
+            DEFAULT_MAX_PROJECTS_PER_CLAIM
+        } else {
+            mapper.get()
+        }
+    }
+
+    /// Validates a claim for only some of the week's projects, and marks the week as partially claimed
+    #[allow(clippy::too_many_arguments)]
+    fn start_partial_claim(
+        &self,
+        caller: &ManagedAddress,
+        current_week: Week,
+        week: Week,
+        user_delegation_amount: &BigUint,
+        user_lkmex_staked_amount: &BigUint,
+        valid_until_epoch: Epoch,
+        signature: &Signature<Self::Api>,
+    ) {
+        let rewards_nr_first_grace_weeks = self.rewards_nr_first_grace_weeks().get();
+        require!(
+            !self.is_week_claimed(caller, week),
+            "Already claimed rewards for this week"
+        );
+        require!(
+            self.is_claim_in_time(week, current_week, rewards_nr_first_grace_weeks),
+            "Claiming too late"
+        );
+        require!(
+            week <= self.get_last_checkpoint_week(),
+            "No checkpoint for week yet"
+        );
+        require!(
+            self.is_checkpoint_finalized(week),
+            "Checkpoint not finalized yet"
+        );
+
+        self.verify_signature(
+            week,
+            caller,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+            valid_until_epoch,
+            signature,
+        );
+        let partially_claimed_mapper = self.partially_claimed_week(caller, week);
+        if !partially_claimed_mapper.get() {
+            partially_claimed_mapper.set(true);
+            self.record_user_claimed_week(caller, week);
+        }
+        self.week_has_claims(week).set_if_empty(true);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn claim_project_week_rewards(
+        &self,
+        caller: &ManagedAddress,
+        current_week: Week,
+        week: Week,
+        project_id: &ProjectId<Self::Api>,
+        project: Project<Self::Api>,
+        user_delegation_amount: &BigUint,
+        user_lkmex_staked_amount: &BigUint,
+        checkpoint: &RewardsCheckpoint<Self::Api>,
+        payments: &mut ManagedVec<EsdtTokenPayment>,
+    ) {
+        let opt_weekly_reward = self.get_weekly_reward_for_project(
+            caller,
+            project_id,
+            &project,
+            current_week,
+            week,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+            checkpoint,
+        );
+
+        if let Some(weekly_reward) = opt_weekly_reward {
+            let mut bonus_pool = self.project_bonus_pool(project_id).get();
+            let mut streak = self.user_project_claim_streak(caller, project_id).get();
+            let rewards_for_project = &weekly_reward.amount
+                + &self.take_weekly_bonuses(
+                    project_id,
+                    week,
+                    &weekly_reward.amount,
+                    &mut bonus_pool,
+                    &mut streak,
+                );
+            self.record_weekly_project_reward(caller, week, project_id, &weekly_reward);
+            self.save_bonus_state(caller, project_id, &bonus_pool, &streak);
+            let paid = self.distribute_project_rewards(
+                caller,
+                project_id,
+                project.reward_token.clone(),
+                rewards_for_project.clone(),
+                &weekly_reward.partner_amounts,
+                payments,
+                false,
             );
+            self.emit_weekly_claim_event(
+                caller,
+                week,
+                project_id,
+                &project.reward_token,
+                &rewards_for_project,
+                paid,
+            );
+        }
+    }
 
-            self.rewards_claimed(&original_caller, week).set(true);
+    fn require_claims_not_paused(&self) {
+        self.require_not_globally_paused();
+        require!(self.not_paused(), "May not claim rewards while paused");
+    }
+
+    /// Sets the minimum amount of the given token paid out on claim. Smaller amounts are kept
+    /// as dust for the user, until the accumulated amount reaches the minimum or the user calls claimDust.
+    /// An amount of 0 removes the minimum.
+    #[only_owner]
+    #[endpoint(setMinPayoutAmount)]
+    fn set_min_payout_amount(&self, token_id: TokenIdentifier, amount: BigUint) {
+        require!(token_id.is_valid_esdt_identifier(), "Invalid token");
+
+        self.min_payout_amount(&token_id).set(&amount);
+        self.log_admin_action(AdminActionKind::MinPayoutAmountChanged(token_id, amount));
+    }
+
+    /// Pays out all the dust kept for the caller, regardless of the minimum payout amounts.
+    #[endpoint(claimDust)]
+    fn claim_dust(&self) -> ManagedVec<EsdtTokenPayment> {
+        self.require_claims_not_paused();
+
+        let caller = self.blockchain().get_caller();
+        let mut dust_mapper = self.user_dust(&caller);
+        require!(!dust_mapper.is_empty(), "No dust to claim");
 
-            args.push(ClaimArgsWrapper {
+        let mut payments = ManagedVec::new();
+        for ((token_id, token_nonce), amount) in dust_mapper.iter() {
+            self.total_dust(&token_id).update(|total| *total -= &amount);
+            payments.push(EsdtTokenPayment::new(token_id, token_nonce, amount));
+        }
+        dust_mapper.clear();
+
+        self.send_payments_to_destination(&caller, &payments);
+
+        payments
+    }
+
+    fn require_claim_allowed(&self, claim_args: &MultiValueEncoded<ClaimArgPair<Self::Api>>) {
+        self.require_claims_not_paused();
+        require!(
+            claim_args.raw_len() / CLAIM_NR_ARGS_PER_PAIR <= MAX_CLAIM_ARG_PAIRS,
+            "Too many arguments"
+        );
+    }
+
+    fn collect_claim_args(
+        &self,
+        user: &ManagedAddress,
+        current_week: Week,
+        claim_args: MultiValueEncoded<ClaimArgPair<Self::Api>>,
+    ) -> ClaimArgsArray<Self::Api> {
+        let last_checkpoint_week = self.get_last_checkpoint_week();
+        let rewards_nr_first_grace_weeks = self.rewards_nr_first_grace_weeks().get();
+
+        let mut args = ClaimArgsArray::new();
+        for arg in claim_args {
+            let (
                 week,
                 user_delegation_amount,
                 user_lkmex_staked_amount,
-                checkpoint,
-            });
+                valid_until_epoch,
+                signature,
+            ) = arg.into_tuple();
+
+            let claim_arg = self.collect_single_claim_arg(
+                user,
+                current_week,
+                last_checkpoint_week,
+                rewards_nr_first_grace_weeks,
+                week,
+                user_delegation_amount,
+                user_lkmex_staked_amount,
+                valid_until_epoch,
+                &signature,
+            );
+            args.push(claim_arg);
         }
+        self.increment_claim_nonce(user);
 
+        args
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn collect_single_claim_arg(
+        &self,
+        user: &ManagedAddress,
+        current_week: Week,
+        last_checkpoint_week: Week,
+        rewards_nr_first_grace_weeks: Week,
+        week: Week,
+        user_delegation_amount: BigUint,
+        user_lkmex_staked_amount: BigUint,
+        valid_until_epoch: Epoch,
+        signature: &Signature<Self::Api>,
+    ) -> ClaimArgsWrapper<Self::Api> {
+        require!(
+            !self.is_week_claimed(user, week),
+            "Already claimed rewards for this week"
+        );
+        require!(
+            self.is_claim_in_time(week, current_week, rewards_nr_first_grace_weeks),
+            "Claiming too late"
+        );
+
+        self.validate_and_mark_claim(
+            user,
+            last_checkpoint_week,
+            week,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+            valid_until_epoch,
+            signature,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn validate_and_mark_claim(
+        &self,
+        user: &ManagedAddress,
+        last_checkpoint_week: Week,
+        week: Week,
+        user_delegation_amount: BigUint,
+        user_lkmex_staked_amount: BigUint,
+        valid_until_epoch: Epoch,
+        signature: &Signature<Self::Api>,
+    ) -> ClaimArgsWrapper<Self::Api> {
+        require!(week <= last_checkpoint_week, "No checkpoint for week yet");
+
+        self.verify_batch_signature(
+            week,
+            user,
+            &user_delegation_amount,
+            &user_lkmex_staked_amount,
+            valid_until_epoch,
+            signature,
+        );
+
+        self.mark_week_claimed(user, week, user_delegation_amount, user_lkmex_staked_amount)
+    }
+
+    fn mark_week_claimed(
+        &self,
+        user: &ManagedAddress,
+        week: Week,
+        user_delegation_amount: BigUint,
+        user_lkmex_staked_amount: BigUint,
+    ) -> ClaimArgsWrapper<Self::Api> {
+        require!(
+            self.is_checkpoint_finalized(week),
+            "Checkpoint not finalized yet"
+        );
+
+        let checkpoint = self.get_checkpoint_or_panic(week);
+        self.set_week_claimed(user, week);
+        self.week_has_claims(week).set_if_empty(true);
+
+        // partially claimed weeks were already recorded on the first partial claim
+        let partially_claimed = self.partially_claimed_week(user, week).take();
+        if !partially_claimed {
+            self.record_user_claimed_week(user, week);
+        }
+
+        ClaimArgsWrapper {
+            week,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+            checkpoint,
+            partially_claimed,
+        }
+    }
+
+    fn record_user_claimed_week(&self, user: &ManagedAddress, week: Week) {
+        self.user_nr_weeks_claimed(user)
+            .update(|nr_weeks| *nr_weeks += 1);
+        self.user_first_claim_week(user).update(|first_week| {
+            if *first_week == 0 || week < *first_week {
+                *first_week = week;
+            }
+        });
+        self.user_last_claim_week(user).update(|last_week| {
+            if week > *last_week {
+                *last_week = week;
+            }
+        });
+    }
+
+    fn record_user_claimed_payments(
+        &self,
+        user: &ManagedAddress,
+        payments: &ManagedVec<EsdtTokenPayment>,
+    ) {
+        if payments.is_empty() {
+            return;
+        }
+
+        self.user_total_claimed(user).update(|total_claimed| {
+            for payment in payments {
+                self.merge_payment(
+                    total_claimed,
+                    payment.token_identifier,
+                    payment.token_nonce,
+                    payment.amount,
+                );
+            }
+        });
+    }
+
+    fn send_claimed_rewards_to_destination(
+        &self,
+        user: &ManagedAddress,
+        current_week: Week,
+        args: &ClaimArgsArray<Self::Api>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        let payments = self.compute_claimed_rewards(user, current_week, args, false);
+        self.pay_rewards_to_destination(user, payments)
+    }
+
+    /// Applies the minimum payout amounts, then sends the rest of the payments to the user's reward destination.
+    /// Returns the sent payments.
+    fn pay_rewards_to_destination(
+        &self,
+        user: &ManagedAddress,
+        payments: ManagedVec<EsdtTokenPayment>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        let payments = self.apply_min_payout(user, payments, false);
+        self.send_payments_to_destination(user, &payments);
+
+        payments
+    }
+
+    fn send_payments_to_destination(
+        &self,
+        user: &ManagedAddress,
+        payments: &ManagedVec<EsdtTokenPayment>,
+    ) {
+        let destination = self.get_reward_destination(user);
+        if !payments.is_empty() {
+            self.send_rewards(&destination, payments);
+        }
+        self.record_user_claimed_payments(user, payments);
+
+        if &destination != user {
+            self.rewards_sent_to_destination_event(user, &destination, payments);
+        }
+        self.claim_finished_event(user, &destination, payments);
+    }
+
+    fn get_reward_destination(&self, user: &ManagedAddress) -> ManagedAddress {
+        let destination_mapper = self.reward_destination(user);
+        if destination_mapper.is_empty() {
+            user.clone()
+        } else {
+            destination_mapper.get()
+        }
+    }
+
+    fn send_claimed_rewards(
+        &self,
+        user: &ManagedAddress,
+        to: &ManagedAddress,
+        current_week: Week,
+        args: &ClaimArgsArray<Self::Api>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        let weekly_rewards = self.compute_claimed_rewards(user, current_week, args, false);
+        let weekly_rewards = self.apply_min_payout(user, weekly_rewards, false);
+        if !weekly_rewards.is_empty() {
+            self.send_rewards(to, &weekly_rewards);
+        }
+        self.record_user_claimed_payments(user, &weekly_rewards);
+
+        self.claim_finished_event(user, to, &weekly_rewards);
+
+        weekly_rewards
+    }
+
+    /// Computes the rewards for the already validated claim arguments,
+    /// and deducts them from the projects' leftover funds.
+    /// The minimum payout amounts are not applied, so the callers apply them once, before paying the rewards.
+    /// When simulating, nothing is saved to storage, and no transfers or events are made.
+    fn compute_claimed_rewards(
+        &self,
+        user: &ManagedAddress,
+        current_week: Week,
+        args: &ClaimArgsArray<Self::Api>,
+        simulate: bool,
+    ) -> ManagedVec<EsdtTokenPayment> {
         let mut weekly_rewards = ManagedVec::new();
-        for (id, project) in self.projects().iter() {
+        for id in &self.get_claim_project_ids(args) {
+            let project = match self.projects().get(&id) {
+                Some(project) => project,
+                None => continue,
+            };
+            let mut bonus_pool = self.project_bonus_pool(&id).get();
+            let mut streak = self.user_project_claim_streak(user, &id).get();
             let mut opt_rewards_for_project = None;
+            let mut partner_rewards = ManagedVec::new();
+            let mut weekly_claims = ManagedVec::<Self::Api, WeeklyClaim<Self::Api>>::new();
+
+            for arg in args {
+                if arg.partially_claimed && self.project_rewards_claimed(user, arg.week, &id).get()
+                {
+                    continue;
+                }
 
-            for arg in &args {
                 let opt_weekly_reward = self.get_weekly_reward_for_project(
+                    user,
                     &id,
                     &project,
                     current_week,
                     arg.week,
                     &arg.user_delegation_amount,
                     &arg.user_lkmex_staked_amount,
-                    &arg.checkpoint.total_delegation_supply,
-                    &arg.checkpoint.total_lkmex_staked,
+                    &arg.checkpoint,
                 );
 
                 if let Some(weekly_reward) = opt_weekly_reward {
+                    let weekly_amount = &weekly_reward.amount
+                        + &self.take_weekly_bonuses(
+                            &id,
+                            arg.week,
+                            &weekly_reward.amount,
+                            &mut bonus_pool,
+                            &mut streak,
+                        );
+                    if !simulate {
+                        self.record_weekly_project_reward(user, arg.week, &id, &weekly_reward);
+                        weekly_claims.push(WeeklyClaim {
+                            week: arg.week,
+                            amount: weekly_amount.clone(),
+                        });
+                    }
+
                     match &mut opt_rewards_for_project {
-                        Some(prev_amt) => *prev_amt += weekly_reward,
-                        None => opt_rewards_for_project = Some(weekly_reward),
+                        Some(prev_amt) => *prev_amt += weekly_amount,
+                        None => opt_rewards_for_project = Some(weekly_amount),
                     }
+                    self.add_partner_amounts(&mut partner_rewards, &weekly_reward.partner_amounts);
                 }
             }
 
             if let Some(rewards_for_project) = opt_rewards_for_project {
-                self.leftover_project_funds(&id)
-                    .update(|leftover| *leftover -= &rewards_for_project);
+                if !simulate {
+                    self.save_bonus_state(user, &id, &bonus_pool, &streak);
+                }
+                let paid = self.distribute_project_rewards(
+                    user,
+                    &id,
+                    project.reward_token.clone(),
+                    rewards_for_project,
+                    &partner_rewards,
+                    &mut weekly_rewards,
+                    simulate,
+                );
+                for weekly_claim in &weekly_claims {
+                    self.emit_weekly_claim_event(
+                        user,
+                        weekly_claim.week,
+                        &id,
+                        &project.reward_token,
+                        &weekly_claim.amount,
+                        paid,
+                    );
+                }
+            }
+        }
 
-                weekly_rewards.push(EsdtTokenPayment::new(
-                    project.reward_token,
-                    0,
+        weekly_rewards
+    }
+
+    /// Returns the projects active in any of the claimed weeks, without duplicates
+    fn get_claim_project_ids(
+        &self,
+        args: &ClaimArgsArray<Self::Api>,
+    ) -> ManagedVec<ProjectId<Self::Api>> {
+        let mut project_ids = ManagedVec::new();
+        for arg in args {
+            for id in &self.get_week_project_ids(arg.week) {
+                if !project_ids.contains(&id) {
+                    project_ids.push(id);
+                }
+            }
+        }
+
+        project_ids
+    }
+
+    /// Adds the user's dust to the payments of the same token and nonce, then keeps the payments
+    /// below their token's minimum payout amount as dust. Returns the payments that are to be sent.
+    /// When simulating, the dust is left unchanged.
+    fn apply_min_payout(
+        &self,
+        user: &ManagedAddress,
+        payments: ManagedVec<EsdtTokenPayment>,
+        simulate: bool,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        let mut dust_mapper = self.user_dust(user);
+        let mut payments_to_send = ManagedVec::new();
+        for mut payment in &payments {
+            let dust_key = (payment.token_identifier.clone(), payment.token_nonce);
+            if let Some(prev_dust) = dust_mapper.get(&dust_key) {
+                if !simulate {
+                    let _ = dust_mapper.remove(&dust_key);
+                    self.total_dust(&payment.token_identifier)
+                        .update(|total| *total -= &prev_dust);
+                }
+                payment.amount += prev_dust;
+            }
+
+            let min_payout = self.min_payout_amount(&payment.token_identifier).get();
+            if payment.amount < min_payout {
+                if !simulate {
+                    self.total_dust(&payment.token_identifier)
+                        .update(|total| *total += &payment.amount);
+                    let _ = dust_mapper.insert(dust_key, payment.amount);
+                }
+            } else {
+                payments_to_send.push(payment);
+            }
+        }
+
+        payments_to_send
+    }
+
+    /// Records the user's claim of the project's weekly reward.
+    fn record_weekly_project_reward(
+        &self,
+        user: &ManagedAddress,
+        week: Week,
+        project_id: &ProjectId<Self::Api>,
+        weekly_reward: &WeeklyReward<Self::Api>,
+    ) {
+        let mut rounding_remainders = self.accumulate_rounding_remainder(
+            self.delegation_rounding_remainder(project_id, week),
+            &weekly_reward.delegation_remainder,
+        );
+        rounding_remainders += self.accumulate_rounding_remainder(
+            self.lkmex_rounding_remainder(project_id, week),
+            &weekly_reward.lkmex_remainder,
+        );
+
+        self.project_week_claims(project_id, week)
+            .update(|claims| *claims += 1);
+        let claimer_mapper = self.project_claimer(project_id, user);
+        if !claimer_mapper.get() {
+            claimer_mapper.set(true);
+            self.project_unique_claimers(project_id)
+                .update(|claimers| *claimers += 1);
+        }
+
+        // capped rewards and rounding remainders are counted as claimed,
+        // so they are refunded instead of swept
+        self.claimed_project_week_rewards(project_id, week)
+            .update(|claimed| {
+                *claimed += &weekly_reward.amount;
+                *claimed += &weekly_reward.capped_amount;
+                *claimed += &rounding_remainders;
+            });
+        if weekly_reward.capped_amount > 0 {
+            self.project_capped_rewards(project_id)
+                .update(|capped| *capped += &weekly_reward.capped_amount);
+            self.project_week_capped_rewards(project_id, week)
+                .update(|capped| *capped += &weekly_reward.capped_amount);
+        }
+        if rounding_remainders > 0 {
+            self.project_rounding_remainders(project_id)
+                .update(|remainders| *remainders += &rounding_remainders);
+        }
+    }
+
+    /// Emits a claim event for the paid weekly rewards, or a rewardsParked event for the ones kept for the user,
+    /// i.e. the rewards of paused projects, including those paused by the circuit breaker, and the locked rewards
+    fn emit_weekly_claim_event(
+        &self,
+        user: &ManagedAddress,
+        week: Week,
+        project_id: &ProjectId<Self::Api>,
+        reward_token: &TokenIdentifier,
+        amount: &BigUint,
+        paid: bool,
+    ) {
+        if paid {
+            self.claim_event(user, week, project_id, reward_token, amount);
+        } else {
+            self.rewards_parked_event(user, week, project_id, reward_token, amount);
+        }
+    }
+
+    /// Returns the bonuses for the weekly reward amount, i.e. the reward floor top-up,
+    /// the streak bonus and the bonus week amount, which are taken from the bonus pool in this order,
+    /// as far as it has funds. Extends the user's claim streak, or starts a new one if a week was missed.
+    /// The bonus pool and the streak are saved through save_bonus_state.
+    fn take_weekly_bonuses(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        week: Week,
+        amount: &BigUint,
+        bonus_pool: &mut BigUint,
+        streak: &mut ClaimStreak,
+    ) -> BigUint {
+        let floor_top_up = self.get_reward_floor_top_up(project_id, amount);
+        let mut bonuses = self.take_from_pool(bonus_pool, floor_top_up);
+
+        let bonus_bps_per_week = self.project_streak_bonus_bps_per_week(project_id).get();
+        if bonus_bps_per_week > 0 {
+            *streak = self.get_next_claim_streak(core::mem::take(streak), week);
+            let streak_bonus =
+                self.get_streak_bonus_amount(project_id, bonus_bps_per_week, streak, amount);
+            bonuses += self.take_from_pool(bonus_pool, streak_bonus);
+        }
+
+        let bonus_week_amount = self.get_bonus_week_amount(project_id, week, amount);
+        bonuses += self.take_from_pool(bonus_pool, bonus_week_amount);
+
+        bonuses
+    }
+
+    fn save_bonus_state(
+        &self,
+        user: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+        bonus_pool: &BigUint,
+        streak: &ClaimStreak,
+    ) {
+        self.project_bonus_pool(project_id).set(bonus_pool);
+        if self.project_streak_bonus_bps_per_week(project_id).get() > 0 {
+            self.user_project_claim_streak(user, project_id).set(streak);
+        }
+    }
+
+    /// Returns the amount needed to raise a positive reward to the project's minimum reward per user,
+    /// regardless of the funds in the project's bonus pool.
+    fn get_reward_floor_top_up(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        amount: &BigUint,
+    ) -> BigUint {
+        let min_reward = self.project_min_reward_per_user(project_id).get();
+        if amount == &0 || amount >= &min_reward {
+            return BigUint::zero();
+        }
+
+        min_reward - amount
+    }
+
+    fn get_next_claim_streak(&self, mut streak: ClaimStreak, week: Week) -> ClaimStreak {
+        if streak.nr_weeks > 0 && streak.last_week + 1 == week {
+            streak.nr_weeks += 1;
+        } else {
+            streak.nr_weeks = 1;
+        }
+        streak.last_week = week;
+
+        streak
+    }
+
+    fn get_streak_bonus_amount(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        bonus_bps_per_week: u64,
+        streak: &ClaimStreak,
+        amount: &BigUint,
+    ) -> BigUint {
+        let bonus_bps = core::cmp::min(
+            bonus_bps_per_week * (streak.nr_weeks - 1) as u64,
+            self.project_max_streak_bonus_bps(project_id).get(),
+        );
+        amount * bonus_bps / MAX_BONUS_BPS
+    }
+
+    /// Removes up to the given amount from the pool. Returns the removed amount.
+    fn take_from_pool(&self, pool: &mut BigUint, amount: BigUint) -> BigUint {
+        let taken = core::cmp::min(amount, pool.clone());
+        *pool -= &taken;
+
+        taken
+    }
+
+    /// Adds the remainder to the week's accumulated remainder for the pool,
+    /// and returns the number of whole tokens it adds up to, which are removed from the accumulator.
+    fn accumulate_rounding_remainder(
+        &self,
+        accumulated_mapper: SingleValueMapper<BigUint>,
+        remainder: &RoundingRemainder<Self::Api>,
+    ) -> BigUint {
+        if remainder.numerator == 0 {
+            return BigUint::zero();
+        }
+
+        let accumulated = accumulated_mapper.get() + &remainder.numerator;
+        let whole_tokens = &accumulated / &remainder.denominator;
+        accumulated_mapper.set(&(accumulated % &remainder.denominator));
+
+        whole_tokens
+    }
+
+    /// Deducts the rewards from the project's leftover funds and adds them to the payments,
+    /// along with the partner rewards, which are deducted from the partner rewards' leftover funds.
+    /// The project's claim fee, if any, is sent to the treasury.
+    /// Rewards of projects with paused claims are kept for the user until the project is resumed.
+    /// If the rewards exceed the project's leftover funds, which points to a wrong checkpoint or signature,
+    /// the project's claims are paused instead, and the user's rewards for the project are kept for them,
+    /// from what is left of the project's funds, until the owner resumes the project's claims.
+    /// When simulating, only the payments are updated.
+    /// Returns false if the rewards were parked instead of being paid, i.e. kept for the user
+    /// because the project's claims are paused or its rewards are locked.
+    fn distribute_project_rewards(
+        &self,
+        user: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+        reward_token: TokenIdentifier,
+        mut rewards_for_project: BigUint,
+        partner_rewards: &ManagedVec<BigUint>,
+        payments: &mut ManagedVec<EsdtTokenPayment>,
+        simulate: bool,
+    ) -> bool {
+        let leftover_funds_mapper = self.leftover_project_funds(project_id);
+        let leftover_funds = leftover_funds_mapper.get();
+        if rewards_for_project > leftover_funds {
+            if simulate {
+                return false;
+            }
+
+            self.project_claims_paused(project_id).set(true);
+            self.project_claims_paused_by(project_id)
+                .set(&self.blockchain().get_sc_address());
+            self.claim_circuit_breaker_event(
+                project_id,
+                user,
+                &leftover_funds,
+                &rewards_for_project,
+            );
+
+            // the week is already marked as claimed, so the rewards are kept instead of being lost,
+            // but only as far as the project's own funds go, as the SC's balance is shared with other projects
+            leftover_funds_mapper.clear();
+            self.total_claimed_project_rewards(project_id)
+                .update(|total_claimed| *total_claimed += &leftover_funds);
+            self.keep_paused_project_rewards(user, project_id, &leftover_funds);
+            self.keep_paused_partner_rewards(user, project_id, partner_rewards);
+            return false;
+        }
+
+        if !simulate {
+            leftover_funds_mapper.set(&(leftover_funds - &rewards_for_project));
+            self.total_claimed_project_rewards(project_id)
+                .update(|total_claimed| *total_claimed += &rewards_for_project);
+        }
+
+        let reward_token_nonce = self.project_reward_token_nonce(project_id).get();
+
+        let fee = self.get_project_claim_fee(project_id, &rewards_for_project);
+        if fee > 0 {
+            rewards_for_project -= &fee;
+            if !simulate {
+                self.project_collected_fees(project_id)
+                    .update(|collected| *collected += &fee);
+
+                let treasury_address = self.treasury_address().get();
+                self.send_reward(&treasury_address, &reward_token, reward_token_nonce, &fee);
+            }
+        }
+        let has_partner_rewards = partner_rewards.iter().any(|amount| *amount > 0);
+        if rewards_for_project == 0 && !has_partner_rewards {
+            return true;
+        }
+
+        if self.project_claims_paused(project_id).get() {
+            if !simulate {
+                self.keep_paused_project_rewards(user, project_id, &rewards_for_project);
+                self.keep_paused_partner_rewards(user, project_id, partner_rewards);
+            }
+            return false;
+        }
+
+        let locked =
+            rewards_for_project > 0 && self.project_rewards_lock_epochs(project_id).get() > 0;
+        if rewards_for_project > 0 {
+            if !simulate {
+                self.pay_or_lock_project_rewards(
+                    user,
+                    project_id,
+                    reward_token,
+                    reward_token_nonce,
+                    rewards_for_project,
+                    payments,
+                );
+            } else if !locked {
+                // locked rewards are kept until claimLockedRewards, so they are not part of the payments
+                self.merge_payment(
+                    payments,
+                    reward_token,
+                    reward_token_nonce,
                     rewards_for_project,
-                ));
+                );
             }
         }
+        self.pay_partner_rewards(project_id, partner_rewards, payments, simulate);
 
-        if !weekly_rewards.is_empty() {
-            self.send().direct_multi(&caller, &weekly_rewards);
+        !locked
+    }
+
+    fn keep_paused_project_rewards(
+        &self,
+        user: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+        amount: &BigUint,
+    ) {
+        self.total_paused_project_rewards(project_id)
+            .update(|total| *total += amount);
+        self.paused_project_rewards(user, project_id)
+            .update(|pending| *pending += amount);
+    }
+
+    fn keep_paused_partner_rewards(
+        &self,
+        user: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+        amounts: &ManagedVec<BigUint>,
+    ) {
+        if amounts.is_empty() {
+            return;
         }
 
-        weekly_rewards
+        self.paused_partner_rewards(user, project_id)
+            .update(|pending| self.add_partner_amounts(pending, amounts));
     }
 
+    /// Adds the partner amounts to the totals, one partner reward at a time
+    fn add_partner_amounts(&self, totals: &mut ManagedVec<BigUint>, amounts: &ManagedVec<BigUint>) {
+        if totals.is_empty() {
+            *totals = amounts.clone();
+            return;
+        }
+
+        for (index, amount) in amounts.iter().enumerate() {
+            if index < totals.len() {
+                let total = totals.get(index).clone_value() + &*amount;
+                let _ = totals.set(index, &total);
+            } else {
+                totals.push(amount.clone_value());
+            }
+        }
+    }
+
+    /// Keeps the rewards for the user until claimLockedRewards if the project's rewards are locked,
+    /// otherwise adds them to the payments
+    fn pay_or_lock_project_rewards(
+        &self,
+        user: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+        reward_token: TokenIdentifier,
+        reward_token_nonce: u64,
+        amount: BigUint,
+        payments: &mut ManagedVec<EsdtTokenPayment>,
+    ) {
+        if self.project_rewards_lock_epochs(project_id).get() > 0 {
+            self.total_pending_locked_rewards(project_id)
+                .update(|total| *total += &amount);
+            self.pending_locked_rewards(user, project_id)
+                .update(|pending| *pending += &amount);
+        } else {
+            self.merge_payment(payments, reward_token, reward_token_nonce, amount);
+        }
+    }
+
+    /// Adds the user's partner rewards, in the order of the project's partner rewards, at most up to
+    /// their leftover funds, and deducts them from the partner rewards' leftover funds, unless simulating
+    fn pay_partner_rewards(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        amounts: &ManagedVec<BigUint>,
+        payments: &mut ManagedVec<EsdtTokenPayment>,
+        simulate: bool,
+    ) {
+        let partner_rewards_mapper = self.project_partner_rewards(project_id);
+        for (i, amount) in amounts.iter().enumerate() {
+            let index = i + 1;
+            if *amount == 0 || index > partner_rewards_mapper.len() {
+                continue;
+            }
+
+            let mut partner_reward = partner_rewards_mapper.get(index);
+            let amount =
+                core::cmp::min(amount.clone_value(), partner_reward.leftover_funds.clone());
+            if amount == 0 {
+                continue;
+            }
+
+            if !simulate {
+                partner_reward.leftover_funds -= &amount;
+                self.project_partner_rewards(project_id)
+                    .set(index, &partner_reward);
+            }
+            self.merge_payment(
+                payments,
+                partner_reward.token,
+                partner_reward.token_nonce,
+                amount,
+            );
+        }
+    }
+
+    fn get_project_claim_fee(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        amount: &BigUint,
+    ) -> BigUint {
+        let fee_bps = self.project_claim_fee_bps(project_id).get();
+        if fee_bps == 0 {
+            return BigUint::zero();
+        }
+
+        amount * fee_bps / MAX_FEE_BPS
+    }
+
+    /// Adds the amount to an existing payment of the same token and nonce, if any,
+    /// so each token is only sent once in the final multi-transfer
+    fn merge_payment(
+        &self,
+        payments: &mut ManagedVec<EsdtTokenPayment>,
+        token_id: TokenIdentifier,
+        token_nonce: u64,
+        amount: BigUint,
+    ) {
+        for i in 0..payments.len() {
+            let mut payment = payments.get(i);
+            if payment.token_identifier == token_id && payment.token_nonce == token_nonce {
+                payment.amount += amount;
+                let _ = payments.set(i, &payment);
+
+                return;
+            }
+        }
+
+        payments.push(EsdtTokenPayment::new(token_id, token_nonce, amount));
+    }
+
+    /// Sets the number of weeks from the start during which any week can be claimed
+    #[only_owner]
+    #[endpoint(setRewardsNrFirstGraceWeeks)]
+    fn set_rewards_nr_first_grace_weeks(&self, rewards_nr_first_grace_weeks: Week) {
+        self.rewards_nr_first_grace_weeks()
+            .set(rewards_nr_first_grace_weeks);
+        self.log_admin_action(AdminActionKind::RewardsGraceWeeksChanged(
+            rewards_nr_first_grace_weeks,
+        ));
+    }
+
+    /// Returns the last week and the last epoch in which rewards for the given week can be claimed
+    #[view(getClaimDeadline)]
+    fn get_claim_deadline(&self, week: Week) -> MultiValue2<Week, Epoch> {
+        let rewards_nr_first_grace_weeks = self.rewards_nr_first_grace_weeks().get();
+        let deadline_week = core::cmp::max(
+            week + self.get_claim_expiration_weeks(),
+            rewards_nr_first_grace_weeks,
+        );
+        let deadline_epoch = self.first_week_start_epoch().get()
+            + (deadline_week as Epoch + 1) * self.get_epochs_per_week()
+            - 1;
+
+        (deadline_week, deadline_epoch).into()
+    }
+
+    /// Simulates claimRewards for the given user and weeks, without the signatures,
+    /// through the same computation as the claim, without saving anything.
+    /// Returns the payments the claim would send, i.e. the rewards of all the weeks merged by token,
+    /// including the bonuses and the user's dust, and excluding the fees and the amounts kept as dust.
+    /// Already claimed weeks, expired weeks and weeks without a checkpoint are skipped.
+    /// Maximum of MAX_CLAIM_ARG_PAIRS weeks, same as for claimRewards. Arguments are triples of:
+    /// - week
+    /// - user_delegation_amount
+    /// - user_lkmex_staked_amount
+    #[label("metabonding-external-view")]
+    #[view(getRewardsForWeeks)]
+    fn get_rewards_for_weeks(
+        &self,
+        user: ManagedAddress,
+        weeks: MultiValueEncoded<MultiValue3<Week, BigUint, BigUint>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        require!(
+            weeks.raw_len() / SIMULATION_NR_ARGS_PER_WEEK <= MAX_CLAIM_ARG_PAIRS,
+            "Too many arguments"
+        );
+
+        let current_week = self.get_current_week();
+        let rewards_nr_first_grace_weeks = self.rewards_nr_first_grace_weeks().get();
+        let mut args = ClaimArgsArray::new();
+        for week_args in weeks {
+            let (week, user_delegation_amount, user_lkmex_staked_amount) = week_args.into_tuple();
+            if self.is_week_claimed(&user, week)
+                || !self.is_claim_in_time(week, current_week, rewards_nr_first_grace_weeks)
+            {
+                continue;
+            }
+
+            if let Some(checkpoint) = self.get_checkpoint(week) {
+                args.push(ClaimArgsWrapper {
+                    week,
+                    user_delegation_amount,
+                    user_lkmex_staked_amount,
+                    checkpoint,
+                    partially_claimed: self.partially_claimed_week(&user, week).get(),
+                });
+            }
+        }
+
+        let weekly_rewards = self.compute_claimed_rewards(&user, current_week, &args, true);
+        self.apply_min_payout(&user, weekly_rewards, true)
+    }
+
+    /// Returns the user's lifetime claim statistics.
+    /// The claimed amounts are summed up for each token and nonce.
+    #[label("metabonding-external-view")]
+    #[view(getUserStats)]
+    fn get_user_stats(&self, user: ManagedAddress) -> UserStats<Self::Api> {
+        UserStats {
+            nr_weeks_claimed: self.user_nr_weeks_claimed(&user).get(),
+            first_claim_week: self.user_first_claim_week(&user).get(),
+            last_claim_week: self.user_last_claim_week(&user).get(),
+            total_claimed: self.user_total_claimed(&user).get(),
+        }
+    }
+
+    /// Returns the weeks the user can still claim, i.e. weeks that have a finalized checkpoint,
+    /// are still in the claim window, and were not claimed yet.
+    /// Having a claimable week does not guarantee the user has any rewards for it.
     #[view(getUserClaimableWeeks)]
     fn get_user_claimable_weeks(&self, user_address: ManagedAddress) -> MultiValueEncoded<Week> {
         let last_checkpoint_week = self.get_last_checkpoint_week();
         let current_week = self.get_current_week();
         let rewards_nr_first_grace_weeks = self.rewards_nr_first_grace_weeks().get();
 
+        let expiration_weeks = self.get_claim_expiration_weeks();
+
+        // older weeks are outside the claim window anyway
         let start_week = if current_week <= rewards_nr_first_grace_weeks
-            || PROJECT_EXPIRATION_WEEKS >= last_checkpoint_week
+            || current_week <= expiration_weeks + 1
         {
             1
         } else {
-            last_checkpoint_week - PROJECT_EXPIRATION_WEEKS
+            current_week - expiration_weeks
         };
 
         let mut weeks_list = MultiValueEncoded::new();
         for week in start_week..=last_checkpoint_week {
-            if !self.rewards_claimed(&user_address, week).get()
+            if self.has_checkpoint(week)
+                && !self.is_week_claimed(&user_address, week)
                 && self.is_claim_in_time(week, current_week, rewards_nr_first_grace_weeks)
+                && self.is_checkpoint_finalized(week)
             {
                 weeks_list.push(week);
             }
@@ -163,9 +1704,65 @@ pub trait ClaimModule:
         weeks_list
     }
 
+    #[view(getMinPayoutAmount)]
+    #[storage_mapper("minPayoutAmount")]
+    fn min_payout_amount(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    /// Returns the dust kept for the user, for each token and nonce
+    #[view(getUserDust)]
+    fn get_user_dust(&self, user: ManagedAddress) -> ManagedVec<EsdtTokenPayment> {
+        let mut dust = ManagedVec::new();
+        for ((token_id, token_nonce), amount) in self.user_dust(&user).iter() {
+            dust.push(EsdtTokenPayment::new(token_id, token_nonce, amount));
+        }
+
+        dust
+    }
+
+    #[storage_mapper("userDust")]
+    fn user_dust(&self, user: &ManagedAddress) -> MapMapper<(TokenIdentifier, u64), BigUint>;
+
+    #[storage_mapper("totalDust")]
+    fn total_dust(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[view(getUserProjectClaimStreak)]
+    #[storage_mapper("userProjectClaimStreak")]
+    fn user_project_claim_streak(
+        &self,
+        user: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<ClaimStreak>;
+
+    #[view(getRewardDestination)]
+    #[storage_mapper("rewardDestination")]
+    fn reward_destination(&self, user: &ManagedAddress) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getClaimDelegate)]
+    #[storage_mapper("claimDelegate")]
+    fn claim_delegate(&self, user: &ManagedAddress) -> SingleValueMapper<ManagedAddress>;
+
+    #[storage_mapper("maxProjectsPerClaim")]
+    fn max_projects_per_claim(&self) -> SingleValueMapper<usize>;
+
+    #[view(getClaimCursor)]
+    #[storage_mapper("claimCursor")]
+    fn claim_cursor(&self, user: &ManagedAddress) -> SingleValueMapper<Week>;
+
+    #[storage_mapper("userNrWeeksClaimed")]
+    fn user_nr_weeks_claimed(&self, user: &ManagedAddress) -> SingleValueMapper<usize>;
+
+    #[storage_mapper("userFirstClaimWeek")]
+    fn user_first_claim_week(&self, user: &ManagedAddress) -> SingleValueMapper<Week>;
+
+    #[storage_mapper("userLastClaimWeek")]
+    fn user_last_claim_week(&self, user: &ManagedAddress) -> SingleValueMapper<Week>;
+
+    #[storage_mapper("userTotalClaimed")]
+    fn user_total_claimed(
+        &self,
+        user: &ManagedAddress,
+    ) -> SingleValueMapper<ManagedVec<EsdtTokenPayment>>;
+
     #[storage_mapper("rewardsNrFirstGraceWeeks")]
     fn rewards_nr_first_grace_weeks(&self) -> SingleValueMapper<Week>;
-
-    #[storage_mapper("rewardsClaimed")]
-    fn rewards_claimed(&self, user: &ManagedAddress, week: Week) -> SingleValueMapper<bool>;
 }
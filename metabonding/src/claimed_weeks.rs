@@ -0,0 +1,140 @@
+elrond_wasm::imports!();
+
+use crate::{audit_log::AdminActionKind, project::ProjectId, rewards::Week};
+
+const BITS_PER_BYTE: Week = 8;
+
+#[elrond_wasm::module]
+pub trait ClaimedWeeksModule: crate::audit_log::AuditLogModule {
+    /// Moves the legacy per-week claimed flags of the given users into the claimed weeks bitmap.
+    /// Flags are checked for weeks in range [1, last_week].
+    #[only_owner]
+    #[endpoint(migrateClaimedWeeks)]
+    fn migrate_claimed_weeks(&self, last_week: Week, users: MultiValueEncoded<ManagedAddress>) {
+        for user in users {
+            for week in 1..=last_week {
+                let legacy_mapper = self.rewards_claimed(&user, week);
+                if legacy_mapper.get() {
+                    self.set_week_claimed(&user, week);
+                    legacy_mapper.clear();
+                }
+            }
+        }
+
+        self.log_admin_action(AdminActionKind::ClaimedWeeksMigrated(last_week));
+    }
+
+    /// Returns the weeks for which the user already claimed their rewards
+    #[label("metabonding-external-view")]
+    #[view(getClaimedWeeks)]
+    fn get_claimed_weeks(&self, user: ManagedAddress) -> MultiValueEncoded<Week> {
+        let bitmap = self.claimed_weeks_bitmap(&user).get();
+        let mut claimed_weeks = MultiValueEncoded::new();
+        for byte_index in 0..bitmap.len() {
+            let byte = self.load_bitmap_byte(&bitmap, byte_index);
+            for bit in 0..BITS_PER_BYTE {
+                if byte & (1 << bit) != 0 {
+                    claimed_weeks.push(byte_index * BITS_PER_BYTE + bit);
+                }
+            }
+        }
+
+        claimed_weeks
+    }
+
+    #[view(getRewardsClaimed)]
+    fn get_rewards_claimed(&self, user: ManagedAddress, week: Week) -> bool {
+        self.is_week_claimed(&user, week)
+    }
+
+    fn is_week_claimed(&self, user: &ManagedAddress, week: Week) -> bool {
+        let bitmap = self.claimed_weeks_bitmap(user).get();
+        self.is_week_claimed_in_bitmap(&bitmap, user, week)
+    }
+
+    /// Returns the claim status of the weeks in range [from_week, to_week] as a bitmask,
+    /// week from_week + i being bit (i % 8) of byte (i / 8)
+    #[label("metabonding-external-view")]
+    #[view(getClaimStatuses)]
+    fn get_claim_statuses(
+        &self,
+        user: ManagedAddress,
+        from_week: Week,
+        to_week: Week,
+    ) -> ManagedBuffer {
+        require!(from_week <= to_week, "Invalid week range");
+
+        let bitmap = self.claimed_weeks_bitmap(&user).get();
+        let mut statuses = ManagedBuffer::new();
+        let mut byte = 0u8;
+        for week in from_week..=to_week {
+            let bit = (week - from_week) % BITS_PER_BYTE;
+            if self.is_week_claimed_in_bitmap(&bitmap, &user, week) {
+                byte |= 1 << bit;
+            }
+            if bit == BITS_PER_BYTE - 1 || week == to_week {
+                statuses.append_bytes(&[byte]);
+                byte = 0;
+            }
+        }
+
+        statuses
+    }
+
+    fn is_week_claimed_in_bitmap(
+        &self,
+        bitmap: &ManagedBuffer,
+        user: &ManagedAddress,
+        week: Week,
+    ) -> bool {
+        let byte_index = week / BITS_PER_BYTE;
+        if byte_index < bitmap.len() {
+            let byte = self.load_bitmap_byte(bitmap, byte_index);
+            if byte & (1 << (week % BITS_PER_BYTE)) != 0 {
+                return true;
+            }
+        }
+
+        self.rewards_claimed(user, week).get()
+    }
+
+    fn set_week_claimed(&self, user: &ManagedAddress, week: Week) {
+        let byte_index = week / BITS_PER_BYTE;
+        self.claimed_weeks_bitmap(user).update(|bitmap| {
+            while bitmap.len() <= byte_index {
+                bitmap.append_bytes(&[0u8]);
+            }
+
+            let byte = self.load_bitmap_byte(bitmap, byte_index) | (1 << (week % BITS_PER_BYTE));
+            let _ = bitmap.set_slice(byte_index, &[byte]);
+        });
+    }
+
+    fn load_bitmap_byte(&self, bitmap: &ManagedBuffer, byte_index: usize) -> u8 {
+        let mut byte = [0u8; 1];
+        let _ = bitmap.load_slice(byte_index, &mut byte);
+
+        byte[0]
+    }
+
+    /// Set while only some of the week's projects were claimed through claimRewardsForProjects
+    #[storage_mapper("partiallyClaimedWeek")]
+    fn partially_claimed_week(&self, user: &ManagedAddress, week: Week) -> SingleValueMapper<bool>;
+
+    #[view(isProjectRewardsClaimed)]
+    #[storage_mapper("projectRewardsClaimed")]
+    fn project_rewards_claimed(
+        &self,
+        user: &ManagedAddress,
+        week: Week,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<bool>;
+
+    /// One bit per week, week W being bit (W % 8) of byte (W / 8)
+    #[storage_mapper("claimedWeeksBitmap")]
+    fn claimed_weeks_bitmap(&self, user: &ManagedAddress) -> SingleValueMapper<ManagedBuffer>;
+
+    /// Legacy storage, only read for users that were not yet migrated to the bitmap
+    #[storage_mapper("rewardsClaimed")]
+    fn rewards_claimed(&self, user: &ManagedAddress, week: Week) -> SingleValueMapper<bool>;
+}
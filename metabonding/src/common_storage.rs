@@ -1,15 +1,168 @@
 elrond_wasm::imports!();
 
-use crate::project::Epoch;
+use crate::{
+    project::{Epoch, PROJECT_EXPIRATION_WEEKS},
+    rewards::Week,
+};
 
+/// Default week length, used unless changed through setWeekSchedule
 pub const EPOCHS_IN_WEEK: Epoch = 7;
 pub const MAX_PERCENTAGE: u64 = 100;
+pub const MAX_SPLIT_BPS: u64 = 10_000;
+pub const MAX_FEE_BPS: u64 = 10_000;
+pub const MAX_BONUS_BPS: u64 = 10_000;
 
 #[elrond_wasm::module]
 pub trait CommonStorageModule {
+    fn get_claim_expiration_weeks(&self) -> Week {
+        let mapper = self.claim_expiration_weeks();
+        if mapper.is_empty() {
+            PROJECT_EXPIRATION_WEEKS
+        } else {
+            mapper.get()
+        }
+    }
+
+    fn get_epochs_per_week(&self) -> Epoch {
+        let mapper = self.epochs_per_week();
+        if mapper.is_empty() {
+            EPOCHS_IN_WEEK
+        } else {
+            mapper.get()
+        }
+    }
+
+    /// Sensitive actions may not be called directly while a timelock delay is set.
+    /// They have to be scheduled through scheduleAction instead.
+    fn require_no_timelock(&self) {
+        require!(
+            self.timelock_delay_epochs().get() == 0,
+            "Action must go through the timelock"
+        );
+    }
+
+    /// The token of a bounty can only be changed while its pool is empty.
+    fn set_bounty(
+        &self,
+        token_mapper: SingleValueMapper<EgldOrEsdtTokenIdentifier>,
+        amount_mapper: SingleValueMapper<BigUint>,
+        pool_mapper: SingleValueMapper<BigUint>,
+        token_id: EgldOrEsdtTokenIdentifier,
+        amount: BigUint,
+    ) {
+        require!(token_id.is_valid(), "Invalid token");
+
+        if !token_mapper.is_empty() && token_mapper.get() != token_id {
+            require!(pool_mapper.get() == 0, "Bounty pool not empty");
+        }
+
+        token_mapper.set(&token_id);
+        amount_mapper.set(&amount);
+    }
+
+    fn fund_bounty(
+        &self,
+        token_mapper: SingleValueMapper<EgldOrEsdtTokenIdentifier>,
+        pool_mapper: SingleValueMapper<BigUint>,
+    ) {
+        let (payment_token, payment_amount) = self.call_value().egld_or_single_fungible_esdt();
+        require!(
+            !token_mapper.is_empty() && token_mapper.get() == payment_token,
+            "Invalid payment token"
+        );
+
+        pool_mapper.update(|pool| *pool += payment_amount);
+    }
+
+    /// Pays the bounty to the given address, if there are enough funds left in the pool.
+    fn pay_bounty(
+        &self,
+        to: &ManagedAddress,
+        token_mapper: SingleValueMapper<EgldOrEsdtTokenIdentifier>,
+        amount_mapper: SingleValueMapper<BigUint>,
+        pool_mapper: SingleValueMapper<BigUint>,
+    ) {
+        let bounty_amount = amount_mapper.get();
+        let pool = pool_mapper.get();
+        if bounty_amount == 0 || pool < bounty_amount {
+            return;
+        }
+
+        pool_mapper.set(&(pool - &bounty_amount));
+
+        let bounty_token = token_mapper.get();
+        self.send().direct(to, &bounty_token, 0, &bounty_amount);
+    }
+
+    /// Sends the given amount from the bounty pool back to the owner.
+    fn withdraw_bounty_pool(
+        &self,
+        token_mapper: SingleValueMapper<EgldOrEsdtTokenIdentifier>,
+        pool_mapper: SingleValueMapper<BigUint>,
+        amount: BigUint,
+    ) {
+        let pool = pool_mapper.get();
+        require!(amount > 0 && amount <= pool, "Invalid amount");
+
+        pool_mapper.set(&(pool - &amount));
+
+        let owner = self.blockchain().get_owner_address();
+        self.send().direct(&owner, &token_mapper.get(), 0, &amount);
+    }
+
+    /// Returns the active signers, plus the removed signers that are still within their grace period.
+    fn get_valid_signers(&self) -> ManagedVec<ManagedAddress> {
+        let mut valid_signers = ManagedVec::new();
+        for signer in self.signers().iter() {
+            valid_signers.push(signer);
+        }
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        for (signer, valid_until_epoch) in self.retired_signers().iter() {
+            if current_epoch <= valid_until_epoch {
+                valid_signers.push(signer);
+            }
+        }
+
+        valid_signers
+    }
+
+    /// Single signer used before the signer set was introduced. Moved into the signer set on init.
     #[storage_mapper("signer")]
-    fn signer(&self) -> SingleValueMapper<ManagedAddress>;
+    fn legacy_signer(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getSigners)]
+    #[storage_mapper("signers")]
+    fn signers(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    /// Removed signers, with the last epoch in which their signatures are still accepted
+    #[view(getRetiredSigners)]
+    #[storage_mapper("retiredSigners")]
+    fn retired_signers(&self) -> MapMapper<ManagedAddress, Epoch>;
+
+    /// The number of distinct signers that have to sign each claim. 0 and 1 both require a single signature.
+    #[view(getClaimSignatureThreshold)]
+    #[storage_mapper("claimSignatureThreshold")]
+    fn claim_signature_threshold(&self) -> SingleValueMapper<usize>;
+
+    #[view(getSignerGracePeriodEpochs)]
+    #[storage_mapper("signerGracePeriodEpochs")]
+    fn signer_grace_period_epochs(&self) -> SingleValueMapper<Epoch>;
 
     #[storage_mapper("firstWeekStartEpoch")]
     fn first_week_start_epoch(&self) -> SingleValueMapper<Epoch>;
+
+    #[storage_mapper("epochsPerWeek")]
+    fn epochs_per_week(&self) -> SingleValueMapper<Epoch>;
+
+    #[storage_mapper("claimExpirationWeeks")]
+    fn claim_expiration_weeks(&self) -> SingleValueMapper<Week>;
+
+    #[view(getTimelockDelayEpochs)]
+    #[storage_mapper("timelockDelayEpochs")]
+    fn timelock_delay_epochs(&self) -> SingleValueMapper<Epoch>;
+
+    #[view(getTreasuryAddress)]
+    #[storage_mapper("treasuryAddress")]
+    fn treasury_address(&self) -> SingleValueMapper<ManagedAddress>;
 }
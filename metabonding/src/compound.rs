@@ -0,0 +1,141 @@
+elrond_wasm::imports!();
+
+use crate::{audit_log::AdminActionKind, claim::ClaimArgPair};
+
+pub type EnterFarmResultType<M> = MultiValue2<EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
+
+pub mod farm_proxy {
+    elrond_wasm::imports!();
+
+    use super::EnterFarmResultType;
+
+    #[elrond_wasm::proxy]
+    pub trait FarmProxy {
+        #[payable("*")]
+        #[endpoint(enterFarm)]
+        fn enter_farm(
+            &self,
+            opt_orig_caller: OptionalValue<ManagedAddress>,
+        ) -> EnterFarmResultType<Self::Api>;
+    }
+}
+
+#[elrond_wasm::module]
+pub trait CompoundModule:
+    elrond_wasm_modules::pause::PauseModule
+    + crate::project::ProjectModule
+    + crate::access_control::AccessControlModule
+    + crate::common_storage::CommonStorageModule
+    + crate::math::MathModule
+    + crate::validation::ValidationModule
+    + crate::rewards::RewardsModule
+    + crate::claim::ClaimModule
+    + crate::claimed_weeks::ClaimedWeeksModule
+    + crate::energy_factory::EnergyFactoryModule
+    + crate::events::EventsModule
+    + sc_whitelist_module::SCWhitelistModule
+{
+    /// Whitelists a farm for compounding. Only rewards of the farm's farming token are compounded.
+    #[only_owner]
+    #[endpoint(addCompoundFarm)]
+    fn add_compound_farm(&self, farm_address: ManagedAddress, farming_token: TokenIdentifier) {
+        require!(
+            self.blockchain().is_smart_contract(&farm_address),
+            "Invalid farm address"
+        );
+        require!(farming_token.is_valid_esdt_identifier(), "Invalid token");
+
+        self.compound_farm_farming_token(&farm_address)
+            .set(&farming_token);
+        self.log_admin_action(AdminActionKind::CompoundFarmAdded(
+            farm_address,
+            farming_token,
+        ));
+    }
+
+    #[only_owner]
+    #[endpoint(removeCompoundFarm)]
+    fn remove_compound_farm(&self, farm_address: ManagedAddress) {
+        self.compound_farm_farming_token(&farm_address).clear();
+        self.log_admin_action(AdminActionKind::CompoundFarmRemoved(farm_address));
+    }
+
+    /// Claims rewards, same as claimRewards, then enters the given farm with
+    /// the rewards that are in the farm's farming token.
+    /// The resulting farm position is sent to the caller's reward destination, along with the rest of the rewards,
+    /// and the farm position is entered on behalf of that destination.
+    /// Arguments have the same format as for claimRewards.
+    #[endpoint(claimAndCompound)]
+    fn claim_and_compound(
+        &self,
+        farm_address: ManagedAddress,
+        claim_args: MultiValueEncoded<ClaimArgPair<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        self.require_claim_allowed(&claim_args);
+
+        let farming_token_mapper = self.compound_farm_farming_token(&farm_address);
+        require!(!farming_token_mapper.is_empty(), "Farm not whitelisted");
+
+        let farming_token = farming_token_mapper.get();
+        let caller = self.blockchain().get_caller();
+        let destination = self.get_reward_destination(&caller);
+        let current_week = self.get_current_week();
+        let args = self.collect_claim_args(&caller, current_week, claim_args);
+        let weekly_rewards = self.compute_claimed_rewards(&caller, current_week, &args, false);
+        let weekly_rewards = self.apply_min_payout(&caller, weekly_rewards, false);
+        // the stats count the claimed rewards, before they are compounded
+        self.record_user_claimed_payments(&caller, &weekly_rewards);
+
+        let mut output_payments = ManagedVec::new();
+        for payment in &weekly_rewards {
+            if payment.token_identifier != farming_token || payment.token_nonce != 0 {
+                self.merge_payment(
+                    &mut output_payments,
+                    payment.token_identifier,
+                    payment.token_nonce,
+                    payment.amount,
+                );
+                continue;
+            }
+
+            let (farm_tokens, boosted_rewards) = self
+                .farm_proxy(farm_address.clone())
+                .enter_farm(OptionalValue::Some(destination.clone()))
+                .with_esdt_transfer(payment)
+                .execute_on_dest_context::<EnterFarmResultType<Self::Api>>()
+                .into_tuple();
+
+            // the farm's boosted rewards may be the same token as another project's rewards
+            output_payments.push(farm_tokens);
+            if boosted_rewards.amount > 0 {
+                self.merge_payment(
+                    &mut output_payments,
+                    boosted_rewards.token_identifier,
+                    boosted_rewards.token_nonce,
+                    boosted_rewards.amount,
+                );
+            }
+        }
+
+        if !output_payments.is_empty() {
+            self.send_rewards(&destination, &output_payments);
+        }
+
+        if destination != caller {
+            self.rewards_sent_to_destination_event(&caller, &destination, &output_payments);
+        }
+        self.claim_finished_event(&caller, &destination, &output_payments);
+
+        output_payments
+    }
+
+    #[proxy]
+    fn farm_proxy(&self, sc_address: ManagedAddress) -> farm_proxy::Proxy<Self::Api>;
+
+    #[view(getCompoundFarmFarmingToken)]
+    #[storage_mapper("compoundFarmFarmingToken")]
+    fn compound_farm_farming_token(
+        &self,
+        farm_address: &ManagedAddress,
+    ) -> SingleValueMapper<TokenIdentifier>;
+}
@@ -0,0 +1,209 @@
+elrond_wasm::imports!();
+
+use crate::{audit_log::AdminActionKind, project::ProjectId, rewards::Week};
+
+pub mod energy_factory_proxy {
+    elrond_wasm::imports!();
+    elrond_wasm::derive_imports!();
+
+    #[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem)]
+    pub struct LockOption {
+        pub lock_epochs: u64,
+        pub penalty_start_percentage: u64,
+    }
+
+    #[elrond_wasm::proxy]
+    pub trait EnergyFactoryProxy {
+        #[payable("*")]
+        #[endpoint(lockTokens)]
+        fn lock_tokens(
+            &self,
+            lock_epochs: u64,
+            opt_destination: OptionalValue<ManagedAddress>,
+        ) -> EsdtTokenPayment;
+
+        #[view(getEnergyAmountForUser)]
+        fn get_energy_amount_for_user(&self, user: ManagedAddress) -> BigUint;
+
+        #[view(getBaseAssetTokenId)]
+        fn base_asset_token_id(&self) -> TokenIdentifier;
+
+        #[view(getLockOptions)]
+        fn get_lock_options_view(&self) -> MultiValueEncoded<LockOption>;
+    }
+}
+
+#[elrond_wasm::module]
+pub trait EnergyFactoryModule:
+    elrond_wasm_modules::pause::PauseModule
+    + crate::project::ProjectModule
+    + crate::access_control::AccessControlModule
+    + crate::common_storage::CommonStorageModule
+    + crate::math::MathModule
+    + crate::validation::ValidationModule
+    + crate::events::EventsModule
+    + crate::global_pause::GlobalPauseModule
+    + crate::oracle::OracleModule
+    + crate::rewards::RewardsModule
+{
+    /// The SC must be whitelisted in the energy factory, so it can lock tokens on behalf of the users.
+    #[only_owner]
+    #[endpoint(setEnergyFactoryAddress)]
+    fn set_energy_factory_address(&self, energy_factory_address: ManagedAddress) {
+        require!(
+            self.blockchain().is_smart_contract(&energy_factory_address),
+            "Invalid energy factory address"
+        );
+
+        self.energy_factory_address().set(&energy_factory_address);
+        self.log_admin_action(AdminActionKind::EnergyFactoryAddressChanged(
+            energy_factory_address,
+        ));
+    }
+
+    /// Allows users to register their energy for the current week through registerEnergy.
+    /// The energy factory has to be in the same shard, as it is queried synchronously.
+    #[only_owner]
+    #[endpoint(setEnergyLookupEnabled)]
+    fn set_energy_lookup_enabled(&self, enabled: bool) {
+        require!(
+            !enabled || !self.energy_factory_address().is_empty(),
+            "Energy factory address not set"
+        );
+
+        self.energy_lookup_enabled().set(enabled);
+        self.log_admin_action(AdminActionKind::EnergyLookupEnabledChanged(enabled));
+    }
+
+    /// Records the caller's current energy, read from the energy factory, for the current week.
+    /// Can be called again to update it, until the week's checkpoint is added.
+    /// For the weeks with registered energy, energy-weighted projects distribute their LKMEX share
+    /// by the registered energy of each user over the total registered energy,
+    /// so users don't have to give their energy or LKMEX amounts when claiming.
+    #[endpoint(registerEnergy)]
+    fn register_energy(&self) {
+        self.require_not_globally_paused();
+        require!(
+            self.energy_lookup_enabled().get(),
+            "Energy lookup not enabled"
+        );
+
+        let week = self.get_current_week();
+        require!(
+            week > self.get_last_checkpoint_week(),
+            "Energy already snapshotted for week"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let energy = self.get_user_energy(caller.clone());
+        let user_energy_mapper = self.user_week_energy(&caller, week);
+        let previous_energy = user_energy_mapper.get();
+        user_energy_mapper.set(&energy);
+        self.week_registered_energy(week).update(|total| {
+            *total -= &previous_energy;
+            *total += &energy;
+        });
+    }
+
+    /// Makes the project's claimed rewards be locked through the energy factory for the given number of epochs,
+    /// which must be one of the energy factory's lock options.
+    /// The claimed rewards are kept for the users, who get them locked through claimLockedRewards.
+    /// The project's reward token must be the energy factory's base token, which is fungible,
+    /// so projects paid in MetaESDTs or SFTs can't have their rewards locked. 0 disables locking.
+    /// Can only be changed before the project starts. May be called by a project manager or the project owner.
+    #[endpoint(setProjectRewardsLockEpochs)]
+    fn set_project_rewards_lock_epochs(&self, project_id: ProjectId<Self::Api>, lock_epochs: u64) {
+        let project = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+        require!(
+            lock_epochs == 0 || !self.energy_factory_address().is_empty(),
+            "Energy factory address not set"
+        );
+        require!(
+            self.get_current_week() < project.start_week,
+            "Project already started"
+        );
+
+        if lock_epochs > 0 {
+            let energy_factory_address = self.energy_factory_address().get();
+            let base_token_id: TokenIdentifier = self
+                .energy_factory_proxy(energy_factory_address.clone())
+                .base_asset_token_id()
+                .execute_on_dest_context();
+            require!(
+                project.reward_token == base_token_id
+                    && self.project_reward_token_nonce(&project_id).get() == 0,
+                "Reward token is not the energy factory's base token"
+            );
+
+            let lock_options: MultiValueEncoded<energy_factory_proxy::LockOption> = self
+                .energy_factory_proxy(energy_factory_address)
+                .get_lock_options_view()
+                .execute_on_dest_context();
+            require!(
+                lock_options
+                    .into_iter()
+                    .any(|lock_option| lock_option.lock_epochs == lock_epochs),
+                "Invalid lock epochs"
+            );
+        }
+
+        self.project_rewards_lock_epochs(&project_id)
+            .set(lock_epochs);
+        self.log_admin_action(AdminActionKind::ProjectRewardsLockEpochsChanged(
+            project_id,
+            lock_epochs,
+        ));
+    }
+
+    /// The energy factory sends the locked tokens to the destination, and updates the user's energy.
+    #[allow(clippy::too_many_arguments)]
+    fn lock_rewards_for_user(
+        &self,
+        user: &ManagedAddress,
+        destination: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+        token_id: TokenIdentifier,
+        token_nonce: u64,
+        amount: BigUint,
+        lock_epochs: u64,
+    ) -> EsdtTokenPayment {
+        let energy_factory_address = self.energy_factory_address().get();
+        let locked_tokens: EsdtTokenPayment = self
+            .energy_factory_proxy(energy_factory_address)
+            .lock_tokens(lock_epochs, OptionalValue::Some(destination.clone()))
+            .with_esdt_transfer(EsdtTokenPayment::new(token_id, token_nonce, amount))
+            .execute_on_dest_context();
+
+        self.rewards_locked_event(user, project_id, &locked_tokens);
+
+        locked_tokens
+    }
+
+    #[view(getUserEnergy)]
+    fn get_user_energy(&self, user: ManagedAddress) -> BigUint {
+        require!(
+            !self.energy_factory_address().is_empty(),
+            "Energy factory address not set"
+        );
+
+        let energy_factory_address = self.energy_factory_address().get();
+        self.energy_factory_proxy(energy_factory_address)
+            .get_energy_amount_for_user(user)
+            .execute_on_dest_context()
+    }
+
+    #[proxy]
+    fn energy_factory_proxy(
+        &self,
+        sc_address: ManagedAddress,
+    ) -> energy_factory_proxy::Proxy<Self::Api>;
+
+    #[view(getEnergyFactoryAddress)]
+    #[storage_mapper("energyFactoryAddress")]
+    fn energy_factory_address(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(isEnergyLookupEnabled)]
+    #[storage_mapper("energyLookupEnabled")]
+    fn energy_lookup_enabled(&self) -> SingleValueMapper<bool>;
+}
@@ -0,0 +1,212 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::{
+    project::{Project, ProjectId},
+    rewards::Week,
+    timelock::ScheduledAction,
+};
+
+#[derive(TypeAbi, TopEncode)]
+pub struct CheckpointAddedEventData<M: ManagedTypeApi> {
+    pub total_delegation_supply: BigUint<M>,
+    pub total_lkmex_staked: BigUint<M>,
+}
+
+#[derive(TypeAbi, TopEncode)]
+pub struct RewardsDepositedEventData<M: ManagedTypeApi> {
+    pub token_nonce: u64,
+    pub amount: BigUint<M>,
+    pub total_deposited: BigUint<M>,
+}
+
+#[derive(TypeAbi, TopEncode)]
+pub struct ProjectExtendedEventData<M: ManagedTypeApi> {
+    pub old_end_week: Week,
+    pub new_end_week: Week,
+    pub extra_supply: BigUint<M>,
+}
+
+#[derive(TypeAbi, TopEncode)]
+pub struct CheckpointUpdatedEventData<M: ManagedTypeApi> {
+    pub old_total_delegation_supply: BigUint<M>,
+    pub old_total_lkmex_staked: BigUint<M>,
+    pub new_total_delegation_supply: BigUint<M>,
+    pub new_total_lkmex_staked: BigUint<M>,
+}
+
+#[elrond_wasm::module]
+pub trait EventsModule {
+    #[event("claim")]
+    fn claim_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] week: Week,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        #[indexed] token_id: &TokenIdentifier,
+        amount: &BigUint,
+    );
+
+    /// The rewards kept for the user instead of being paid, i.e. those of paused projects,
+    /// including the ones paused by the circuit breaker, and the locked rewards
+    #[event("rewardsParked")]
+    fn rewards_parked_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] week: Week,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        #[indexed] token_id: &TokenIdentifier,
+        amount: &BigUint,
+    );
+
+    #[event("claimFinished")]
+    fn claim_finished_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] receiver: &ManagedAddress,
+        payments: &ManagedVec<EsdtTokenPayment>,
+    );
+
+    #[event("rewardsSentToDestination")]
+    fn rewards_sent_to_destination_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] destination: &ManagedAddress,
+        payments: &ManagedVec<EsdtTokenPayment>,
+    );
+
+    #[event("checkpointAdded")]
+    fn checkpoint_added_event(
+        &self,
+        #[indexed] week: Week,
+        data: &CheckpointAddedEventData<Self::Api>,
+    );
+
+    #[event("checkpointUpdated")]
+    fn checkpoint_updated_event(
+        &self,
+        #[indexed] week: Week,
+        data: &CheckpointUpdatedEventData<Self::Api>,
+    );
+
+    #[event("rewardsDeposited")]
+    fn rewards_deposited_event(
+        &self,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        #[indexed] depositor: &ManagedAddress,
+        data: &RewardsDepositedEventData<Self::Api>,
+    );
+
+    #[event("projectAdded")]
+    fn project_added_event(
+        &self,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        #[indexed] project_owner: &ManagedAddress,
+        project: &Project<Self::Api>,
+    );
+
+    #[event("projectExtended")]
+    fn project_extended_event(
+        &self,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        data: &ProjectExtendedEventData<Self::Api>,
+    );
+
+    #[event("projectClaimsPaused")]
+    fn project_claims_paused_event(
+        &self,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        #[indexed] caller: &ManagedAddress,
+    );
+
+    #[event("projectClaimsResumed")]
+    fn project_claims_resumed_event(
+        &self,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        #[indexed] caller: &ManagedAddress,
+    );
+
+    #[event("projectOwnershipTransferred")]
+    fn project_ownership_transferred_event(
+        &self,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        #[indexed] old_owner: &ManagedAddress,
+        #[indexed] new_owner: &ManagedAddress,
+    );
+
+    #[event("projectRemoved")]
+    fn project_removed_event(
+        &self,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        #[indexed] project_owner: &ManagedAddress,
+        refunded_amount: &BigUint,
+    );
+
+    #[event("projectCancelled")]
+    fn project_cancelled_event(
+        &self,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        #[indexed] cancelled_from_week: Week,
+        refunded_amount: &BigUint,
+    );
+
+    #[event("sweepExpiredRewards")]
+    fn sweep_event(
+        &self,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        #[indexed] treasury_address: &ManagedAddress,
+        #[indexed] last_swept_week: Week,
+        amount: &BigUint,
+    );
+
+    #[event("claimCircuitBreaker")]
+    fn claim_circuit_breaker_event(
+        &self,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] leftover_funds: &BigUint,
+        claimed_amount: &BigUint,
+    );
+
+    #[event("actionScheduled")]
+    fn action_scheduled_event(
+        &self,
+        #[indexed] action_id: u64,
+        scheduled_action: &ScheduledAction<Self::Api>,
+    );
+
+    #[event("actionExecuted")]
+    fn action_executed_event(&self, #[indexed] action_id: u64);
+
+    #[event("actionCancelled")]
+    fn action_cancelled_event(&self, #[indexed] action_id: u64);
+
+    #[event("rewardsLocked")]
+    fn rewards_locked_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] project_id: &ProjectId<Self::Api>,
+        locked_tokens: &EsdtTokenPayment,
+    );
+
+    #[event("ownershipTransferred")]
+    fn ownership_transferred_event(
+        &self,
+        #[indexed] old_owner: &ManagedAddress,
+        #[indexed] new_owner: &ManagedAddress,
+    );
+
+    #[event("globalPause")]
+    fn global_pause_event(&self, #[indexed] caller: &ManagedAddress);
+
+    #[event("globalUnpause")]
+    fn global_unpause_event(&self, #[indexed] caller: &ManagedAddress);
+
+    /// `reason` is the ESDT system SC's error message, or empty if the token was rejected
+    #[event("rewardTokenWhitelistFailed")]
+    fn reward_token_whitelist_failed_event(
+        &self,
+        #[indexed] token_id: &TokenIdentifier,
+        reason: &ManagedBuffer,
+    );
+}
@@ -0,0 +1,35 @@
+elrond_wasm::imports!();
+
+use crate::{admin::Role, audit_log::AdminActionKind};
+
+/// Emergency switch for the whole SC. Unlike the regular pause, which only stops the claims,
+/// it also stops the deposits, the checkpoints, the project proposals and the week settings.
+/// Views keep working while paused.
+#[elrond_wasm::module]
+pub trait GlobalPauseModule: crate::admin::AdminModule + crate::events::EventsModule {
+    #[endpoint(pauseAll)]
+    fn pause_all(&self) {
+        self.require_caller_role(Role::Pauser);
+
+        self.globally_paused().set(true);
+        self.global_pause_event(&self.blockchain().get_caller());
+        self.log_admin_action(AdminActionKind::GloballyPaused);
+    }
+
+    #[endpoint(unpauseAll)]
+    fn unpause_all(&self) {
+        self.require_caller_role(Role::Pauser);
+
+        self.globally_paused().clear();
+        self.global_unpause_event(&self.blockchain().get_caller());
+        self.log_admin_action(AdminActionKind::GloballyUnpaused);
+    }
+
+    fn require_not_globally_paused(&self) {
+        require!(!self.globally_paused().get(), "Contract is globally paused");
+    }
+
+    #[view(isGloballyPaused)]
+    #[storage_mapper("globallyPaused")]
+    fn globally_paused(&self) -> SingleValueMapper<bool>;
+}
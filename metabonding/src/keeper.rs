@@ -0,0 +1,152 @@
+elrond_wasm::imports!();
+
+use crate::{
+    admin::Role,
+    audit_log::AdminActionKind,
+    oracle::OracleSignature,
+    rewards::Week,
+    signed_message::{CHECKPOINT_TAG, KEEPER_CHECKPOINT_TAG},
+    validation::Signature,
+};
+
+#[elrond_wasm::module]
+pub trait KeeperModule:
+    elrond_wasm_modules::pause::PauseModule
+    + crate::project::ProjectModule
+    + crate::access_control::AccessControlModule
+    + crate::common_storage::CommonStorageModule
+    + crate::math::MathModule
+    + crate::validation::ValidationModule
+    + crate::events::EventsModule
+    + crate::oracle::OracleModule
+    + crate::rewards::RewardsModule
+{
+    #[only_owner]
+    #[endpoint(addKeepers)]
+    fn add_keepers(&self, keepers: MultiValueEncoded<ManagedAddress>) {
+        let keepers = keepers.to_vec();
+        let mut mapper = self.keepers();
+        for keeper in keepers.iter() {
+            let _ = mapper.insert(keeper.clone_value());
+        }
+
+        self.log_admin_action(AdminActionKind::KeepersAdded(keepers));
+    }
+
+    #[only_owner]
+    #[endpoint(removeKeepers)]
+    fn remove_keepers(&self, keepers: MultiValueEncoded<ManagedAddress>) {
+        let keepers = keepers.to_vec();
+        let mut mapper = self.keepers();
+        for keeper in keepers.iter() {
+            let _ = mapper.swap_remove(&keeper);
+        }
+
+        self.log_admin_action(AdminActionKind::KeepersRemoved(keepers));
+    }
+
+    /// Sets the reward paid to a keeper for each submitted checkpoint.
+    /// The token can only be changed while the bounty pool is empty.
+    #[endpoint(setKeeperBounty)]
+    fn set_keeper_bounty(&self, token_id: EgldOrEsdtTokenIdentifier, amount: BigUint) {
+        self.require_caller_role(Role::Treasurer);
+
+        self.set_bounty(
+            self.keeper_bounty_token(),
+            self.keeper_bounty_amount(),
+            self.keeper_bounty_pool(),
+            token_id.clone(),
+            amount.clone(),
+        );
+        self.log_admin_action(AdminActionKind::KeeperBountyChanged(token_id, amount));
+    }
+
+    #[payable("*")]
+    #[endpoint(fundKeeperBounty)]
+    fn fund_keeper_bounty(&self) {
+        self.require_caller_role(Role::Treasurer);
+
+        self.fund_bounty(self.keeper_bounty_token(), self.keeper_bounty_pool());
+    }
+
+    /// Sends the given amount from the keeper bounty pool back to the owner.
+    #[only_owner]
+    #[endpoint(withdrawKeeperBounty)]
+    fn withdraw_keeper_bounty(&self, amount: BigUint) {
+        self.withdraw_bounty_pool(
+            self.keeper_bounty_token(),
+            self.keeper_bounty_pool(),
+            amount.clone(),
+        );
+        self.log_admin_action(AdminActionKind::KeeperBountyWithdrawn(amount));
+    }
+
+    /// Adds a checkpoint on behalf of the signer, who signs the same data as the oracles,
+    /// under its own tag, so the signer's checkpoint signatures can't be mixed up with the oracles' or the claims'.
+    /// If an oracle quorum is set, the oracle signatures are required as well.
+    /// The keeper receives the bounty if there are enough funds left in the pool.
+    #[endpoint(keeperAddRewardsCheckpoint)]
+    fn keeper_add_rewards_checkpoint(
+        &self,
+        week: Week,
+        total_delegation_supply: BigUint,
+        total_lkmex_staked: BigUint,
+        signature: Signature<Self::Api>,
+        oracle_signatures: MultiValueEncoded<OracleSignature<Self::Api>>,
+    ) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.keepers().contains(&caller),
+            "Only keepers may submit checkpoints"
+        );
+
+        let message = self.build_checkpoint_message(
+            KEEPER_CHECKPOINT_TAG,
+            week,
+            &total_delegation_supply,
+            &total_lkmex_staked,
+        );
+        self.require_signed_by_signer(&message, &signature);
+
+        if self.oracle_quorum().get() > 0 {
+            let oracle_message = self.build_checkpoint_message(
+                CHECKPOINT_TAG,
+                week,
+                &total_delegation_supply,
+                &total_lkmex_staked,
+            );
+            self.verify_oracle_signatures(&oracle_message, oracle_signatures);
+        }
+
+        let current_week = self.get_current_week();
+        self.add_checkpoint(
+            week,
+            total_delegation_supply,
+            total_lkmex_staked,
+            current_week,
+        );
+
+        self.pay_bounty(
+            &caller,
+            self.keeper_bounty_token(),
+            self.keeper_bounty_amount(),
+            self.keeper_bounty_pool(),
+        );
+    }
+
+    #[view(getKeepers)]
+    #[storage_mapper("keepers")]
+    fn keepers(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[view(getKeeperBountyToken)]
+    #[storage_mapper("keeperBountyToken")]
+    fn keeper_bounty_token(&self) -> SingleValueMapper<EgldOrEsdtTokenIdentifier>;
+
+    #[view(getKeeperBountyAmount)]
+    #[storage_mapper("keeperBountyAmount")]
+    fn keeper_bounty_amount(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getKeeperBountyPool)]
+    #[storage_mapper("keeperBountyPool")]
+    fn keeper_bounty_pool(&self) -> SingleValueMapper<BigUint>;
+}
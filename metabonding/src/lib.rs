@@ -5,11 +5,29 @@ use rewards::Week;
 elrond_wasm::imports!();
 
 pub mod access_control;
+pub mod admin;
+pub mod audit_log;
+pub mod auto_checkpoint;
 pub mod claim;
+pub mod claimed_weeks;
 pub mod common_storage;
+pub mod compound;
+pub mod energy_factory;
+pub mod events;
+pub mod global_pause;
+pub mod keeper;
 pub mod math;
+pub mod oracle;
+pub mod ownership;
 pub mod project;
+pub mod project_proposal;
+pub mod reward_token_whitelist;
 pub mod rewards;
+pub mod signed_message;
+pub mod stake_verification;
+pub mod status;
+pub mod sweep;
+pub mod timelock;
 pub mod validation;
 
 /// Source code for the pause module:
@@ -18,16 +36,34 @@ pub mod validation;
 pub trait Metabonding:
     elrond_wasm_modules::pause::PauseModule
     + project::ProjectModule
+    + project_proposal::ProjectProposalModule
+    + reward_token_whitelist::RewardTokenWhitelistModule
     + rewards::RewardsModule
+    + auto_checkpoint::AutoCheckpointModule
+    + keeper::KeeperModule
     + claim::ClaimModule
+    + stake_verification::StakeVerificationModule
+    + claimed_weeks::ClaimedWeeksModule
+    + compound::CompoundModule
+    + energy_factory::EnergyFactoryModule
+    + sweep::SweepModule
+    + status::StatusModule
+    + timelock::TimelockModule
+    + events::EventsModule
+    + global_pause::GlobalPauseModule
     + access_control::AccessControlModule
+    + admin::AdminModule
+    + audit_log::AuditLogModule
     + common_storage::CommonStorageModule
     + math::MathModule
+    + oracle::OracleModule
+    + ownership::OwnershipModule
     + validation::ValidationModule
     + sc_whitelist_module::SCWhitelistModule
 {
     /// Arguments:
-    /// - signer - public key that will be used for checking the claim signatures
+    /// - signer - public key that will be used for checking the claim signatures.
+    ///     Added to the signer set, along with the signer used before the set was introduced, if any
     /// - opt_rewards_nr_first_grace_weeks - Optional argument that will make it so
     ///     the first X weeks can be claimed at any time (i.e. they will never expire)
     /// - opt_first_week_start_epoch - The epoch which signals the start of week 0.
@@ -40,7 +76,16 @@ pub trait Metabonding:
         opt_rewards_nr_first_grace_weeks: OptionalValue<Week>,
         opt_first_week_start_epoch: OptionalValue<u64>,
     ) {
-        self.signer().set(&signer);
+        let legacy_signer_mapper = self.legacy_signer();
+        if !legacy_signer_mapper.is_empty() {
+            let _ = self.signers().insert(legacy_signer_mapper.take());
+        }
+        let _ = self.signers().insert(signer);
+        for project_id in self.projects().keys() {
+            if self.legacy_rewards_deposited(&project_id).take() {
+                let _ = self.funded_projects().insert(project_id);
+            }
+        }
         self.set_paused(true);
 
         let rewards_nr_first_grace_weeks = match opt_rewards_nr_first_grace_weeks {
@@ -58,9 +103,12 @@ pub trait Metabonding:
             .set_if_empty(first_week_start_epoch);
     }
 
+    /// Replaces all the signers with the given one. The replaced signers are kept valid during the grace period.
     #[only_owner]
     #[endpoint(changeSigner)]
     fn change_signer(&self, new_signer: ManagedAddress) {
-        self.signer().set(&new_signer);
+        self.require_no_timelock();
+
+        self.apply_change_signer(new_signer);
     }
 }
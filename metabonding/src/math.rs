@@ -12,6 +12,20 @@ pub trait MathModule {
         &(amount * part) / total
     }
 
+    /// The part of the ratio lost to the integer division, as a numerator over `total`
+    fn calculate_ratio_remainder(
+        &self,
+        amount: &BigUint,
+        part: &BigUint,
+        total: &BigUint,
+    ) -> BigUint {
+        if total == &0 {
+            return BigUint::zero();
+        }
+
+        &(amount * part) % total
+    }
+
     #[inline]
     fn is_in_range(&self, value: Week, min: Week, max: Week) -> bool {
         (min..=max).contains(&value)
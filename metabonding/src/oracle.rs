@@ -0,0 +1,157 @@
+elrond_wasm::imports!();
+
+use crate::{
+    audit_log::AdminActionKind,
+    rewards::Week,
+    signed_message::{SignedMessage, CHECKPOINT_UPDATE_TAG, SIGNED_MESSAGE_MAX_LEN},
+    validation::Signature,
+};
+
+pub type OracleSignature<M> = MultiValue2<ManagedAddress<M>, Signature<M>>;
+
+#[elrond_wasm::module]
+pub trait OracleModule:
+    crate::access_control::AccessControlModule + crate::common_storage::CommonStorageModule
+{
+    #[only_owner]
+    #[endpoint(addOracles)]
+    fn add_oracles(&self, oracles: MultiValueEncoded<ManagedAddress>) {
+        self.require_no_timelock();
+
+        self.apply_add_oracles(oracles.to_vec());
+    }
+
+    #[only_owner]
+    #[endpoint(removeOracles)]
+    fn remove_oracles(&self, oracles: MultiValueEncoded<ManagedAddress>) {
+        self.require_no_timelock();
+
+        self.apply_remove_oracles(oracles.to_vec());
+    }
+
+    /// Sets the number of oracle signatures required for checkpoints.
+    /// A quorum of 0 disables the oracle check, and checkpoints may only be added by a checkpoint submitter or signer.
+    #[only_owner]
+    #[endpoint(setOracleQuorum)]
+    fn set_oracle_quorum(&self, quorum: usize) {
+        self.require_no_timelock();
+
+        self.apply_oracle_quorum(quorum);
+    }
+
+    fn apply_add_oracles(&self, oracles: ManagedVec<ManagedAddress>) {
+        let mut mapper = self.oracles();
+        for oracle in oracles.iter() {
+            let _ = mapper.insert(oracle.clone_value());
+        }
+
+        self.log_admin_action(AdminActionKind::OraclesAdded(oracles));
+    }
+
+    fn apply_remove_oracles(&self, oracles: ManagedVec<ManagedAddress>) {
+        let mut mapper = self.oracles();
+        for oracle in oracles.iter() {
+            let _ = mapper.swap_remove(&oracle);
+        }
+
+        let quorum = self.oracle_quorum().get();
+        require!(quorum <= mapper.len(), "Not enough oracles left for quorum");
+        self.log_admin_action(AdminActionKind::OraclesRemoved(oracles));
+    }
+
+    fn apply_oracle_quorum(&self, quorum: usize) {
+        require!(
+            quorum <= self.oracles().len(),
+            "Quorum higher than number of oracles"
+        );
+
+        self.oracle_quorum().set(quorum);
+        self.log_admin_action(AdminActionKind::OracleQuorumChanged(quorum));
+    }
+
+    /// If an oracle quorum is set, anyone may submit the checkpoint,
+    /// as long as it is signed by at least quorum distinct oracles.
+    /// Otherwise, only a checkpoint submitter or a signer may submit checkpoints.
+    fn require_checkpoint_authorized(
+        &self,
+        message: &SignedMessage<Self::Api>,
+        oracle_signatures: MultiValueEncoded<OracleSignature<Self::Api>>,
+    ) {
+        if self.oracle_quorum().get() == 0 {
+            self.require_caller_checkpoint_submitter_or_signer();
+            return;
+        }
+
+        self.verify_oracle_signatures(message, oracle_signatures);
+    }
+
+    fn verify_oracle_signatures(
+        &self,
+        message: &SignedMessage<Self::Api>,
+        oracle_signatures: MultiValueEncoded<OracleSignature<Self::Api>>,
+    ) {
+        let quorum = self.oracle_quorum().get();
+        let oracles_mapper = self.oracles();
+        let mut signed_by = ManagedVec::<Self::Api, ManagedAddress>::new();
+        for oracle_signature in oracle_signatures {
+            let (oracle, signature) = oracle_signature.into_tuple();
+            require!(oracles_mapper.contains(&oracle), "Unknown oracle");
+            require!(!signed_by.contains(&oracle), "Duplicate oracle signature");
+
+            let valid_signature = self
+                .crypto()
+                .verify_ed25519_legacy_managed::<SIGNED_MESSAGE_MAX_LEN>(
+                    oracle.as_managed_byte_array(),
+                    message.as_buffer(),
+                    &signature,
+                );
+            require!(valid_signature, "Invalid oracle signature");
+
+            signed_by.push(oracle);
+        }
+
+        require!(signed_by.len() >= quorum, "Not enough oracle signatures");
+    }
+
+    /// A week's checkpoint can only be added once, as later weeks may never be added before it,
+    /// so the week is used as the nonce.
+    /// The tag tells apart the oracles' and the keepers' signed checkpoints, and the square root sums.
+    fn build_checkpoint_message(
+        &self,
+        tag: &[u8],
+        week: Week,
+        total_delegation_supply: &BigUint,
+        total_lkmex_staked: &BigUint,
+    ) -> SignedMessage<Self::Api> {
+        SignedMessage::new(tag, &self.blockchain().get_sc_address(), week as u64)
+            .with_field(total_delegation_supply)
+            .with_field(total_lkmex_staked)
+    }
+
+    /// A week's checkpoint may be corrected several times, so its number of past updates is used as the nonce,
+    /// and the message can't be replayed to revert a later correction.
+    fn build_checkpoint_update_message(
+        &self,
+        week: Week,
+        update_nonce: u64,
+        total_delegation_supply: &BigUint,
+        total_lkmex_staked: &BigUint,
+    ) -> SignedMessage<Self::Api> {
+        SignedMessage::new(
+            CHECKPOINT_UPDATE_TAG,
+            &self.blockchain().get_sc_address(),
+            update_nonce,
+        )
+        .with_field(&week)
+        .with_field(total_delegation_supply)
+        .with_field(total_lkmex_staked)
+    }
+
+    #[view(getOracles)]
+    #[storage_mapper("oracles")]
+    fn oracles(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[view(getOracleQuorum)]
+    #[storage_mapper("oracleQuorum")]
+    fn oracle_quorum(&self) -> SingleValueMapper<usize>;
+}
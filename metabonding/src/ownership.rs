@@ -0,0 +1,89 @@
+elrond_wasm::imports!();
+
+use crate::audit_log::AdminActionKind;
+
+/// Two-step transfer of the SC's ownership, so it can't be lost to a mistyped address.
+/// The SC owner is the protocol-level owner, which only the current owner may change,
+/// so the ownership is held by the SC itself until the new owner claims it.
+/// While the SC holds its own ownership, the owner-only endpoints can't be called by anyone,
+/// so the ownership must only be handed over after proposing the new owner.
+#[elrond_wasm::module]
+pub trait OwnershipModule: crate::audit_log::AuditLogModule + crate::events::EventsModule {
+    /// Starts the transfer of the SC's ownership. The owner then hands the ownership over to the SC,
+    /// by sending a ChangeOwnerAddress built-in function call to the SC's own address, with the SC's
+    /// address as argument, after which the new owner calls claimOwnership.
+    #[only_owner]
+    #[endpoint(proposeNewOwner)]
+    fn propose_new_owner(&self, new_owner: ManagedAddress) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            new_owner != caller && new_owner != self.blockchain().get_sc_address(),
+            "Invalid new owner"
+        );
+
+        self.pending_owner().set(&new_owner);
+        self.ownership_proposer().set(&caller);
+        self.log_admin_action(AdminActionKind::NewOwnerProposed(new_owner));
+    }
+
+    /// Completes the transfer, once the SC holds its own ownership. Only the proposed owner may claim it.
+    #[endpoint(claimOwnership)]
+    fn claim_ownership(&self) {
+        let pending_owner_mapper = self.pending_owner();
+        let caller = self.blockchain().get_caller();
+        require!(
+            !pending_owner_mapper.is_empty() && caller == pending_owner_mapper.get(),
+            "Only the pending owner may claim ownership"
+        );
+        self.require_ownership_handed_over();
+
+        pending_owner_mapper.clear();
+        let old_owner = self.ownership_proposer().take();
+        self.change_sc_owner(&caller);
+        self.ownership_transferred_event(&old_owner, &caller);
+        self.log_admin_action(AdminActionKind::OwnershipTransferred(old_owner));
+    }
+
+    /// Cancels the pending transfer. If the ownership was already handed over to the SC,
+    /// it is given back to the owner who proposed the transfer, who is the only one allowed to cancel.
+    #[endpoint(cancelOwnershipTransfer)]
+    fn cancel_ownership_transfer(&self) {
+        let proposer_mapper = self.ownership_proposer();
+        let caller = self.blockchain().get_caller();
+        require!(
+            !proposer_mapper.is_empty() && caller == proposer_mapper.get(),
+            "Only the proposer may cancel the transfer"
+        );
+
+        proposer_mapper.clear();
+        self.pending_owner().clear();
+
+        let sc_address = self.blockchain().get_sc_address();
+        if self.blockchain().get_owner_address() == sc_address {
+            self.change_sc_owner(&caller);
+        }
+
+        self.log_admin_action(AdminActionKind::OwnershipTransferCancelled);
+    }
+
+    fn require_ownership_handed_over(&self) {
+        require!(
+            self.blockchain().get_owner_address() == self.blockchain().get_sc_address(),
+            "Ownership not handed over to the SC"
+        );
+    }
+
+    fn change_sc_owner(&self, new_owner: &ManagedAddress) {
+        let sc_address = self.blockchain().get_sc_address();
+        self.send()
+            .change_owner_address(sc_address, new_owner)
+            .execute_on_dest_context::<()>();
+    }
+
+    #[view(getPendingOwner)]
+    #[storage_mapper("pendingOwner")]
+    fn pending_owner(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[storage_mapper("ownershipProposer")]
+    fn ownership_proposer(&self) -> SingleValueMapper<ManagedAddress>;
+}
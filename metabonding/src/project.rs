@@ -2,21 +2,63 @@ elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 
 use crate::{
-    common_storage::{EPOCHS_IN_WEEK, MAX_PERCENTAGE},
+    admin::Role,
+    audit_log::AdminActionKind,
+    common_storage::{MAX_BONUS_BPS, MAX_FEE_BPS, MAX_PERCENTAGE, MAX_SPLIT_BPS},
+    project_proposal::ProjectProposal,
     rewards::Week,
 };
 use core::convert::TryInto;
 
 pub const PROJECT_EXPIRATION_WEEKS: Week = 4;
 const MAX_PROJECT_ID_LEN: usize = 10;
+const MAX_PROJECT_NAME_LEN: usize = 50;
+const WEBSITE_HASH_LEN: usize = 32;
+const MAX_PROJECT_CATEGORY_LEN: usize = 20;
+pub const EMISSION_CURVE_WEIGHTS_SUM: u32 = 10_000;
 const MIN_GAS_FOR_CLEAR: u64 = 5_000_000;
+pub const MAX_PROJECTS_PAGE_SIZE: usize = 50;
+pub const MAX_PARTNER_REWARDS: usize = 3;
 static INVALID_PROJECT_ID_ERR_MSG: &[u8] = b"Invalid project ID";
+/// Projects rewarding EGLD use this as their reward token, so EGLD rewards are handled like any ESDT.
+/// It is converted back to EGLD whenever rewards are sent.
+pub static EGLD_REWARD_TOKEN_ID: &[u8] = b"EGLD-000000";
 
 pub type ProjectId<M> = ManagedBuffer<M>;
 pub type ProjectAsMultiResult<M> =
     MultiValue5<TokenIdentifier<M>, BigUint<M>, BigUint<M>, Week, Week>;
+pub type ProjectDetailsAsMultiResult<M> = MultiValue7<
+    ManagedBuffer<M>,
+    ManagedByteArray<M, WEBSITE_HASH_LEN>,
+    TokenIdentifier<M>,
+    BigUint<M>,
+    BigUint<M>,
+    Week,
+    Week,
+>;
+pub type ProjectStatusAsMultiResult<M> =
+    MultiValue6<ProjectId<M>, TokenIdentifier<M>, Week, Week, bool, bool>;
 pub type Epoch = u64;
 
+pub const LKMEX_FACTOR_PRECISION: u64 = 10_000;
+
+/// In quadratic mode, each user's weight is the square root of their amount,
+/// for the weeks whose checkpoint includes the sums of square roots.
+/// In combined mode, the whole weekly supply is distributed by a single weight,
+/// which is the delegation amount plus the LKMEX amount times the project's LKMEX factor.
+#[derive(TypeAbi, TopEncode, TopDecode, PartialEq, Eq, Clone, Copy)]
+pub enum DistributionMode {
+    Linear,
+    Quadratic,
+    Combined,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode)]
+pub struct ProjectMetadata<M: ManagedTypeApi> {
+    pub name: ManagedBuffer<M>,
+    pub website_hash: ManagedByteArray<M, WEBSITE_HASH_LEN>,
+}
+
 #[derive(TypeAbi, TopEncode, TopDecode)]
 pub struct Project<M: ManagedTypeApi> {
     pub reward_token: TokenIdentifier<M>,
@@ -26,10 +68,42 @@ pub struct Project<M: ManagedTypeApi> {
     pub end_week: Week,
 }
 
+/// A token co-distributed with the project's own reward token, e.g. by a partner,
+/// with its own delegation and LKMEX supplies, like the project's.
+/// Its weekly rewards are computed from its own supplies, like the project's, once its total supply is deposited.
+#[derive(TypeAbi, TopEncode, TopDecode)]
+pub struct PartnerReward<M: ManagedTypeApi> {
+    pub token: TokenIdentifier<M>,
+    pub token_nonce: u64,
+    pub delegation_supply: BigUint<M>,
+    pub lkmex_supply: BigUint<M>,
+    pub deposited_amount: BigUint<M>,
+    pub leftover_funds: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> PartnerReward<M> {
+    #[inline]
+    pub fn get_total_supply(&self) -> BigUint<M> {
+        &self.delegation_supply + &self.lkmex_supply
+    }
+}
+
 impl<M: ManagedTypeApi> Project<M> {
     #[inline]
-    pub fn is_expired(&self, current_week: Week) -> bool {
-        current_week > self.end_week + PROJECT_EXPIRATION_WEEKS
+    pub fn is_expired(&self, current_week: Week, expiration_weeks: Week) -> bool {
+        current_week > self.end_week + expiration_weeks
+    }
+
+    pub fn get_weekly_reward_supply(&self) -> BigUint<M> {
+        let project_duration_weeks = self.get_duration_in_weeks() as u32;
+
+        &self.delegation_reward_supply / project_duration_weeks
+            + &self.lkmex_reward_supply / project_duration_weeks
+    }
+
+    /// The weekly supplies are rounded down, so this can be slightly less than the total reward supply
+    pub fn get_distributable_supply(&self) -> BigUint<M> {
+        self.get_weekly_reward_supply() * self.get_duration_in_weeks() as u32
     }
 
     #[inline]
@@ -50,17 +124,25 @@ impl<M: ManagedTypeApi> Project<M> {
 }
 
 #[elrond_wasm::module]
-pub trait ProjectModule: crate::common_storage::CommonStorageModule {
-    /// Adds a new project. Arguments:
-    /// - project_id: a unique ID of maximum 10 bytes
+pub trait ProjectModule:
+    crate::admin::AdminModule
+    + crate::audit_log::AuditLogModule
+    + crate::common_storage::CommonStorageModule
+    + crate::events::EventsModule
+    + crate::math::MathModule
+    + crate::reward_token_whitelist::RewardTokenWhitelistModule
+{
+    /// Adds a new project, and returns its ID. Arguments:
+    /// - project_id: a unique ID of maximum 10 bytes. If empty, the ID is generated by the SC.
     /// - project_owner - the owner of the project. They will receive any unclaimed funds for the projects.
     /// - reward_token - the token ID of the token given as reward
     /// - reward_supply - total supply of the reward token
     /// - start_week - the week from which the project starts producing rewards. Has to be >= 1.
     /// - duration_weeks - the duration in weeks of the project
     /// - lkmex_rewards_percentage - The percentage of the total rewards which will be given to LKMEX stakers.
-    ///     Expected value range is [0, 100]
-    #[only_owner]
+    ///     Expected value range is [0, 100]. The split can be set more finely, in basis points, through setProjectRewardSplit
+    /// - opt_name - an optional unique name of maximum 50 bytes, through which the project can be looked up with getProjectIdByName
+    #[allow(clippy::too_many_arguments)]
     #[endpoint(addProject)]
     fn add_project(
         &self,
@@ -71,7 +153,184 @@ pub trait ProjectModule: crate::common_storage::CommonStorageModule {
         start_week: Week,
         duration_weeks: Week,
         lkmex_rewards_percentage: u64,
+        opt_name: OptionalValue<ManagedBuffer>,
+    ) -> ProjectId<Self::Api> {
+        self.require_caller_role(Role::ProjectManager);
+
+        let project = self.create_project(
+            reward_token,
+            reward_supply,
+            start_week,
+            duration_weeks,
+            lkmex_rewards_percentage,
+        );
+        let project_id = if project_id.is_empty() {
+            self.generate_project_id()
+        } else {
+            project_id
+        };
+        if let OptionalValue::Some(name) = opt_name {
+            self.require_valid_project_name(&name);
+
+            let id_by_name_mapper = self.project_id_by_name(&name);
+            require!(id_by_name_mapper.is_empty(), "Name already in use");
+
+            id_by_name_mapper.set(&project_id);
+            self.project_metadata(&project_id).set(&ProjectMetadata {
+                name,
+                website_hash: ManagedByteArray::new_from_bytes(&[0u8; WEBSITE_HASH_LEN]),
+            });
+        }
+        self.insert_project(project_id.clone(), project_owner, project);
+
+        project_id
+    }
+
+    /// Adds a new season of an ended project, under a generated project ID, which is returned.
+    /// The reward token, project owner, metadata, category, claim fee and distribution settings
+    /// are copied from the ended project. The claimant whitelist, emission curve and edge offsets, if any, are not copied.
+    #[endpoint(renewProject)]
+    fn renew_project(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        new_start_week: Week,
+        new_end_week: Week,
+        new_delegation_reward_supply: BigUint,
+        new_lkmex_reward_supply: BigUint,
+    ) -> ProjectId<Self::Api> {
+        self.require_caller_role(Role::ProjectManager);
+
+        let project = self.get_project_or_panic(&project_id);
+        let current_week = self.get_current_week();
+        require!(current_week > project.end_week, "Project not ended");
+        require!(
+            new_start_week > current_week,
+            "Start week must be in the future"
+        );
+        require!(new_end_week >= new_start_week, "Invalid end week");
+        require!(
+            new_delegation_reward_supply > 0 || new_lkmex_reward_supply > 0,
+            "Reward supply cannot be 0"
+        );
+
+        let new_project = Project {
+            reward_token: project.reward_token,
+            delegation_reward_supply: new_delegation_reward_supply,
+            lkmex_reward_supply: new_lkmex_reward_supply,
+            start_week: new_start_week,
+            end_week: new_end_week,
+        };
+        let new_project_id = self.generate_project_id();
+        let project_owner = self.project_owner(&project_id).get();
+        self.insert_project(new_project_id.clone(), project_owner, new_project);
+
+        let metadata_mapper = self.project_metadata(&project_id);
+        if !metadata_mapper.is_empty() {
+            self.project_metadata(&new_project_id)
+                .set(&metadata_mapper.get());
+        }
+
+        let category = self.project_category(&project_id).get();
+        if !category.is_empty() {
+            let _ = self
+                .projects_by_category(&category)
+                .insert(new_project_id.clone());
+            self.project_category(&new_project_id).set(&category);
+        }
+
+        self.project_energy_weighted(&new_project_id)
+            .set(self.project_energy_weighted(&project_id).get());
+        self.project_distribution_mode(&new_project_id)
+            .set(self.project_distribution_mode(&project_id).get());
+        self.project_lkmex_factor_bps(&new_project_id)
+            .set(self.project_lkmex_factor_bps(&project_id).get());
+        self.project_claim_fee_bps(&new_project_id)
+            .set(self.project_claim_fee_bps(&project_id).get());
+        self.project_max_reward_per_user_per_week(&new_project_id)
+            .set(&self.project_max_reward_per_user_per_week(&project_id).get());
+
+        new_project_id
+    }
+
+    /// IDs are the big-endian encoding of a counter, skipping any IDs that were set manually or proposed
+    fn generate_project_id(&self) -> ProjectId<Self::Api> {
+        let mut last_id = self.last_generated_project_id().get();
+        loop {
+            last_id += 1;
+
+            let project_id = ProjectId::new_from_bytes(&last_id.to_be_bytes());
+            if !self.projects().contains_key(&project_id)
+                && !self.pending_projects().contains_key(&project_id)
+            {
+                self.last_generated_project_id().set(last_id);
+
+                return project_id;
+            }
+        }
+    }
+
+    /// Adds a new project, along with its metadata. Arguments:
+    /// - project_id: a unique ID of maximum 10 bytes
+    /// - project_owner - the owner of the project. They will receive any unclaimed funds for the projects.
+    /// - name - the display name of the project, of maximum 50 bytes
+    /// - website_hash - the sha256 of the project's website URL
+    /// - reward_token - the token ID of the token given as reward
+    /// - delegation_reward_supply - the rewards given to EGLD delegators
+    /// - lkmex_reward_supply - the rewards given to LKMEX stakers
+    /// - start_week - the week from which the project starts producing rewards. Has to be in the future.
+    /// - end_week - the last week in which the project produces rewards
+    #[allow(clippy::too_many_arguments)]
+    #[endpoint(addProjectWithDetails)]
+    fn add_project_with_details(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        project_owner: ManagedAddress,
+        name: ManagedBuffer,
+        website_hash: ManagedByteArray<Self::Api, WEBSITE_HASH_LEN>,
+        reward_token: TokenIdentifier,
+        delegation_reward_supply: BigUint,
+        lkmex_reward_supply: BigUint,
+        start_week: Week,
+        end_week: Week,
     ) {
+        self.require_caller_role(Role::ProjectManager);
+
+        require!(
+            reward_token.is_valid_esdt_identifier(),
+            "Invalid reward token"
+        );
+        require!(
+            delegation_reward_supply > 0 || lkmex_reward_supply > 0,
+            "Reward supply cannot be 0"
+        );
+        require!(
+            start_week > self.get_current_week(),
+            "Start week must be in the future"
+        );
+        require!(end_week >= start_week, "Invalid end week");
+        self.require_valid_project_name(&name);
+
+        self.project_metadata(&project_id)
+            .set(&ProjectMetadata { name, website_hash });
+
+        let project = Project {
+            reward_token,
+            delegation_reward_supply,
+            lkmex_reward_supply,
+            start_week,
+            end_week,
+        };
+        self.insert_project(project_id, project_owner, project);
+    }
+
+    fn create_project(
+        &self,
+        reward_token: TokenIdentifier,
+        reward_supply: BigUint,
+        start_week: Week,
+        duration_weeks: Week,
+        lkmex_rewards_percentage: u64,
+    ) -> Project<Self::Api> {
         require!(
             reward_token.is_valid_esdt_identifier(),
             "Invalid reward token"
@@ -84,145 +343,1859 @@ pub trait ProjectModule: crate::common_storage::CommonStorageModule {
             "Invalid percentage"
         );
 
-        let id_len = project_id.len();
-        require!(
-            id_len > 0 && id_len <= MAX_PROJECT_ID_LEN,
-            INVALID_PROJECT_ID_ERR_MSG
-        );
-
-        self.project_owner(&project_id).set(&project_owner);
-
         let lkmex_reward_supply = &reward_supply * lkmex_rewards_percentage / MAX_PERCENTAGE;
         let delegation_reward_supply = &reward_supply - &lkmex_reward_supply;
 
-        let project = Project {
+        Project {
             reward_token,
             delegation_reward_supply,
             lkmex_reward_supply,
             start_week,
             end_week: start_week + duration_weeks - 1,
-        };
-        let insert_result = self.projects().insert(project_id, project);
+        }
+    }
+
+    fn require_valid_project_id(&self, project_id: &ProjectId<Self::Api>) {
+        let id_len = project_id.len();
+        require!(
+            id_len > 0 && id_len <= MAX_PROJECT_ID_LEN,
+            INVALID_PROJECT_ID_ERR_MSG
+        );
+    }
+
+    fn require_valid_project_name(&self, name: &ManagedBuffer) {
+        let name_len = name.len();
+        require!(
+            name_len > 0 && name_len <= MAX_PROJECT_NAME_LEN,
+            "Invalid project name"
+        );
+    }
+
+    /// Whether the project was added with the given name, i.e. it is returned by getProjectIdByName
+    fn is_project_name_owner(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        name: &ManagedBuffer,
+    ) -> bool {
+        let id_by_name_mapper = self.project_id_by_name(name);
+        !id_by_name_mapper.is_empty() && &id_by_name_mapper.get() == project_id
+    }
+
+    fn insert_project(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        project_owner: ManagedAddress,
+        project: Project<Self::Api>,
+    ) {
+        self.require_valid_project_id(&project_id);
+        require!(
+            !self.pending_projects().contains_key(&project_id),
+            "ID already in use"
+        );
+        self.require_below_max_active_projects(&project);
+        self.require_reward_token_allowed(&project.reward_token);
+
+        self.project_owner(&project_id).set(&project_owner);
+        self.project_added_event(&project_id, &project_owner, &project);
+        let _ = self
+            .projects_by_reward_token(&project.reward_token)
+            .insert(project_id.clone());
+
+        self.add_project_to_snapshots(&project_id, &project);
+        let insert_result = self.projects().insert(project_id.clone(), project);
         require!(insert_result.is_none(), "ID already in use");
+
+        self.log_admin_action(AdminActionKind::ProjectAdded(project_id));
     }
 
-    /// Removes a project and gives any leftover funds to the project_owner
+    /// Sets the maximum number of projects producing rewards in the same week. 0 means no limit.
+    /// Only applies to projects added afterwards.
     #[only_owner]
-    #[endpoint(removeProject)]
-    fn remove_project(&self, project_id: ProjectId<Self::Api>) {
-        let project = self.get_project_or_panic(&project_id);
-        self.clear_and_refund_project(&project_id, &project.reward_token);
+    #[endpoint(setMaxActiveProjects)]
+    fn set_max_active_projects(&self, max_active_projects: usize) {
+        self.max_active_projects().set(max_active_projects);
+        self.log_admin_action(AdminActionKind::MaxActiveProjectsChanged(
+            max_active_projects,
+        ));
     }
 
-    /// Clears all expired projects and sends the leftover funds to the respective project_owner.
-    /// A project is considered expired if PROJECT_EXPIRATION_WEEKS weeks
-    ///     have passed since its last rewards week
-    #[only_owner]
-    #[endpoint(clearExpiredProjects)]
-    fn clear_expired_projects(&self) -> OperationCompletionStatus {
-        let mut prev_token = TokenIdentifier::from_esdt_bytes(&[]);
-        let mut prev_id = ProjectId::<Self::Api>::new();
-        let mut clear_prev_id = false;
-        let current_week = self.get_current_week();
+    /// The number of active projects only increases at the start of a project,
+    /// so it's enough to check the new project's start week and the start weeks that fall within its range.
+    fn require_below_max_active_projects(&self, new_project: &Project<Self::Api>) {
+        let max_active_projects = self.max_active_projects().get();
+        if max_active_projects == 0 {
+            return;
+        }
 
-        // can only clear on next step, otherwise we'd lose the map's internal links
-        for (id, project) in self.projects().iter() {
-            let gas_left = self.blockchain().get_gas_left();
-            if gas_left < MIN_GAS_FOR_CLEAR {
-                return OperationCompletionStatus::InterruptedBeforeOutOfGas;
+        self.require_active_projects_below(new_project.start_week, max_active_projects);
+        for project in self.projects().values() {
+            if self.is_in_range(
+                project.start_week,
+                new_project.start_week,
+                new_project.end_week,
+            ) {
+                self.require_active_projects_below(project.start_week, max_active_projects);
             }
+        }
+    }
 
-            if clear_prev_id {
-                self.clear_and_refund_project(&prev_id, &prev_token);
-                clear_prev_id = false;
-            }
+    fn require_active_projects_below(&self, week: Week, max_active_projects: usize) {
+        require!(
+            self.get_nr_active_projects(week) < max_active_projects,
+            "Too many active projects"
+        );
+    }
 
-            if project.is_expired(current_week) {
-                prev_token = project.reward_token;
-                prev_id = id;
-                clear_prev_id = true;
+    /// Saves the IDs of the projects active in the week, so the claims for that week
+    /// only go through these projects, instead of all the projects, including the finished ones.
+    fn snapshot_active_projects(&self, week: Week) {
+        let mut snapshot_mapper = self.week_active_projects(week);
+        for (id, project) in self.projects().iter() {
+            if self.is_in_range(week, project.start_week, project.end_week) {
+                snapshot_mapper.push(&id);
             }
         }
 
-        if clear_prev_id {
-            self.clear_and_refund_project(&prev_id, &prev_token);
-        }
-
-        OperationCompletionStatus::Completed
+        self.first_snapshot_week().set_if_empty(week);
+        self.last_snapshot_week().set(week);
     }
 
-    fn clear_and_refund_project(
+    /// Projects may be added after the checkpoints of some of their weeks, e.g. when starting in the past
+    fn add_project_to_snapshots(
         &self,
         project_id: &ProjectId<Self::Api>,
-        token_id: &TokenIdentifier,
+        project: &Project<Self::Api>,
     ) {
-        let project_owner = self.project_owner(project_id).take();
-        let leftover_funds = self.leftover_project_funds(project_id).take();
+        let first_snapshot_week_mapper = self.first_snapshot_week();
+        if first_snapshot_week_mapper.is_empty() {
+            return;
+        }
 
-        let _ = self.projects().remove(project_id);
+        let first_week = core::cmp::max(project.start_week, first_snapshot_week_mapper.get());
+        let last_week = core::cmp::min(project.end_week, self.last_snapshot_week().get());
+        for week in first_week..=last_week {
+            self.week_active_projects(week).push(project_id);
+        }
+    }
 
-        if leftover_funds > 0 {
-            self.send()
-                .direct_esdt(&project_owner, token_id, 0, &leftover_funds);
+    /// Returns the IDs of the projects which may have rewards in the given week, without duplicates.
+    /// Weeks before the snapshots were introduced fall back to all the projects.
+    /// Removed projects may still be included.
+    fn get_week_project_ids(&self, week: Week) -> ManagedVec<ProjectId<Self::Api>> {
+        let mut project_ids = ManagedVec::new();
+        let first_snapshot_week_mapper = self.first_snapshot_week();
+        if first_snapshot_week_mapper.is_empty() || week < first_snapshot_week_mapper.get() {
+            for id in self.projects().keys() {
+                project_ids.push(id);
+            }
+
+            return project_ids;
+        }
+
+        // a removed ID may be reused, and thus added to the snapshot twice
+        for id in self.week_active_projects(week).iter() {
+            if !project_ids.contains(&id) {
+                project_ids.push(id);
+            }
         }
+
+        project_ids
     }
 
-    #[view(getAllProjectIds)]
-    fn get_all_project_ids(&self) -> MultiValueEncoded<ProjectId<Self::Api>> {
-        let mut all_ids = ManagedVec::new();
-        for id in self.projects().keys() {
-            all_ids.push(id);
+    fn get_nr_active_projects(&self, week: Week) -> usize {
+        let mut nr_active_projects = 0;
+        for project in self.projects().values() {
+            if self.is_in_range(week, project.start_week, project.end_week) {
+                nr_active_projects += 1;
+            }
         }
 
-        all_ids.into()
+        nr_active_projects
     }
 
-    /// Returns a project by ID. The results are, in order:
-    /// - reward_token
-    /// - delegation_reward_supply
-    /// - lkmex_reward_supply
-    /// - start_week
-    /// - end_week
-    #[view(getProjectById)]
-    fn get_project_by_id(
+    /// Changes the project's reward supplies. Only allowed before the rewards are deposited
+    /// and before the project starts. The deposit then has to match the new total.
+    #[endpoint(updateProjectRewardSupply)]
+    fn update_project_reward_supply(
         &self,
         project_id: ProjectId<Self::Api>,
-    ) -> ProjectAsMultiResult<Self::Api> {
-        self.get_project_or_panic(&project_id).into_multiresult()
-    }
+        new_delegation_reward_supply: BigUint,
+        new_lkmex_reward_supply: BigUint,
+    ) {
+        self.require_caller_role(Role::ProjectManager);
 
-    fn get_project_or_panic(&self, project_id: &ProjectId<Self::Api>) -> Project<Self::Api> {
-        self.projects()
-            .get(project_id)
-            .unwrap_or_else(|| sc_panic!(INVALID_PROJECT_ID_ERR_MSG))
+        let mut project = self.get_project_or_panic(&project_id);
+        require!(
+            self.deposited_amount(&project_id).get() == 0,
+            "Rewards already deposited"
+        );
+        require!(
+            self.get_current_week() < project.start_week,
+            "Project already started"
+        );
+        require!(
+            new_delegation_reward_supply > 0 || new_lkmex_reward_supply > 0,
+            "Reward supply cannot be 0"
+        );
+
+        project.delegation_reward_supply = new_delegation_reward_supply;
+        project.lkmex_reward_supply = new_lkmex_reward_supply;
+        let _ = self.projects().insert(project_id.clone(), project);
+        self.log_project_update(&project_id, b"updateProjectRewardSupply");
     }
 
-    #[view(getCurrentWeek)]
-    fn get_current_week(&self) -> Week {
-        let first_week_start_epoch = self.first_week_start_epoch().get();
-        let current_epoch = self.blockchain().get_block_epoch();
+    /// Changes the share of the project's rewards given to LKMEX stakers, in basis points.
+    /// Only allowed before the project starts, as all the project's weeks share the same supplies,
+    /// so a later change would also apply to the weeks that can already be claimed.
+    /// The weekly reward supply is kept as is, so already deposited rewards remain valid.
+    /// For projects with an emission curve, the total supply is split instead,
+    /// which is only allowed before the rewards are deposited.
+    #[endpoint(setProjectRewardSplit)]
+    fn set_project_reward_split(&self, project_id: ProjectId<Self::Api>, lkmex_rewards_bps: u64) {
+        self.require_caller_role(Role::ProjectManager);
 
-        // will never overflow usize
-        unsafe {
-            ((current_epoch - first_week_start_epoch) / EPOCHS_IN_WEEK)
-                .try_into()
-                .unwrap_unchecked()
+        let mut project = self.get_project_or_panic(&project_id);
+        require!(
+            self.get_current_week() < project.start_week,
+            "Project already started"
+        );
+        require!(lkmex_rewards_bps <= MAX_SPLIT_BPS, "Invalid split");
+
+        if !self.project_week_weights(&project_id).is_empty() {
+            require!(
+                self.deposited_amount(&project_id).get() == 0,
+                "Rewards already deposited"
+            );
+
+            let total_supply = &project.delegation_reward_supply + &project.lkmex_reward_supply;
+            project.lkmex_reward_supply = &total_supply * lkmex_rewards_bps / MAX_SPLIT_BPS;
+            project.delegation_reward_supply = total_supply - &project.lkmex_reward_supply;
+            let _ = self.projects().insert(project_id.clone(), project);
+            self.log_project_update(&project_id, b"setProjectRewardSplit");
+            return;
         }
+
+        let duration_weeks = project.get_duration_in_weeks() as u32;
+        let weekly_reward_supply = project.get_weekly_reward_supply();
+        let weekly_lkmex_supply = &weekly_reward_supply * lkmex_rewards_bps / MAX_SPLIT_BPS;
+        let weekly_delegation_supply = &weekly_reward_supply - &weekly_lkmex_supply;
+
+        project.delegation_reward_supply = weekly_delegation_supply * duration_weeks;
+        project.lkmex_reward_supply = weekly_lkmex_supply * duration_weeks;
+        let _ = self.projects().insert(project_id.clone(), project);
+        self.log_project_update(&project_id, b"setProjectRewardSplit");
     }
 
-    #[storage_mapper("projects")]
-    fn projects(&self) -> MapMapper<ProjectId<Self::Api>, Project<Self::Api>>;
+    /// Sets the share of the project's supply given in each of its weeks, instead of a flat
+    /// supply / duration. One weight is expected for each week of the project, in order,
+    /// and the weights must add up to 10_000. Passing no weights restores the flat distribution.
+    /// Only allowed before the rewards are deposited and before the project starts.
+    /// Projects with an emission curve cannot be extended or cancelled.
+    #[endpoint(setProjectEmissionCurve)]
+    fn set_project_emission_curve(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        weights: MultiValueEncoded<u32>,
+    ) {
+        self.require_caller_role(Role::ProjectManager);
 
-    #[storage_mapper("projectOwner")]
-    fn project_owner(&self, project_id: &ProjectId<Self::Api>)
-        -> SingleValueMapper<ManagedAddress>;
+        let project = self.get_project_or_panic(&project_id);
+        require!(
+            self.deposited_amount(&project_id).get() == 0,
+            "Rewards already deposited"
+        );
+        require!(
+            self.get_current_week() < project.start_week,
+            "Project already started"
+        );
 
-    #[storage_mapper("leftoverProjectFunds")]
-    fn leftover_project_funds(
+        let mut weights_mapper = self.project_week_weights(&project_id);
+        weights_mapper.clear();
+        self.log_project_update(&project_id, b"setProjectEmissionCurve");
+        if weights.is_empty() {
+            return;
+        }
+
+        require!(
+            weights.len() == project.get_duration_in_weeks(),
+            "Invalid number of weights"
+        );
+
+        let mut weights_sum = 0u64;
+        for weight in weights {
+            weights_sum += weight as u64;
+            let _ = weights_mapper.push(&weight);
+        }
+        require!(
+            weights_sum == EMISSION_CURVE_WEIGHTS_SUM as u64,
+            "Invalid weights sum"
+        );
+    }
+
+    /// Sets the number of epochs of the project's first week before the project starts,
+    /// and of its last week after the project ends. The rewards of those weeks are reduced proportionally.
+    /// Only allowed before the rewards are deposited and before the project starts.
+    /// Projects with edge offsets cannot be extended or cancelled.
+    #[endpoint(setProjectEdgeOffsets)]
+    fn set_project_edge_offsets(
         &self,
-        project_id: &ProjectId<Self::Api>,
-    ) -> SingleValueMapper<BigUint>;
+        project_id: ProjectId<Self::Api>,
+        start_offset_epochs: Epoch,
+        end_offset_epochs: Epoch,
+    ) {
+        self.require_caller_role(Role::ProjectManager);
 
-    #[storage_mapper("rewardsDeposited")]
-    fn rewards_deposited(&self, project_id: &ProjectId<Self::Api>) -> SingleValueMapper<bool>;
+        let project = self.get_project_or_panic(&project_id);
+        require!(
+            self.deposited_amount(&project_id).get() == 0,
+            "Rewards already deposited"
+        );
+        require!(
+            self.get_current_week() < project.start_week,
+            "Project already started"
+        );
+        let epochs_per_week = self.get_epochs_per_week();
+        require!(
+            start_offset_epochs < epochs_per_week
+                && end_offset_epochs < epochs_per_week
+                && (project.start_week != project.end_week
+                    || start_offset_epochs + end_offset_epochs < epochs_per_week),
+            "Invalid offsets"
+        );
+
+        self.project_start_offset_epochs(&project_id)
+            .set(start_offset_epochs);
+        self.project_end_offset_epochs(&project_id)
+            .set(end_offset_epochs);
+        self.log_project_update(&project_id, b"setProjectEdgeOffsets");
+    }
+
+    /// Returns the (delegation, LKMEX) reward supplies for the given week of the project
+    fn get_project_week_reward_supplies(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        project: &Project<Self::Api>,
+        week: Week,
+    ) -> (BigUint, BigUint) {
+        let weights_mapper = self.project_week_weights(project_id);
+        let (delegation_supply, lkmex_supply) = if weights_mapper.is_empty() {
+            let duration_weeks = project.get_duration_in_weeks() as u32;
+            (
+                &project.delegation_reward_supply / duration_weeks,
+                &project.lkmex_reward_supply / duration_weeks,
+            )
+        } else {
+            let weight = weights_mapper.get(week - project.start_week + 1);
+            (
+                &project.delegation_reward_supply * weight / EMISSION_CURVE_WEIGHTS_SUM,
+                &project.lkmex_reward_supply * weight / EMISSION_CURVE_WEIGHTS_SUM,
+            )
+        };
+
+        let epochs_per_week = self.get_epochs_per_week();
+        let active_epochs = self.get_project_week_active_epochs(project_id, project, week);
+        if active_epochs == epochs_per_week {
+            return (delegation_supply, lkmex_supply);
+        }
+
+        (
+            delegation_supply * active_epochs / epochs_per_week,
+            lkmex_supply * active_epochs / epochs_per_week,
+        )
+    }
+
+    /// The number of epochs of the given week in which the project distributes rewards
+    fn get_project_week_active_epochs(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        project: &Project<Self::Api>,
+        week: Week,
+    ) -> Epoch {
+        // the offsets may exceed a week length changed afterwards
+        let mut active_epochs = self.get_epochs_per_week();
+        if week == project.start_week {
+            active_epochs =
+                active_epochs.saturating_sub(self.project_start_offset_epochs(project_id).get());
+        }
+        if week == project.end_week {
+            active_epochs =
+                active_epochs.saturating_sub(self.project_end_offset_epochs(project_id).get());
+        }
+
+        active_epochs
+    }
+
+    fn has_edge_offsets(&self, project_id: &ProjectId<Self::Api>) -> bool {
+        self.project_start_offset_epochs(project_id).get() > 0
+            || self.project_end_offset_epochs(project_id).get() > 0
+    }
+
+    fn get_project_week_reward_supply(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        project: &Project<Self::Api>,
+        week: Week,
+    ) -> BigUint {
+        let (delegation_supply, lkmex_supply) =
+            self.get_project_week_reward_supplies(project_id, project, week);
+
+        delegation_supply + lkmex_supply
+    }
+
+    fn get_project_distributable_supply(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        project: &Project<Self::Api>,
+    ) -> BigUint {
+        if self.project_week_weights(project_id).is_empty() && !self.has_edge_offsets(project_id) {
+            return project.get_distributable_supply();
+        }
+
+        let mut distributable_supply = BigUint::zero();
+        for week in project.start_week..=project.end_week {
+            distributable_supply += self.get_project_week_reward_supply(project_id, project, week);
+        }
+
+        distributable_supply
+    }
+
+    /// Sets the fee taken from the project's claimed rewards, in basis points.
+    /// The fee is sent to the treasury address.
+    #[only_owner]
+    #[endpoint(setProjectClaimFee)]
+    fn set_project_claim_fee(&self, project_id: ProjectId<Self::Api>, fee_bps: u64) {
+        self.require_no_timelock();
+
+        self.apply_project_claim_fee(project_id, fee_bps);
+    }
+
+    fn apply_project_claim_fee(&self, project_id: ProjectId<Self::Api>, fee_bps: u64) {
+        let _ = self.get_project_or_panic(&project_id);
+        require!(fee_bps <= MAX_FEE_BPS, "Invalid fee");
+        require!(
+            fee_bps == 0 || !self.treasury_address().is_empty(),
+            "Treasury address not set"
+        );
+
+        self.project_claim_fee_bps(&project_id).set(fee_bps);
+        self.log_admin_action(AdminActionKind::ProjectClaimFeeUpdated(project_id, fee_bps));
+    }
+
+    /// Cancels the remaining weeks of a project that already started.
+    /// The weeks up to and including the current week can still be claimed,
+    /// while the rewards of the following weeks are refunded to the project owner.
+    /// Only the deposited amount above the remaining weeks' rewards is refunded, so a partially funded
+    /// project can still be funded with the missing amount, and is funded if the deposits already cover it.
+    #[endpoint(cancelProject)]
+    fn cancel_project(&self, project_id: ProjectId<Self::Api>) {
+        self.require_caller_role(Role::ProjectManager);
+
+        let mut project = self.get_project_or_panic(&project_id);
+        let current_week = self.get_current_week();
+        require!(current_week >= project.start_week, "Project not started");
+        require!(current_week < project.end_week, "No weeks left to cancel");
+        require!(
+            self.project_week_weights(&project_id).is_empty(),
+            "Project has an emission curve"
+        );
+        require!(
+            !self.has_edge_offsets(&project_id),
+            "Project has edge offsets"
+        );
+        require!(
+            self.project_partner_rewards(&project_id).is_empty(),
+            "Project has partner rewards"
+        );
+
+        // the remaining weeks keep the same weekly rewards
+        let duration_weeks = project.get_duration_in_weeks() as u32;
+        let remaining_duration_weeks = (current_week - project.start_week + 1) as u32;
+        project.delegation_reward_supply =
+            &project.delegation_reward_supply / duration_weeks * remaining_duration_weeks;
+        project.lkmex_reward_supply =
+            &project.lkmex_reward_supply / duration_weeks * remaining_duration_weeks;
+        project.end_week = current_week;
+        let reward_token = project.reward_token.clone();
+        let new_distributable_supply = project.get_distributable_supply();
+        let _ = self.projects().insert(project_id.clone(), project);
+
+        self.project_cancelled_from_week(&project_id)
+            .set(current_week + 1);
+        self.pending_project_extension(&project_id).clear();
+
+        // for a funded project, this is exactly the sum of the cancelled weeks' rewards
+        let mut refund_amount = BigUint::zero();
+        let deposited_amount_mapper = self.deposited_amount(&project_id);
+        let deposited_amount = deposited_amount_mapper.get();
+        if deposited_amount > new_distributable_supply {
+            let leftover_funds_mapper = self.leftover_project_funds(&project_id);
+            let leftover_funds = leftover_funds_mapper.get();
+            refund_amount = core::cmp::min(
+                &deposited_amount - &new_distributable_supply,
+                leftover_funds.clone(),
+            );
+            leftover_funds_mapper.set(&(leftover_funds - &refund_amount));
+            deposited_amount_mapper.set(&new_distributable_supply);
+        }
+        if deposited_amount >= new_distributable_supply
+            && self.funded_projects().insert(project_id.clone())
+        {
+            self.refund_listing_fee_escrow(&project_id);
+        }
+
+        let project_owner = self.project_owner(&project_id).get();
+        if refund_amount > 0 {
+            let reward_token_nonce = self.project_reward_token_nonce(&project_id).get();
+            self.send_reward(
+                &project_owner,
+                &reward_token,
+                reward_token_nonce,
+                &refund_amount,
+            );
+        }
+
+        self.project_cancelled_event(&project_id, current_week + 1, &refund_amount);
+        self.log_project_update(&project_id, b"cancelProject");
+    }
+
+    /// Removes a project and gives any leftover funds to the project_owner.
+    /// The leftover funds are the deposited rewards, minus what was already claimed or swept.
+    /// Projects with rewards kept for users while paused, or not yet locked,
+    /// cannot be removed until those rewards are claimed.
+    #[endpoint(removeProject)]
+    fn remove_project(&self, project_id: ProjectId<Self::Api>) {
+        self.require_caller_role(Role::ProjectManager);
+
+        let project = self.get_project_or_panic(&project_id);
+        self.clear_and_refund_project(&project_id, &project.reward_token);
+    }
+
+    /// Clears all expired projects and sends the leftover funds to the respective project_owner.
+    /// A project is considered expired if the configured claim expiration weeks
+    ///     have passed since its last rewards week (PROJECT_EXPIRATION_WEEKS by default)
+    /// Projects with rewards kept for users while paused, or not yet locked, are skipped.
+    #[endpoint(clearExpiredProjects)]
+    fn clear_expired_projects(&self) -> OperationCompletionStatus {
+        self.require_caller_role(Role::ProjectManager);
+
+        let mut prev_token = TokenIdentifier::from_esdt_bytes(&[]);
+        let mut prev_id = ProjectId::<Self::Api>::new();
+        let mut clear_prev_id = false;
+        let current_week = self.get_current_week();
+        let expiration_weeks = self.get_claim_expiration_weeks();
+
+        // can only clear on next step, otherwise we'd lose the map's internal links
+        for (id, project) in self.projects().iter() {
+            let gas_left = self.blockchain().get_gas_left();
+            if gas_left < MIN_GAS_FOR_CLEAR {
+                return OperationCompletionStatus::InterruptedBeforeOutOfGas;
+            }
+
+            if clear_prev_id {
+                self.clear_and_refund_project(&prev_id, &prev_token);
+                clear_prev_id = false;
+            }
+
+            if project.is_expired(current_week, expiration_weeks)
+                && self.total_paused_project_rewards(&id).get() == 0
+                && self.total_pending_locked_rewards(&id).get() == 0
+            {
+                prev_token = project.reward_token;
+                prev_id = id;
+                clear_prev_id = true;
+            }
+        }
+
+        if clear_prev_id {
+            self.clear_and_refund_project(&prev_id, &prev_token);
+        }
+
+        OperationCompletionStatus::Completed
+    }
+
+    /// Clears a single expired project and sends the leftover funds to the project owner.
+    /// May be called by anyone, who receives the cleanup bounty if there are enough funds left in the pool.
+    /// Projects still holding paused or pending locked rewards can't be cleared.
+    #[endpoint(clearExpiredProject)]
+    fn clear_expired_project(&self, project_id: ProjectId<Self::Api>) {
+        let project = self.get_project_or_panic(&project_id);
+        let current_week = self.get_current_week();
+        require!(
+            project.is_expired(current_week, self.get_claim_expiration_weeks()),
+            "Project not expired"
+        );
+
+        self.clear_and_refund_project(&project_id, &project.reward_token);
+
+        let caller = self.blockchain().get_caller();
+        self.pay_bounty(
+            &caller,
+            self.cleanup_bounty_token(),
+            self.cleanup_bounty_amount(),
+            self.cleanup_bounty_pool(),
+        );
+    }
+
+    /// Sets the reward paid to the caller of clearExpiredProject.
+    /// The token can only be changed while the bounty pool is empty.
+    #[endpoint(setCleanupBounty)]
+    fn set_cleanup_bounty(&self, token_id: EgldOrEsdtTokenIdentifier, amount: BigUint) {
+        self.require_caller_role(Role::Treasurer);
+
+        self.set_bounty(
+            self.cleanup_bounty_token(),
+            self.cleanup_bounty_amount(),
+            self.cleanup_bounty_pool(),
+            token_id.clone(),
+            amount.clone(),
+        );
+        self.log_admin_action(AdminActionKind::CleanupBountyChanged(token_id, amount));
+    }
+
+    #[payable("*")]
+    #[endpoint(fundCleanupBounty)]
+    fn fund_cleanup_bounty(&self) {
+        self.require_caller_role(Role::Treasurer);
+
+        self.fund_bounty(self.cleanup_bounty_token(), self.cleanup_bounty_pool());
+    }
+
+    /// Sends the given amount from the cleanup bounty pool back to the owner.
+    #[only_owner]
+    #[endpoint(withdrawCleanupBounty)]
+    fn withdraw_cleanup_bounty(&self, amount: BigUint) {
+        self.withdraw_bounty_pool(
+            self.cleanup_bounty_token(),
+            self.cleanup_bounty_pool(),
+            amount.clone(),
+        );
+        self.log_admin_action(AdminActionKind::CleanupBountyWithdrawn(amount));
+    }
+
+    /// Pauses reward distribution for the given project.
+    /// Users can still claim the affected weeks, and the project's rewards will be kept for them
+    /// until the project is resumed, after which they can be claimed through claimPausedProjectRewards.
+    /// May be called by a project manager or the project owner. The caller is recorded as the pauser.
+    #[endpoint(pauseProjectClaims)]
+    fn pause_project_claims(&self, project_id: ProjectId<Self::Api>) {
+        let _ = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+        require!(
+            !self.project_claims_paused(&project_id).get(),
+            "Project claims already paused"
+        );
+
+        let caller = self.blockchain().get_caller();
+        self.project_claims_paused(&project_id).set(true);
+        self.project_claims_paused_by(&project_id).set(&caller);
+        self.project_claims_paused_event(&project_id, &caller);
+        self.log_admin_action(AdminActionKind::ProjectClaimsPaused(project_id));
+    }
+
+    /// May only be called by whoever paused the claims, or by the owner.
+    /// Claims paused by the circuit breaker may only be resumed by the owner.
+    #[endpoint(resumeProjectClaims)]
+    fn resume_project_claims(&self, project_id: ProjectId<Self::Api>) {
+        let _ = self.get_project_or_panic(&project_id);
+        let caller = self.blockchain().get_caller();
+        let paused_by_mapper = self.project_claims_paused_by(&project_id);
+        if caller != self.blockchain().get_owner_address() {
+            if paused_by_mapper.is_empty() {
+                self.require_caller_project_manager_or_project_owner(&project_id);
+            } else {
+                let paused_by = paused_by_mapper.get();
+                require!(
+                    paused_by != self.blockchain().get_sc_address(),
+                    "Only owner may resume claims paused by the circuit breaker"
+                );
+                require!(
+                    caller == paused_by,
+                    "Only the pauser or the owner may resume claims"
+                );
+            }
+        }
+
+        self.project_claims_paused(&project_id).clear();
+        paused_by_mapper.clear();
+        self.project_claims_resumed_event(&project_id, &caller);
+        self.log_admin_action(AdminActionKind::ProjectClaimsResumed(project_id));
+    }
+
+    /// Updates the project's name and website hash. May be called by a project manager or the project owner.
+    #[endpoint(updateProjectMetadata)]
+    fn update_project_metadata(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        name: ManagedBuffer,
+        website_hash: ManagedByteArray<Self::Api, WEBSITE_HASH_LEN>,
+    ) {
+        let _ = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+        self.require_valid_project_name(&name);
+
+        // keep the name lookup of projects added with a name in sync
+        let metadata_mapper = self.project_metadata(&project_id);
+        if !metadata_mapper.is_empty() {
+            let old_name = metadata_mapper.get().name;
+            if old_name != name && self.is_project_name_owner(&project_id, &old_name) {
+                let id_by_name_mapper = self.project_id_by_name(&name);
+                require!(id_by_name_mapper.is_empty(), "Name already in use");
+
+                id_by_name_mapper.set(&project_id);
+                self.project_id_by_name(&old_name).clear();
+            }
+        }
+
+        metadata_mapper.set(&ProjectMetadata { name, website_hash });
+        self.log_project_update(&project_id, b"updateProjectMetadata");
+    }
+
+    /// Sets the category used for filtering projects in frontends, e.g. "defi" or "gaming".
+    /// An empty category removes the project from its previous category.
+    /// May be called by a project manager or the project owner.
+    #[endpoint(setProjectCategory)]
+    fn set_project_category(&self, project_id: ProjectId<Self::Api>, category: ManagedBuffer) {
+        let _ = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+        require!(
+            category.len() <= MAX_PROJECT_CATEGORY_LEN,
+            "Invalid project category"
+        );
+
+        self.remove_project_from_category(&project_id);
+        if !category.is_empty() {
+            let _ = self
+                .projects_by_category(&category)
+                .insert(project_id.clone());
+            self.project_category(&project_id).set(&category);
+        }
+        self.log_project_update(&project_id, b"setProjectCategory");
+    }
+
+    fn remove_project_from_category(&self, project_id: &ProjectId<Self::Api>) {
+        let category = self.project_category(project_id).take();
+        if !category.is_empty() {
+            let _ = self.projects_by_category(&category).swap_remove(project_id);
+        }
+    }
+
+    /// Sets the maximum reward a user may receive from the project in a single week.
+    /// The rewards above the cap are kept in the project's funds,
+    /// and can be refunded through refundProjectCappedRewards once their week expired. 0 means no cap.
+    /// Can only be changed before the project starts, so all the users of a week have the same cap.
+    /// May be called by a project manager or the project owner.
+    #[endpoint(setProjectMaxRewardPerUserPerWeek)]
+    fn set_project_max_reward_per_user_per_week(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        max_reward: BigUint,
+    ) {
+        let project = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+        require!(
+            self.get_current_week() < project.start_week,
+            "Project already started"
+        );
+
+        self.project_max_reward_per_user_per_week(&project_id)
+            .set(&max_reward);
+        self.log_admin_action(AdminActionKind::ProjectMaxRewardPerUserPerWeekChanged(
+            project_id, max_reward,
+        ));
+    }
+
+    /// Sends the project's rewards lost to rounding to the project owner, once the project ended.
+    /// May be called by a project manager or the project owner.
+    #[endpoint(reclaimProjectRoundingRemainders)]
+    fn reclaim_project_rounding_remainders(&self, project_id: ProjectId<Self::Api>) {
+        let project = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+        require!(
+            self.get_current_week() > project.end_week,
+            "Project not ended"
+        );
+
+        let rounding_remainders = self.project_rounding_remainders(&project_id).take();
+        let leftover_funds_mapper = self.leftover_project_funds(&project_id);
+        let leftover_funds = leftover_funds_mapper.get();
+        let refund_amount = core::cmp::min(rounding_remainders, leftover_funds.clone());
+        require!(refund_amount > 0, "No rounding remainders");
+
+        leftover_funds_mapper.set(&(leftover_funds - &refund_amount));
+
+        let project_owner = self.project_owner(&project_id).get();
+        let reward_token_nonce = self.project_reward_token_nonce(&project_id).get();
+        self.send_reward(
+            &project_owner,
+            &project.reward_token,
+            reward_token_nonce,
+            &refund_amount,
+        );
+        self.log_project_update(&project_id, b"reclaimProjectRoundingRemainders");
+    }
+
+    /// Sets the bonus given to users for each consecutive week in which they claimed the project's rewards,
+    /// in basis points of their weekly reward, and the maximum bonus. The first week of a streak gets no bonus.
+    /// Bonuses are paid from the project's bonus pool, while it has funds. A bonus of 0 disables streaks.
+    /// May be called by a project manager or the project owner.
+    #[endpoint(setProjectStreakBonus)]
+    fn set_project_streak_bonus(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        bonus_bps_per_week: u64,
+        max_bonus_bps: u64,
+    ) {
+        let _ = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+        require!(
+            bonus_bps_per_week <= max_bonus_bps && max_bonus_bps <= MAX_BONUS_BPS,
+            "Invalid bonus"
+        );
+
+        self.project_streak_bonus_bps_per_week(&project_id)
+            .set(bonus_bps_per_week);
+        self.project_max_streak_bonus_bps(&project_id)
+            .set(max_bonus_bps);
+        self.log_project_update(&project_id, b"setProjectStreakBonus");
+    }
+
+    /// Sets the minimum weekly reward of any user with a positive reward from the project.
+    /// The difference is paid from the project's bonus pool, while it has funds. 0 means no minimum.
+    /// May be called by a project manager or the project owner.
+    #[endpoint(setProjectMinRewardPerUser)]
+    fn set_project_min_reward_per_user(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        min_reward: BigUint,
+    ) {
+        let _ = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+
+        self.project_min_reward_per_user(&project_id)
+            .set(&min_reward);
+        self.log_project_update(&project_id, b"setProjectMinRewardPerUser");
+    }
+
+    /// Adds a partner token to be distributed along with the project's reward token.
+    /// The partner token's weekly rewards are computed from its own supplies, over the project's weeks,
+    /// like the project's own rewards.
+    /// Can only be added before the project starts, and not while an extension is pending,
+    /// up to MAX_PARTNER_REWARDS per project. Projects with partner rewards can't be cancelled or extended.
+    /// The partner rewards are deposited through depositPartnerRewards.
+    #[endpoint(addProjectPartnerReward)]
+    fn add_project_partner_reward(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        token: TokenIdentifier,
+        delegation_supply: BigUint,
+        lkmex_supply: BigUint,
+    ) {
+        self.require_caller_role(Role::ProjectManager);
+
+        let project = self.get_project_or_panic(&project_id);
+        require!(
+            self.get_current_week() < project.start_week,
+            "Project already started"
+        );
+        require!(
+            token.is_valid_esdt_identifier()
+                && token != project.reward_token
+                && !self.is_egld_reward_token(&token),
+            "Invalid partner reward token"
+        );
+        require!(delegation_supply > 0 || lkmex_supply > 0, "Invalid supply");
+        require!(
+            self.pending_project_extension(&project_id).is_empty(),
+            "Project has a pending extension"
+        );
+        self.require_reward_token_allowed(&token);
+
+        let mut partner_rewards_mapper = self.project_partner_rewards(&project_id);
+        require!(
+            partner_rewards_mapper.len() < MAX_PARTNER_REWARDS,
+            "Too many partner rewards"
+        );
+        for partner_reward in partner_rewards_mapper.iter() {
+            require!(
+                partner_reward.token != token,
+                "Partner reward already added"
+            );
+        }
+
+        let _ = self
+            .projects_by_reward_token(&token)
+            .insert(project_id.clone());
+        let _ = partner_rewards_mapper.push(&PartnerReward {
+            token,
+            token_nonce: 0,
+            delegation_supply,
+            lkmex_supply,
+            deposited_amount: BigUint::zero(),
+            leftover_funds: BigUint::zero(),
+        });
+        self.log_project_update(&project_id, b"addProjectPartnerReward");
+    }
+
+    /// Sets the exact nonce of the reward token that deposits must have, for SFT or MetaESDT rewards.
+    /// Otherwise, the nonce is set by the first deposit.
+    /// Can only be set before any rewards are deposited.
+    /// May be called by a project manager or the project owner.
+    #[endpoint(setProjectRewardTokenNonce)]
+    fn set_project_reward_token_nonce(&self, project_id: ProjectId<Self::Api>, token_nonce: u64) {
+        let _ = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+        require!(
+            self.deposited_amount(&project_id).get() == 0,
+            "Rewards already deposited"
+        );
+
+        self.project_reward_token_nonce(&project_id)
+            .set(token_nonce);
+        self.project_reward_token_nonce_fixed(&project_id).set(true);
+        self.log_project_update(&project_id, b"setProjectRewardTokenNonce");
+    }
+
+    /// Restricts the project's rewards to the whitelisted claimants.
+    /// Rewards of users who are not whitelisted are kept in the project's leftover funds.
+    /// May be called by a project manager or the project owner.
+    #[endpoint(setProjectClaimantWhitelistEnabled)]
+    fn set_project_claimant_whitelist_enabled(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        enabled: bool,
+    ) {
+        let _ = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+
+        self.project_claimant_whitelist_enabled(&project_id)
+            .set(enabled);
+        self.log_project_update(&project_id, b"setProjectClaimantWhitelistEnabled");
+    }
+
+    /// May be called by a project manager or the project owner.
+    #[endpoint(addProjectClaimants)]
+    fn add_project_claimants(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        claimants: MultiValueEncoded<ManagedAddress>,
+    ) {
+        let _ = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+
+        let mut mapper = self.project_claimant_whitelist(&project_id);
+        for claimant in claimants {
+            let _ = mapper.insert(claimant);
+        }
+        self.log_project_update(&project_id, b"addProjectClaimants");
+    }
+
+    /// May be called by a project manager or the project owner.
+    #[endpoint(removeProjectClaimants)]
+    fn remove_project_claimants(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        claimants: MultiValueEncoded<ManagedAddress>,
+    ) {
+        let _ = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+
+        let mut mapper = self.project_claimant_whitelist(&project_id);
+        for claimant in claimants {
+            let _ = mapper.swap_remove(&claimant);
+        }
+        self.log_project_update(&project_id, b"removeProjectClaimants");
+    }
+
+    #[view(isProjectClaimantEligible)]
+    fn is_project_claimant_eligible(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        user: &ManagedAddress,
+    ) -> bool {
+        !self.project_claimant_whitelist_enabled(project_id).get()
+            || self.project_claimant_whitelist(project_id).contains(user)
+    }
+
+    /// Starts the transfer of the project's ownership, which is completed once the new owner
+    /// calls acceptProjectOwnership. May be called by a project manager or the project owner.
+    #[endpoint(transferProjectOwnership)]
+    fn transfer_project_ownership(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        new_owner: ManagedAddress,
+    ) {
+        let _ = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+        require!(
+            new_owner != self.project_owner(&project_id).get(),
+            "Already project owner"
+        );
+
+        self.pending_project_owner(&project_id).set(&new_owner);
+        self.log_project_update(&project_id, b"transferProjectOwnership");
+    }
+
+    #[endpoint(acceptProjectOwnership)]
+    fn accept_project_ownership(&self, project_id: ProjectId<Self::Api>) {
+        let _ = self.get_project_or_panic(&project_id);
+        let pending_owner_mapper = self.pending_project_owner(&project_id);
+        let caller = self.blockchain().get_caller();
+        require!(
+            !pending_owner_mapper.is_empty() && caller == pending_owner_mapper.get(),
+            "Only the pending project owner may accept ownership"
+        );
+
+        pending_owner_mapper.clear();
+        let old_owner = self.project_owner(&project_id).replace(&caller);
+        self.project_ownership_transferred_event(&project_id, &old_owner, &caller);
+    }
+
+    fn require_caller_project_manager_or_project_owner(&self, project_id: &ProjectId<Self::Api>) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.has_role(&caller, Role::ProjectManager)
+                || caller == self.project_owner(project_id).get(),
+            "Only project manager or project owner may call this function"
+        );
+    }
+
+    /// Makes the project distribute its LKMEX share of the rewards according to the users' energy,
+    /// for the weeks whose checkpoint includes the total energy.
+    /// Only the project owner may change this, and only before the project starts.
+    #[endpoint(setProjectEnergyWeighted)]
+    fn set_project_energy_weighted(&self, project_id: ProjectId<Self::Api>, energy_weighted: bool) {
+        self.require_distribution_change_allowed(&project_id);
+
+        self.project_energy_weighted(&project_id)
+            .set(energy_weighted);
+        self.log_project_update(&project_id, b"setProjectEnergyWeighted");
+    }
+
+    /// Sets how the project's rewards are distributed between users.
+    /// Only the project owner may change this, and only before the project starts.
+    #[endpoint(setProjectDistributionMode)]
+    fn set_project_distribution_mode(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        distribution_mode: DistributionMode,
+    ) {
+        self.require_distribution_change_allowed(&project_id);
+
+        self.project_distribution_mode(&project_id)
+            .set(distribution_mode);
+        self.log_project_update(&project_id, b"setProjectDistributionMode");
+    }
+
+    /// Sets the weight of LKMEX relative to delegation for the combined distribution mode, in basis points.
+    /// Only the project owner may change this, and only before the project starts.
+    #[endpoint(setProjectLkmexFactor)]
+    fn set_project_lkmex_factor(&self, project_id: ProjectId<Self::Api>, lkmex_factor_bps: u64) {
+        self.require_distribution_change_allowed(&project_id);
+
+        self.project_lkmex_factor_bps(&project_id)
+            .set(lkmex_factor_bps);
+        self.log_project_update(&project_id, b"setProjectLkmexFactor");
+    }
+
+    fn require_distribution_change_allowed(&self, project_id: &ProjectId<Self::Api>) {
+        let project = self.get_project_or_panic(project_id);
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == self.project_owner(project_id).get(),
+            "Only project owner may change the distribution"
+        );
+        require!(
+            self.get_current_week() < project.start_week,
+            "Project already started"
+        );
+    }
+
+    fn refund_listing_fee_escrow(&self, project_id: &ProjectId<Self::Api>) {
+        let escrow_mapper = self.listing_fee_escrow(project_id);
+        if escrow_mapper.is_empty() {
+            return;
+        }
+
+        let escrow = escrow_mapper.take();
+        let project_owner = self.project_owner(project_id).get();
+        self.send().direct(
+            &project_owner,
+            &escrow.token_identifier,
+            escrow.token_nonce,
+            &escrow.amount,
+        );
+    }
+
+    fn forfeit_listing_fee_escrow(&self, project_id: &ProjectId<Self::Api>) {
+        let treasury_mapper = self.treasury_address();
+        require!(!treasury_mapper.is_empty(), "Treasury address not set");
+
+        let escrow = self.listing_fee_escrow(project_id).take();
+        self.send().direct(
+            &treasury_mapper.get(),
+            &escrow.token_identifier,
+            escrow.token_nonce,
+            &escrow.amount,
+        );
+    }
+
+    fn is_egld_reward_token(&self, token_id: &TokenIdentifier) -> bool {
+        token_id.as_managed_buffer() == &ManagedBuffer::new_from_bytes(EGLD_REWARD_TOKEN_ID)
+    }
+
+    fn egld_or_esdt_to_reward_token(&self, token_id: EgldOrEsdtTokenIdentifier) -> TokenIdentifier {
+        if token_id.is_egld() {
+            TokenIdentifier::from_esdt_bytes(EGLD_REWARD_TOKEN_ID)
+        } else {
+            token_id.unwrap_esdt()
+        }
+    }
+
+    fn reward_token_to_egld_or_esdt(
+        &self,
+        token_id: &TokenIdentifier,
+    ) -> EgldOrEsdtTokenIdentifier {
+        if self.is_egld_reward_token(token_id) {
+            EgldOrEsdtTokenIdentifier::egld()
+        } else {
+            EgldOrEsdtTokenIdentifier::esdt(token_id.clone())
+        }
+    }
+
+    /// Returns the paid reward tokens, with EGLD as EGLD_REWARD_TOKEN_ID
+    fn get_reward_payment(&self) -> EsdtTokenPayment {
+        let payment = self.call_value().egld_or_single_esdt();
+        EsdtTokenPayment::new(
+            self.egld_or_esdt_to_reward_token(payment.token_identifier),
+            payment.token_nonce,
+            payment.amount,
+        )
+    }
+
+    fn send_reward(
+        &self,
+        to: &ManagedAddress,
+        token_id: &TokenIdentifier,
+        token_nonce: u64,
+        amount: &BigUint,
+    ) {
+        self.send().direct(
+            to,
+            &self.reward_token_to_egld_or_esdt(token_id),
+            token_nonce,
+            amount,
+        );
+    }
+
+    /// EGLD can not be part of a multi-transfer, so it is sent separately
+    fn send_rewards(&self, to: &ManagedAddress, payments: &ManagedVec<EsdtTokenPayment>) {
+        let mut esdt_payments = ManagedVec::new();
+        for payment in payments {
+            if self.is_egld_reward_token(&payment.token_identifier) {
+                self.send().direct_egld(to, &payment.amount);
+            } else {
+                esdt_payments.push(payment);
+            }
+        }
+
+        if !esdt_payments.is_empty() {
+            self.send().direct_multi(to, &esdt_payments);
+        }
+    }
+
+    fn clear_and_refund_project(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        token_id: &TokenIdentifier,
+    ) {
+        // the per-user paused and pending locked rewards are all empty once their totals are 0
+        require!(
+            self.total_paused_project_rewards(project_id).take() == 0,
+            "Project has paused rewards"
+        );
+        require!(
+            self.total_pending_locked_rewards(project_id).take() == 0,
+            "Project has pending locked rewards"
+        );
+
+        self.refund_listing_fee_escrow(project_id);
+
+        let project_owner = self.project_owner(project_id).take();
+        let leftover_funds = self.leftover_project_funds(project_id).take();
+        let reward_token_nonce = self.project_reward_token_nonce(project_id).take();
+        self.project_reward_token_nonce_fixed(project_id).clear();
+        self.project_rewards_lock_epochs(project_id).clear();
+        self.pending_project_owner(project_id).clear();
+        let _ = self.funded_projects().swap_remove(project_id);
+        self.deposited_amount(project_id).clear();
+        self.owner_deposit_allowed(project_id).clear();
+        self.last_swept_week(project_id).clear();
+        self.project_claims_paused(project_id).clear();
+        self.project_claims_paused_by(project_id).clear();
+        self.project_energy_weighted(project_id).clear();
+        self.project_distribution_mode(project_id).clear();
+        self.project_lkmex_factor_bps(project_id).clear();
+        self.project_week_weights(project_id).clear();
+        self.project_start_offset_epochs(project_id).clear();
+        self.project_end_offset_epochs(project_id).clear();
+        let metadata_mapper = self.project_metadata(project_id);
+        if !metadata_mapper.is_empty() {
+            let name = metadata_mapper.take().name;
+            if self.is_project_name_owner(project_id, &name) {
+                self.project_id_by_name(&name).clear();
+            }
+        }
+        self.pending_project_extension(project_id).clear();
+        self.project_cancelled_from_week(project_id).clear();
+        self.project_claim_fee_bps(project_id).clear();
+        self.project_collected_fees(project_id).clear();
+        self.total_claimed_project_rewards(project_id).clear();
+        self.total_swept_project_rewards(project_id).clear();
+        self.project_unique_claimers(project_id).clear();
+        self.project_claimant_whitelist_enabled(project_id).clear();
+        self.project_claimant_whitelist(project_id).clear();
+        self.project_max_reward_per_user_per_week(project_id)
+            .clear();
+        self.project_capped_rewards(project_id).clear();
+        self.last_capped_rewards_refund_week(project_id).clear();
+        self.project_rounding_remainders(project_id).clear();
+        self.project_streak_bonus_bps_per_week(project_id).clear();
+        self.project_max_streak_bonus_bps(project_id).clear();
+        self.project_bonus_pool(project_id).clear();
+        self.project_min_reward_per_user(project_id).clear();
+        self.remove_project_from_category(project_id);
+
+        // the per-user claimer flags are left in storage, as they cannot be iterated
+        if let Some(project) = self.projects().remove(project_id) {
+            let _ = self
+                .projects_by_reward_token(&project.reward_token)
+                .swap_remove(project_id);
+            for week in project.start_week..=project.end_week {
+                self.project_week_claims(project_id, week).clear();
+                self.project_week_capped_rewards(project_id, week).clear();
+                self.claimed_project_week_rewards(project_id, week).clear();
+                self.delegation_rounding_remainder(project_id, week).clear();
+                self.lkmex_rounding_remainder(project_id, week).clear();
+            }
+        }
+
+        if leftover_funds > 0 {
+            self.send_reward(
+                &project_owner,
+                token_id,
+                reward_token_nonce,
+                &leftover_funds,
+            );
+        }
+
+        let mut partner_rewards_mapper = self.project_partner_rewards(project_id);
+        for partner_reward in partner_rewards_mapper.iter() {
+            let _ = self
+                .projects_by_reward_token(&partner_reward.token)
+                .swap_remove(project_id);
+            if partner_reward.leftover_funds > 0 {
+                self.send().direct_esdt(
+                    &project_owner,
+                    &partner_reward.token,
+                    partner_reward.token_nonce,
+                    &partner_reward.leftover_funds,
+                );
+            }
+        }
+        partner_rewards_mapper.clear();
+
+        self.project_removed_event(project_id, &project_owner, &leftover_funds);
+        self.log_admin_action(AdminActionKind::ProjectRemoved(project_id.clone()));
+    }
+
+    #[view(getAllProjectIds)]
+    fn get_all_project_ids(&self) -> MultiValueEncoded<ProjectId<Self::Api>> {
+        let mut all_ids = ManagedVec::new();
+        for id in self.projects().keys() {
+            all_ids.push(id);
+        }
+
+        all_ids.into()
+    }
+
+    /// Returns at most limit projects, skipping the first from projects. Each result is, in order:
+    /// - project_id
+    /// - reward_token
+    /// - start_week
+    /// - end_week
+    /// - deposited: whether all the rewards were deposited
+    /// - paused: whether the project's claims are paused
+    #[label("metabonding-external-view")]
+    #[view(getProjects)]
+    fn get_projects(
+        &self,
+        from: usize,
+        limit: usize,
+    ) -> MultiValueEncoded<ProjectStatusAsMultiResult<Self::Api>> {
+        require!(limit <= MAX_PROJECTS_PAGE_SIZE, "Page size too large");
+
+        let mut result = MultiValueEncoded::new();
+        for (id, project) in self.projects().iter().skip(from).take(limit) {
+            let deposited = self.funded_projects().contains(&id);
+            let paused = self.project_claims_paused(&id).get();
+            result.push(
+                (
+                    id,
+                    project.reward_token,
+                    project.start_week,
+                    project.end_week,
+                    deposited,
+                    paused,
+                )
+                    .into(),
+            );
+        }
+
+        result
+    }
+
+    #[label("metabonding-external-view")]
+    #[view(getProjectsByCategory)]
+    fn get_projects_by_category(
+        &self,
+        category: ManagedBuffer,
+    ) -> MultiValueEncoded<ProjectId<Self::Api>> {
+        let mut ids = ManagedVec::new();
+        for id in self.projects_by_category(&category).iter() {
+            ids.push(id);
+        }
+
+        ids.into()
+    }
+
+    /// Returns the IDs of the projects that reward the given token,
+    /// either as their reward token or as one of their partner rewards.
+    #[label("metabonding-external-view")]
+    #[view(getProjectsByRewardToken)]
+    fn get_projects_by_reward_token(
+        &self,
+        token_id: TokenIdentifier,
+    ) -> MultiValueEncoded<ProjectId<Self::Api>> {
+        let mut ids = ManagedVec::new();
+        for id in self.projects_by_reward_token(&token_id).iter() {
+            ids.push(id);
+        }
+
+        ids.into()
+    }
+
+    /// Returns the IDs of the projects that produce rewards in the given week.
+    #[label("metabonding-external-view")]
+    #[view(getActiveProjects)]
+    fn get_active_projects(&self, week: Week) -> MultiValueEncoded<ProjectId<Self::Api>> {
+        let mut ids = ManagedVec::new();
+        for (id, project) in self.projects().iter() {
+            if project.start_week <= week && week <= project.end_week {
+                ids.push(id);
+            }
+        }
+
+        ids.into()
+    }
+
+    /// Returns the deposited rewards which were neither claimed nor swept after expiring,
+    /// followed by the leftover funds of each of the project's partner rewards.
+    /// Bonus rewards paid from the project's bonus pool count as claimed,
+    /// so the reward token's amount is 0 if they exceed the deposits' remainder.
+    #[label("metabonding-external-view")]
+    #[view(getRemainingRewards)]
+    fn get_remaining_rewards(
+        &self,
+        project_id: ProjectId<Self::Api>,
+    ) -> MultiValueEncoded<EsdtTokenPayment> {
+        let project = self.get_project_or_panic(&project_id);
+
+        let deposited = self.deposited_amount(&project_id).get();
+        let spent = self.total_claimed_project_rewards(&project_id).get()
+            + self.total_swept_project_rewards(&project_id).get();
+        let remaining_amount = if spent >= deposited {
+            BigUint::zero()
+        } else {
+            deposited - spent
+        };
+
+        let mut remaining = ManagedVec::new();
+        remaining.push(EsdtTokenPayment::new(
+            project.reward_token,
+            self.project_reward_token_nonce(&project_id).get(),
+            remaining_amount,
+        ));
+        for partner_reward in self.project_partner_rewards(&project_id).iter() {
+            remaining.push(EsdtTokenPayment::new(
+                partner_reward.token,
+                partner_reward.token_nonce,
+                partner_reward.leftover_funds,
+            ));
+        }
+
+        remaining.into()
+    }
+
+    /// Returns a project by ID. The results are, in order:
+    /// - reward_token
+    /// - delegation_reward_supply
+    /// - lkmex_reward_supply
+    /// - start_week
+    /// - end_week
+    #[view(getProjectById)]
+    fn get_project_by_id(
+        &self,
+        project_id: ProjectId<Self::Api>,
+    ) -> ProjectAsMultiResult<Self::Api> {
+        self.get_project_or_panic(&project_id).into_multiresult()
+    }
+
+    /// Returns a project, along with its metadata. The results are, in order:
+    /// - name
+    /// - website_hash
+    /// - reward_token
+    /// - delegation_reward_supply
+    /// - lkmex_reward_supply
+    /// - start_week
+    /// - end_week
+    ///
+    /// Projects added without metadata have an empty name and website hash.
+    #[label("metabonding-external-view")]
+    #[view(getProjectDetails)]
+    fn get_project_details(
+        &self,
+        project_id: ProjectId<Self::Api>,
+    ) -> ProjectDetailsAsMultiResult<Self::Api> {
+        let project = self.get_project_or_panic(&project_id);
+        let metadata_mapper = self.project_metadata(&project_id);
+        let (name, website_hash) = if metadata_mapper.is_empty() {
+            (
+                ManagedBuffer::new(),
+                ManagedByteArray::new_from_bytes(&[0u8; WEBSITE_HASH_LEN]),
+            )
+        } else {
+            let metadata = metadata_mapper.get();
+            (metadata.name, metadata.website_hash)
+        };
+
+        (
+            name,
+            website_hash,
+            project.reward_token,
+            project.delegation_reward_supply,
+            project.lkmex_reward_supply,
+            project.start_week,
+            project.end_week,
+        )
+            .into()
+    }
+
+    fn get_project_or_panic(&self, project_id: &ProjectId<Self::Api>) -> Project<Self::Api> {
+        self.projects()
+            .get(project_id)
+            .unwrap_or_else(|| sc_panic!(INVALID_PROJECT_ID_ERR_MSG))
+    }
+
+    #[view(getCurrentWeek)]
+    fn get_current_week(&self) -> Week {
+        let first_week_start_epoch = self.first_week_start_epoch().get();
+        let current_epoch = self.blockchain().get_block_epoch();
+
+        // will never overflow usize
+        unsafe {
+            ((current_epoch - first_week_start_epoch) / self.get_epochs_per_week())
+                .try_into()
+                .unwrap_unchecked()
+        }
+    }
+
+    #[view(getProjectIdByName)]
+    #[storage_mapper("projectIdByName")]
+    fn project_id_by_name(&self, name: &ManagedBuffer) -> SingleValueMapper<ProjectId<Self::Api>>;
+
+    #[storage_mapper("lastGeneratedProjectId")]
+    fn last_generated_project_id(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("pendingProjects")]
+    fn pending_projects(&self) -> MapMapper<ProjectId<Self::Api>, ProjectProposal<Self::Api>>;
+
+    #[view(getMaxActiveProjects)]
+    #[storage_mapper("maxActiveProjects")]
+    fn max_active_projects(&self) -> SingleValueMapper<usize>;
+
+    #[storage_mapper("projects")]
+    fn projects(&self) -> MapMapper<ProjectId<Self::Api>, Project<Self::Api>>;
+
+    #[view(getProjectOwner)]
+    #[storage_mapper("projectOwner")]
+    fn project_owner(&self, project_id: &ProjectId<Self::Api>)
+        -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getPendingProjectOwner)]
+    #[storage_mapper("pendingProjectOwner")]
+    fn pending_project_owner(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getTotalClaimedProjectRewards)]
+    #[storage_mapper("totalClaimedProjectRewards")]
+    fn total_claimed_project_rewards(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getTotalSweptProjectRewards)]
+    #[storage_mapper("totalSweptProjectRewards")]
+    fn total_swept_project_rewards(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getProjectUniqueClaimers)]
+    #[storage_mapper("projectUniqueClaimers")]
+    fn project_unique_claimers(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<usize>;
+
+    #[storage_mapper("projectClaimer")]
+    fn project_claimer(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        user: &ManagedAddress,
+    ) -> SingleValueMapper<bool>;
+
+    #[view(getProjectClaimsForWeek)]
+    #[storage_mapper("projectWeekClaims")]
+    fn project_week_claims(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        week: Week,
+    ) -> SingleValueMapper<usize>;
+
+    #[view(getCleanupBountyToken)]
+    #[storage_mapper("cleanupBountyToken")]
+    fn cleanup_bounty_token(&self) -> SingleValueMapper<EgldOrEsdtTokenIdentifier>;
+
+    #[view(getCleanupBountyAmount)]
+    #[storage_mapper("cleanupBountyAmount")]
+    fn cleanup_bounty_amount(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getCleanupBountyPool)]
+    #[storage_mapper("cleanupBountyPool")]
+    fn cleanup_bounty_pool(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("leftoverProjectFunds")]
+    fn leftover_project_funds(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("lastSweptWeek")]
+    fn last_swept_week(&self, project_id: &ProjectId<Self::Api>) -> SingleValueMapper<Week>;
+
+    #[view(areProjectClaimsPaused)]
+    #[storage_mapper("projectClaimsPaused")]
+    fn project_claims_paused(&self, project_id: &ProjectId<Self::Api>) -> SingleValueMapper<bool>;
+
+    /// The address that paused the project's claims, or the SC's address if they were paused by the circuit breaker
+    #[view(getProjectClaimsPausedBy)]
+    #[storage_mapper("projectClaimsPausedBy")]
+    fn project_claims_paused_by(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getPausedProjectRewards)]
+    #[storage_mapper("pausedProjectRewards")]
+    fn paused_project_rewards(
+        &self,
+        user: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    /// The user's kept partner rewards, in the order of the project's partner rewards.
+    /// They stay part of the partner rewards' leftover funds until claimed.
+    #[view(getPausedPartnerRewards)]
+    #[storage_mapper("pausedPartnerRewards")]
+    fn paused_partner_rewards(
+        &self,
+        user: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<ManagedVec<BigUint>>;
+
+    #[view(getTotalPausedProjectRewards)]
+    #[storage_mapper("totalPausedProjectRewards")]
+    fn total_paused_project_rewards(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    /// Claimed rewards of projects with locked rewards, not yet locked through claimLockedRewards
+    #[view(getPendingLockedRewards)]
+    #[storage_mapper("pendingLockedRewards")]
+    fn pending_locked_rewards(
+        &self,
+        user: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getTotalPendingLockedRewards)]
+    #[storage_mapper("totalPendingLockedRewards")]
+    fn total_pending_locked_rewards(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getProjectLkmexFactorBps)]
+    #[storage_mapper("projectLkmexFactorBps")]
+    fn project_lkmex_factor_bps(&self, project_id: &ProjectId<Self::Api>)
+        -> SingleValueMapper<u64>;
+
+    #[view(getProjectDistributionMode)]
+    #[storage_mapper("projectDistributionMode")]
+    fn project_distribution_mode(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<DistributionMode>;
+
+    #[view(isProjectEnergyWeighted)]
+    #[storage_mapper("projectEnergyWeighted")]
+    fn project_energy_weighted(&self, project_id: &ProjectId<Self::Api>)
+        -> SingleValueMapper<bool>;
+
+    #[view(getProjectStartOffsetEpochs)]
+    #[storage_mapper("projectStartOffsetEpochs")]
+    fn project_start_offset_epochs(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<Epoch>;
+
+    #[view(getProjectEndOffsetEpochs)]
+    #[storage_mapper("projectEndOffsetEpochs")]
+    fn project_end_offset_epochs(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<Epoch>;
+
+    #[view(getProjectEmissionCurve)]
+    #[storage_mapper("projectWeekWeights")]
+    fn project_week_weights(&self, project_id: &ProjectId<Self::Api>) -> VecMapper<u32>;
+
+    #[storage_mapper("projectMetadata")]
+    fn project_metadata(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<ProjectMetadata<Self::Api>>;
+
+    #[view(getProjectClaimFee)]
+    #[storage_mapper("projectClaimFeeBps")]
+    fn project_claim_fee_bps(&self, project_id: &ProjectId<Self::Api>) -> SingleValueMapper<u64>;
+
+    #[view(getProjectCollectedFees)]
+    #[storage_mapper("projectCollectedFees")]
+    fn project_collected_fees(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getProjectMaxRewardPerUserPerWeek)]
+    #[storage_mapper("projectMaxRewardPerUserPerWeek")]
+    fn project_max_reward_per_user_per_week(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getProjectStreakBonusBpsPerWeek)]
+    #[storage_mapper("projectStreakBonusBpsPerWeek")]
+    fn project_streak_bonus_bps_per_week(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<u64>;
+
+    #[view(getProjectMaxStreakBonusBps)]
+    #[storage_mapper("projectMaxStreakBonusBps")]
+    fn project_max_streak_bonus_bps(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<u64>;
+
+    #[view(getProjectMinRewardPerUser)]
+    #[storage_mapper("projectMinRewardPerUser")]
+    fn project_min_reward_per_user(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    /// Part of the project's leftover funds, reserved for streak bonuses, reward floors and bonus weeks
+    #[view(getProjectBonusPool)]
+    #[storage_mapper("projectBonusPool")]
+    fn project_bonus_pool(&self, project_id: &ProjectId<Self::Api>) -> SingleValueMapper<BigUint>;
+
+    #[view(getProjectRoundingRemainders)]
+    #[storage_mapper("projectRoundingRemainders")]
+    fn project_rounding_remainders(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("claimedProjectWeekRewards")]
+    fn claimed_project_week_rewards(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        week: Week,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("delegationRoundingRemainder")]
+    fn delegation_rounding_remainder(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        week: Week,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("lkmexRoundingRemainder")]
+    fn lkmex_rounding_remainder(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        week: Week,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getProjectWeekCappedRewards)]
+    #[storage_mapper("projectWeekCappedRewards")]
+    fn project_week_capped_rewards(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        week: Week,
+    ) -> SingleValueMapper<BigUint>;
+
+    /// The last week whose capped rewards were refunded
+    #[view(getLastCappedRewardsRefundWeek)]
+    #[storage_mapper("lastCappedRewardsRefundWeek")]
+    fn last_capped_rewards_refund_week(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<Week>;
+
+    #[view(getProjectCappedRewards)]
+    #[storage_mapper("projectCappedRewards")]
+    fn project_capped_rewards(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getProjectCategory)]
+    #[storage_mapper("projectCategory")]
+    fn project_category(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<ManagedBuffer>;
+
+    #[storage_mapper("projectsByCategory")]
+    fn projects_by_category(
+        &self,
+        category: &ManagedBuffer,
+    ) -> UnorderedSetMapper<ProjectId<Self::Api>>;
+
+    #[view(getWeekActiveProjects)]
+    #[storage_mapper("weekActiveProjects")]
+    fn week_active_projects(&self, week: Week) -> VecMapper<ProjectId<Self::Api>>;
+
+    #[storage_mapper("firstSnapshotWeek")]
+    fn first_snapshot_week(&self) -> SingleValueMapper<Week>;
+
+    #[storage_mapper("lastSnapshotWeek")]
+    fn last_snapshot_week(&self) -> SingleValueMapper<Week>;
+
+    #[storage_mapper("projectsByRewardToken")]
+    fn projects_by_reward_token(
+        &self,
+        token_id: &TokenIdentifier,
+    ) -> UnorderedSetMapper<ProjectId<Self::Api>>;
+
+    #[view(isProjectClaimantWhitelistEnabled)]
+    #[storage_mapper("projectClaimantWhitelistEnabled")]
+    fn project_claimant_whitelist_enabled(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<bool>;
+
+    #[view(getProjectClaimants)]
+    #[storage_mapper("projectClaimantWhitelist")]
+    fn project_claimant_whitelist(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[view(getProjectCancelledFromWeek)]
+    #[storage_mapper("projectCancelledFromWeek")]
+    fn project_cancelled_from_week(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<Week>;
+
+    #[view(getPendingProjectExtension)]
+    #[storage_mapper("pendingProjectExtension")]
+    fn pending_project_extension(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<Week>;
+
+    #[view(getDepositedAmount)]
+    #[storage_mapper("depositedAmount")]
+    fn deposited_amount(&self, project_id: &ProjectId<Self::Api>) -> SingleValueMapper<BigUint>;
+
+    /// The nonce of the reward token, for projects that give locked tokens (MetaESDT) or SFTs as reward.
+    /// Set by the first deposit, unless set through setProjectRewardTokenNonce. 0 for fungible tokens.
+    #[view(getProjectRewardTokenNonce)]
+    #[storage_mapper("projectRewardTokenNonce")]
+    fn project_reward_token_nonce(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<u64>;
+
+    #[view(getProjectPartnerRewards)]
+    #[storage_mapper("projectPartnerRewards")]
+    fn project_partner_rewards(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> VecMapper<PartnerReward<Self::Api>>;
+
+    #[view(getProjectRewardsLockEpochs)]
+    #[storage_mapper("projectRewardsLockEpochs")]
+    fn project_rewards_lock_epochs(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<u64>;
+
+    /// Set if the reward token nonce was set through setProjectRewardTokenNonce
+    #[view(isProjectRewardTokenNonceFixed)]
+    #[storage_mapper("projectRewardTokenNonceFixed")]
+    fn project_reward_token_nonce_fixed(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<bool>;
+
+    #[view(getListingFeeEscrow)]
+    #[storage_mapper("listingFeeEscrow")]
+    fn listing_fee_escrow(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<EgldOrEsdtTokenPayment<Self::Api>>;
+
+    #[view(isOwnerDepositAllowed)]
+    #[storage_mapper("ownerDepositAllowed")]
+    fn owner_deposit_allowed(&self, project_id: &ProjectId<Self::Api>) -> SingleValueMapper<bool>;
+
+    /// Projects whose rewards were fully deposited
+    #[view(getFundedProjects)]
+    #[storage_mapper("fundedProjects")]
+    fn funded_projects(&self) -> UnorderedSetMapper<ProjectId<Self::Api>>;
+
+    /// Legacy storage, only read on upgrade, to move the flags into fundedProjects
+    #[storage_mapper("rewardsDeposited")]
+    fn legacy_rewards_deposited(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> SingleValueMapper<bool>;
 }
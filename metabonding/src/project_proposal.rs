@@ -0,0 +1,189 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::{
+    admin::Role,
+    audit_log::AdminActionKind,
+    project::{Project, ProjectId},
+    rewards::Week,
+};
+
+#[derive(TypeAbi, TopEncode, TopDecode)]
+pub struct ProjectProposal<M: ManagedTypeApi> {
+    pub proposer: ManagedAddress<M>,
+    pub project: Project<M>,
+    pub fee_token: EgldOrEsdtTokenIdentifier<M>,
+    pub fee_amount: BigUint<M>,
+}
+
+#[elrond_wasm::module]
+pub trait ProjectProposalModule:
+    crate::project::ProjectModule
+    + crate::common_storage::CommonStorageModule
+    + crate::events::EventsModule
+    + crate::global_pause::GlobalPauseModule
+{
+    /// Sets the fee paid by teams when proposing a project. An amount of 0 disables the fee.
+    /// The token can only be changed while there are no pending proposals.
+    #[only_owner]
+    #[endpoint(setListingFee)]
+    fn set_listing_fee(&self, token_id: EgldOrEsdtTokenIdentifier, amount: BigUint) {
+        self.require_no_timelock();
+
+        self.apply_listing_fee(token_id, amount);
+    }
+
+    fn apply_listing_fee(&self, token_id: EgldOrEsdtTokenIdentifier, amount: BigUint) {
+        require!(token_id.is_valid(), "Invalid token");
+        require!(
+            self.pending_projects().is_empty(),
+            "Cannot change fee while there are pending projects"
+        );
+
+        self.listing_fee_token().set(&token_id);
+        self.listing_fee_amount().set(&amount);
+        self.log_admin_action(AdminActionKind::ListingFeeUpdated(token_id, amount));
+    }
+
+    /// Proposes a new project, which is only added after the owner approves it.
+    /// The caller becomes the project owner. The arguments are the same as for addProject.
+    /// If a listing fee is set, it must be paid along with the proposal, and it is held in escrow.
+    /// It is refunded if the proposal is rejected, or once the approved project's rewards are deposited.
+    /// If the rewards are not deposited before the project's start week, it is forfeited to the treasury.
+    #[payable("*")]
+    #[endpoint(proposeProject)]
+    fn propose_project(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        reward_token: TokenIdentifier,
+        reward_supply: BigUint,
+        start_week: Week,
+        duration_weeks: Week,
+        lkmex_rewards_percentage: u64,
+    ) {
+        self.require_not_globally_paused();
+        self.require_valid_project_id(&project_id);
+        require!(
+            !self.projects().contains_key(&project_id)
+                && !self.pending_projects().contains_key(&project_id),
+            "ID already in use"
+        );
+        require!(
+            start_week > self.get_current_week(),
+            "Start week must be in the future"
+        );
+
+        let project = self.create_project(
+            reward_token,
+            reward_supply,
+            start_week,
+            duration_weeks,
+            lkmex_rewards_percentage,
+        );
+
+        let (fee_token, fee_amount) = self.call_value().egld_or_single_fungible_esdt();
+        let required_fee = self.listing_fee_amount().get();
+        if required_fee > 0 {
+            require!(
+                fee_token == self.listing_fee_token().get() && fee_amount == required_fee,
+                "Invalid listing fee payment"
+            );
+        } else {
+            require!(fee_amount == 0, "No listing fee required");
+        }
+
+        let proposal = ProjectProposal {
+            proposer: self.blockchain().get_caller(),
+            project,
+            fee_token,
+            fee_amount,
+        };
+        let _ = self.pending_projects().insert(project_id, proposal);
+    }
+
+    /// Adds the proposed project. The listing fee stays in escrow until the project's rewards are deposited.
+    /// Proposals whose start week is no longer in the future can only be rejected.
+    #[endpoint(approveProject)]
+    fn approve_project(&self, project_id: ProjectId<Self::Api>) {
+        self.require_caller_role(Role::ProjectManager);
+
+        let proposal = self.take_pending_project_or_panic(&project_id);
+        require!(
+            proposal.project.start_week > self.get_current_week(),
+            "Start week must be in the future"
+        );
+        if proposal.fee_amount > 0 {
+            self.listing_fee_escrow(&project_id)
+                .set(&EgldOrEsdtTokenPayment::new(
+                    proposal.fee_token,
+                    0,
+                    proposal.fee_amount,
+                ));
+        }
+
+        self.insert_project(project_id, proposal.proposer, proposal.project);
+    }
+
+    /// Sends the escrowed listing fee of a project whose rewards were not deposited before its start week
+    /// to the treasury. May be called by anyone.
+    #[endpoint(forfeitListingFee)]
+    fn forfeit_listing_fee(&self, project_id: ProjectId<Self::Api>) {
+        let project = self.get_project_or_panic(&project_id);
+        require!(
+            !self.listing_fee_escrow(&project_id).is_empty(),
+            "No listing fee in escrow"
+        );
+        require!(
+            !self.funded_projects().contains(&project_id)
+                && self.get_current_week() >= project.start_week,
+            "Listing fee cannot be forfeited"
+        );
+
+        self.forfeit_listing_fee_escrow(&project_id);
+    }
+
+    /// Discards the proposal and refunds the listing fee to the proposer.
+    #[endpoint(rejectProject)]
+    fn reject_project(&self, project_id: ProjectId<Self::Api>) {
+        self.require_caller_role(Role::ProjectManager);
+
+        let proposal = self.take_pending_project_or_panic(&project_id);
+        if proposal.fee_amount > 0 {
+            self.send().direct(
+                &proposal.proposer,
+                &proposal.fee_token,
+                0,
+                &proposal.fee_amount,
+            );
+        }
+
+        self.log_admin_action(AdminActionKind::ProjectProposalRejected(project_id));
+    }
+
+    fn take_pending_project_or_panic(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+    ) -> ProjectProposal<Self::Api> {
+        self.pending_projects()
+            .remove(project_id)
+            .unwrap_or_else(|| sc_panic!("Unknown project proposal"))
+    }
+
+    #[view(getPendingProjectIds)]
+    fn get_pending_project_ids(&self) -> MultiValueEncoded<ProjectId<Self::Api>> {
+        let mut all_ids = ManagedVec::new();
+        for id in self.pending_projects().keys() {
+            all_ids.push(id);
+        }
+
+        all_ids.into()
+    }
+
+    #[view(getListingFeeToken)]
+    #[storage_mapper("listingFeeToken")]
+    fn listing_fee_token(&self) -> SingleValueMapper<EgldOrEsdtTokenIdentifier>;
+
+    #[view(getListingFeeAmount)]
+    #[storage_mapper("listingFeeAmount")]
+    fn listing_fee_amount(&self) -> SingleValueMapper<BigUint>;
+}
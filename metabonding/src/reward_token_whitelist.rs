@@ -0,0 +1,132 @@
+elrond_wasm::imports!();
+
+use crate::{audit_log::AdminActionKind, project::EGLD_REWARD_TOKEN_ID};
+
+// erd1qqqqqqqqqqqqqqqpqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqzllls8a5w6u
+const ESDT_SYSTEM_SC_ADDRESS: [u8; 32] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 255,
+    255,
+];
+static ALLOWED_TOKEN_TYPES: &[&[u8]] = &[b"FungibleESDT", b"SemiFungibleESDT", b"MetaESDT"];
+static NOT_PAUSED_PROPERTY: &[u8] = b"IsPaused-false";
+const TOKEN_TYPE_PROPERTY_INDEX: usize = 1;
+
+pub mod esdt_system_sc_proxy {
+    elrond_wasm::imports!();
+
+    #[elrond_wasm::proxy]
+    pub trait EsdtSystemScProxy {
+        #[endpoint(getTokenProperties)]
+        fn get_token_properties(
+            &self,
+            token_id: TokenIdentifier,
+        ) -> MultiValueEncoded<ManagedBuffer>;
+    }
+}
+
+/// While enabled, projects may only be added with whitelisted reward tokens.
+/// Tokens are only whitelisted after the ESDT system SC confirms they exist, are fungible, semi-fungible or meta ESDTs,
+/// and are not paused. A `rewardTokenWhitelistFailed` event is emitted otherwise.
+#[elrond_wasm::module]
+pub trait RewardTokenWhitelistModule:
+    crate::audit_log::AuditLogModule + crate::events::EventsModule
+{
+    #[only_owner]
+    #[endpoint(setRewardTokenWhitelistEnabled)]
+    fn set_reward_token_whitelist_enabled(&self, enabled: bool) {
+        self.reward_token_whitelist_enabled().set(enabled);
+        self.log_admin_action(AdminActionKind::RewardTokenWhitelistEnabledChanged(enabled));
+    }
+
+    /// Queries the token's properties from the ESDT system SC, which is on the metachain.
+    /// The token is only whitelisted in the callback.
+    #[only_owner]
+    #[endpoint(addRewardTokenToWhitelist)]
+    fn add_reward_token_to_whitelist(&self, token_id: TokenIdentifier) {
+        require!(token_id.is_valid_esdt_identifier(), "Invalid token");
+        require!(
+            !self.reward_token_whitelist().contains(&token_id),
+            "Token already whitelisted"
+        );
+
+        self.esdt_system_sc_proxy(ManagedAddress::from(&ESDT_SYSTEM_SC_ADDRESS))
+            .get_token_properties(token_id.clone())
+            .async_call()
+            .with_callback(self.callbacks().reward_token_properties_callback(token_id))
+            .call_and_exit()
+    }
+
+    #[callback]
+    fn reward_token_properties_callback(
+        &self,
+        token_id: TokenIdentifier,
+        #[call_result] result: ManagedAsyncCallResult<MultiValueEncoded<ManagedBuffer>>,
+    ) {
+        let properties = match result {
+            ManagedAsyncCallResult::Ok(properties) => properties.to_vec(),
+            ManagedAsyncCallResult::Err(err) => {
+                self.reward_token_whitelist_failed_event(&token_id, &err.err_msg);
+                return;
+            }
+        };
+
+        let is_allowed_type = match properties.try_get(TOKEN_TYPE_PROPERTY_INDEX) {
+            Some(token_type) => ALLOWED_TOKEN_TYPES
+                .iter()
+                .any(|allowed_type| *token_type == ManagedBuffer::new_from_bytes(allowed_type)),
+            None => false,
+        };
+        let not_paused_property = ManagedBuffer::new_from_bytes(NOT_PAUSED_PROPERTY);
+        let not_paused = properties
+            .iter()
+            .any(|property| *property == not_paused_property);
+        if !is_allowed_type || !not_paused {
+            self.reward_token_whitelist_failed_event(&token_id, &ManagedBuffer::new());
+            return;
+        }
+
+        let _ = self.reward_token_whitelist().insert(token_id.clone());
+        self.log_admin_action_by(
+            self.blockchain().get_owner_address(),
+            AdminActionKind::RewardTokenWhitelisted(token_id),
+        );
+    }
+
+    #[only_owner]
+    #[endpoint(removeRewardTokenFromWhitelist)]
+    fn remove_reward_token_from_whitelist(&self, token_id: TokenIdentifier) {
+        require!(
+            self.reward_token_whitelist().swap_remove(&token_id),
+            "Token not whitelisted"
+        );
+        self.log_admin_action(AdminActionKind::RewardTokenRemovedFromWhitelist(token_id));
+    }
+
+    /// EGLD rewards are always allowed
+    fn require_reward_token_allowed(&self, token_id: &TokenIdentifier) {
+        if !self.reward_token_whitelist_enabled().get()
+            || token_id.as_managed_buffer() == &ManagedBuffer::new_from_bytes(EGLD_REWARD_TOKEN_ID)
+        {
+            return;
+        }
+
+        require!(
+            self.reward_token_whitelist().contains(token_id),
+            "Reward token not whitelisted"
+        );
+    }
+
+    #[proxy]
+    fn esdt_system_sc_proxy(
+        &self,
+        sc_address: ManagedAddress,
+    ) -> esdt_system_sc_proxy::Proxy<Self::Api>;
+
+    #[view(isRewardTokenWhitelistEnabled)]
+    #[storage_mapper("rewardTokenWhitelistEnabled")]
+    fn reward_token_whitelist_enabled(&self) -> SingleValueMapper<bool>;
+
+    #[view(getRewardTokenWhitelist)]
+    #[storage_mapper("rewardTokenWhitelist")]
+    fn reward_token_whitelist(&self) -> UnorderedSetMapper<TokenIdentifier>;
+}
@@ -1,16 +1,143 @@
 elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 
-use crate::project::{Project, ProjectId, PROJECT_EXPIRATION_WEEKS};
+use crate::{
+    admin::Role,
+    audit_log::AdminActionKind,
+    events::{
+        CheckpointAddedEventData, CheckpointUpdatedEventData, ProjectExtendedEventData,
+        RewardsDepositedEventData,
+    },
+    oracle::OracleSignature,
+    project::{DistributionMode, Epoch, Project, ProjectId, LKMEX_FACTOR_PRECISION},
+    signed_message::{CHECKPOINT_SQRT_SUMS_TAG, CHECKPOINT_TAG},
+    validation::{DataHash, MerkleHash, Signature},
+};
+use elrond_wasm::elrond_codec::{
+    DecodeErrorHandler, EncodeErrorHandler, NestedDecodeInput, TopDecodeInput, TopEncodeOutput,
+};
+
+const WEEKS_IN_YEAR: u64 = 52;
+const APR_PRECISION_BPS: u64 = 10_000;
+const BONUS_WEEK_BASE_BPS: u64 = 10_000;
 
 pub type Week = usize;
+pub type CheckpointArgs<M> = MultiValue3<Week, BigUint<M>, BigUint<M>>;
 pub type PrettyRewards<M> =
     MultiValueEncoded<M, MultiValue3<ProjectId<M>, TokenIdentifier<M>, BigUint<M>>>;
+pub type MultiUserRewards<M> = MultiValueEncoded<
+    M,
+    MultiValue4<ManagedAddress<M>, ProjectId<M>, TokenIdentifier<M>, BigUint<M>>,
+>;
 
-#[derive(TypeAbi, TopEncode, TopDecode)]
+/// Checkpoints added before the energy model (V1) only contain the first two fields.
+/// They are decoded with zero total energy, which is also how V2 checkpoints without energy are encoded.
+/// Likewise, the sums of square roots (V3) are only encoded if set, and are decoded as zero otherwise.
+#[derive(TypeAbi)]
 pub struct RewardsCheckpoint<M: ManagedTypeApi> {
     pub total_delegation_supply: BigUint<M>,
     pub total_lkmex_staked: BigUint<M>,
+    pub total_energy: BigUint<M>,
+    pub total_delegation_sqrt_sum: BigUint<M>,
+    pub total_lkmex_sqrt_sum: BigUint<M>,
+}
+
+/// A user's reward for a project in a given week. If the project has a per-user cap,
+/// capped_amount is the part of the computed reward that exceeded it.
+/// The remainders are the fractions of a token lost to rounding, for each of the two pools.
+/// The partner amounts are the user's rewards of each of the project's partner tokens, in their order.
+pub struct WeeklyReward<M: ManagedTypeApi> {
+    pub amount: BigUint<M>,
+    pub capped_amount: BigUint<M>,
+    pub delegation_remainder: RoundingRemainder<M>,
+    pub lkmex_remainder: RoundingRemainder<M>,
+    pub partner_amounts: ManagedVec<M, BigUint<M>>,
+}
+
+pub struct RoundingRemainder<M: ManagedTypeApi> {
+    pub numerator: BigUint<M>,
+    pub denominator: BigUint<M>,
+}
+
+/// Checkpoints without a stored status (i.e. added before statuses were introduced) are finalized
+#[derive(TypeAbi, TopEncode, TopDecode, PartialEq, Eq, Clone, Copy)]
+pub enum CheckpointStatus {
+    Finalized,
+    Pending,
+}
+
+impl<M: ManagedTypeApi> RewardsCheckpoint<M> {
+    #[inline]
+    pub fn has_energy(&self) -> bool {
+        self.total_energy > 0
+    }
+
+    #[inline]
+    pub fn has_sqrt_sums(&self) -> bool {
+        self.total_delegation_sqrt_sum > 0 || self.total_lkmex_sqrt_sum > 0
+    }
+}
+
+impl<M: ManagedTypeApi> TopEncode for RewardsCheckpoint<M> {
+    fn top_encode_or_handle_err<O, H>(&self, output: O, h: H) -> Result<(), H::HandledErr>
+    where
+        O: TopEncodeOutput,
+        H: EncodeErrorHandler,
+    {
+        let mut buffer = output.start_nested_encode();
+        self.total_delegation_supply
+            .dep_encode_or_handle_err(&mut buffer, h)?;
+        self.total_lkmex_staked
+            .dep_encode_or_handle_err(&mut buffer, h)?;
+        if self.has_energy() || self.has_sqrt_sums() {
+            self.total_energy.dep_encode_or_handle_err(&mut buffer, h)?;
+        }
+        if self.has_sqrt_sums() {
+            self.total_delegation_sqrt_sum
+                .dep_encode_or_handle_err(&mut buffer, h)?;
+            self.total_lkmex_sqrt_sum
+                .dep_encode_or_handle_err(&mut buffer, h)?;
+        }
+        output.finalize_nested_encode(buffer);
+
+        Ok(())
+    }
+}
+
+impl<M: ManagedTypeApi> TopDecode for RewardsCheckpoint<M> {
+    fn top_decode_or_handle_err<I, H>(input: I, h: H) -> Result<Self, H::HandledErr>
+    where
+        I: TopDecodeInput,
+        H: DecodeErrorHandler,
+    {
+        let mut buffer = input.into_nested_buffer();
+        let total_delegation_supply = BigUint::dep_decode_or_handle_err(&mut buffer, h)?;
+        let total_lkmex_staked = BigUint::dep_decode_or_handle_err(&mut buffer, h)?;
+        let total_energy = if buffer.is_depleted() {
+            BigUint::zero()
+        } else {
+            BigUint::dep_decode_or_handle_err(&mut buffer, h)?
+        };
+        let (total_delegation_sqrt_sum, total_lkmex_sqrt_sum) = if buffer.is_depleted() {
+            (BigUint::zero(), BigUint::zero())
+        } else {
+            (
+                BigUint::dep_decode_or_handle_err(&mut buffer, h)?,
+                BigUint::dep_decode_or_handle_err(&mut buffer, h)?,
+            )
+        };
+        if !buffer.is_depleted() {
+            return Err(h.handle_error(DecodeError::INPUT_TOO_LONG));
+        }
+
+        Ok(RewardsCheckpoint {
+            total_delegation_supply,
+            total_lkmex_staked,
+            total_energy,
+            total_delegation_sqrt_sum,
+            total_lkmex_sqrt_sum,
+        })
+    }
 }
 
 #[elrond_wasm::module]
@@ -21,48 +148,780 @@ pub trait RewardsModule:
     + crate::common_storage::CommonStorageModule
     + crate::math::MathModule
     + crate::validation::ValidationModule
+    + crate::events::EventsModule
+    + crate::global_pause::GlobalPauseModule
+    + crate::oracle::OracleModule
 {
     /// Adds a rewards checkpoint for the given Week. Only one checkpoint per week is allowed.
     /// Checkpoints have to be added in order, and only if the current week is equal to the given week
-    ///     or the given week is in the past.
-    /// Only the SC owner or the signer may add checkpoints. Arguments:
+    ///     or the given week is in the past. Weeks may be skipped, in which case no rewards
+    ///     are distributed for them.
+    /// If an oracle quorum is set, the checkpoint must be signed by enough oracles.
+    /// Otherwise, only a checkpoint submitter or a signer may add checkpoints. Arguments:
     /// - week - the week for which the checkpoint is added
     /// - total_delegation_supply - The total amount of staked EGLD in the Delegation SC
     /// - total_lkmex_staked - The total LKMEX staked in the Metabonding-Staking SC
+    /// - oracle_signatures - pairs of (oracle, signature over the other arguments)
     #[endpoint(addRewardsCheckpoint)]
     fn add_rewards_checkpoint(
         &self,
         week: Week,
         total_delegation_supply: BigUint,
         total_lkmex_staked: BigUint,
+        oracle_signatures: MultiValueEncoded<OracleSignature<Self::Api>>,
     ) {
-        self.require_caller_owner_or_signer();
+        let message = self.build_checkpoint_message(
+            CHECKPOINT_TAG,
+            week,
+            &total_delegation_supply,
+            &total_lkmex_staked,
+        );
+        self.require_checkpoint_authorized(&message, oracle_signatures);
 
-        let last_checkpoint_week = self.get_last_checkpoint_week();
         let current_week = self.get_current_week();
+        self.add_checkpoint(
+            week,
+            total_delegation_supply,
+            total_lkmex_staked,
+            current_week,
+        );
+    }
+
+    /// Adds multiple checkpoints at once. Same rules apply as for addRewardsCheckpoint.
+    /// Arguments are triples of (week, total_delegation_supply, total_lkmex_staked)
+    /// Not available when an oracle quorum is set.
+    #[endpoint(addRewardsCheckpoints)]
+    fn add_rewards_checkpoints(&self, checkpoints: MultiValueEncoded<CheckpointArgs<Self::Api>>) {
         require!(
-            week == last_checkpoint_week + 1 && week <= current_week,
+            self.oracle_quorum().get() == 0,
+            "Checkpoints must be signed by oracles"
+        );
+        self.require_caller_checkpoint_submitter_or_signer();
+
+        let current_week = self.get_current_week();
+        for checkpoint in checkpoints {
+            let (week, total_delegation_supply, total_lkmex_staked) = checkpoint.into_tuple();
+            self.add_checkpoint(
+                week,
+                total_delegation_supply,
+                total_lkmex_staked,
+                current_week,
+            );
+        }
+    }
+
+    /// Corrects the totals of an existing checkpoint.
+    /// Only allowed while no user has claimed rewards for that week.
+    /// Same authorization rules apply as for addRewardsCheckpoint, except that the oracles sign
+    /// the week along with the new totals, with the week's number of past updates as the nonce.
+    #[endpoint(updateRewardsCheckpoint)]
+    fn update_rewards_checkpoint(
+        &self,
+        week: Week,
+        new_total_delegation_supply: BigUint,
+        new_total_lkmex_staked: BigUint,
+        oracle_signatures: MultiValueEncoded<OracleSignature<Self::Api>>,
+    ) {
+        self.require_not_globally_paused();
+        let update_nonce_mapper = self.checkpoint_update_nonce(week);
+        let update_nonce = update_nonce_mapper.get();
+        let message = self.build_checkpoint_update_message(
+            week,
+            update_nonce,
+            &new_total_delegation_supply,
+            &new_total_lkmex_staked,
+        );
+        self.require_checkpoint_authorized(&message, oracle_signatures);
+        require!(
+            !self.week_has_claims(week).get(),
+            "Rewards were already claimed for this week"
+        );
+
+        let old_checkpoint = self.get_checkpoint_or_panic(week);
+        let new_checkpoint = RewardsCheckpoint {
+            total_delegation_supply: new_total_delegation_supply.clone(),
+            total_lkmex_staked: new_total_lkmex_staked.clone(),
+            total_energy: old_checkpoint.total_energy,
+            total_delegation_sqrt_sum: old_checkpoint.total_delegation_sqrt_sum,
+            total_lkmex_sqrt_sum: old_checkpoint.total_lkmex_sqrt_sum,
+        };
+        self.set_checkpoint(week, new_checkpoint);
+        update_nonce_mapper.set(update_nonce + 1);
+
+        self.checkpoint_updated_event(
+            week,
+            &CheckpointUpdatedEventData {
+                old_total_delegation_supply: old_checkpoint.total_delegation_supply,
+                old_total_lkmex_staked: old_checkpoint.total_lkmex_staked,
+                new_total_delegation_supply,
+                new_total_lkmex_staked,
+            },
+        );
+        self.log_admin_action(AdminActionKind::CheckpointCorrected(week));
+    }
+
+    fn add_checkpoint(
+        &self,
+        week: Week,
+        total_delegation_supply: BigUint,
+        total_lkmex_staked: BigUint,
+        current_week: Week,
+    ) {
+        self.require_not_globally_paused();
+
+        let last_checkpoint_week = self.get_last_checkpoint_week();
+        require!(
+            week > last_checkpoint_week && week <= current_week,
             "Invalid checkpoint week"
         );
 
+        self.checkpoint_added_event(
+            week,
+            &CheckpointAddedEventData {
+                total_delegation_supply: total_delegation_supply.clone(),
+                total_lkmex_staked: total_lkmex_staked.clone(),
+            },
+        );
+
         let checkpoint = RewardsCheckpoint {
             total_delegation_supply,
             total_lkmex_staked,
+            total_energy: BigUint::zero(),
+            total_delegation_sqrt_sum: BigUint::zero(),
+            total_lkmex_sqrt_sum: BigUint::zero(),
         };
-        self.rewards_checkpoints().push(&checkpoint);
+        self.set_checkpoint(week, checkpoint);
+        self.last_checkpoint_week().set(week);
+        self.snapshot_active_projects(week);
+        self.log_admin_action(AdminActionKind::CheckpointAdded(week));
+
+        let dispute_epochs = self.checkpoint_dispute_epochs().get();
+        if dispute_epochs > 0 {
+            let current_epoch = self.blockchain().get_block_epoch();
+            self.checkpoint_status(week).set(CheckpointStatus::Pending);
+            self.checkpoint_finalization_epoch(week)
+                .set(current_epoch + dispute_epochs);
+        }
+    }
+
+    /// Sets the number of epochs for which new checkpoints stay pending.
+    /// Rewards for pending checkpoints can't be claimed, but the checkpoints can still be corrected.
+    #[only_owner]
+    #[endpoint(setCheckpointDisputeEpochs)]
+    fn set_checkpoint_dispute_epochs(&self, dispute_epochs: Epoch) {
+        self.checkpoint_dispute_epochs().set(dispute_epochs);
+        self.log_admin_action(AdminActionKind::CheckpointDisputeEpochsChanged(
+            dispute_epochs,
+        ));
+    }
+
+    /// Changes the epoch in which week 0 starts and the number of epochs in a week.
+    /// Only allowed before the first checkpoint, as the schedule also sets the past weeks' epochs,
+    /// from which the claim and deposit deadlines are computed.
+    #[only_owner]
+    #[endpoint(setWeekSchedule)]
+    fn set_week_schedule(&self, first_week_start_epoch: Epoch, epochs_per_week: Epoch) {
+        self.require_no_timelock();
+
+        self.apply_week_schedule(first_week_start_epoch, epochs_per_week);
+    }
+
+    fn apply_week_schedule(&self, first_week_start_epoch: Epoch, epochs_per_week: Epoch) {
+        require!(epochs_per_week > 0, "Invalid week length");
+        require!(
+            first_week_start_epoch <= self.blockchain().get_block_epoch(),
+            "Invalid first week start epoch"
+        );
+        require!(
+            self.get_last_checkpoint_week() == 0,
+            "Checkpoints already added"
+        );
+
+        self.first_week_start_epoch().set(first_week_start_epoch);
+        self.epochs_per_week().set(epochs_per_week);
+
+        self.log_admin_action(AdminActionKind::WeekScheduleChanged(
+            first_week_start_epoch,
+            epochs_per_week,
+        ));
+    }
+
+    /// Returns the epoch in which week 0 starts and the number of epochs in a week
+    #[view(getWeekSchedule)]
+    fn get_week_schedule(&self) -> MultiValue2<Epoch, Epoch> {
+        (
+            self.first_week_start_epoch().get(),
+            self.get_epochs_per_week(),
+        )
+            .into()
+    }
+
+    /// Finalizes a pending checkpoint before its dispute window ends
+    #[endpoint(finalizeCheckpoint)]
+    fn finalize_checkpoint(&self, week: Week) {
+        self.require_caller_role(Role::CheckpointSubmitter);
+        self.require_not_globally_paused();
+
+        require!(
+            self.get_checkpoint_status(week) == CheckpointStatus::Pending,
+            "Checkpoint not pending"
+        );
+
+        self.checkpoint_status(week).clear();
+        self.checkpoint_finalization_epoch(week).clear();
+        self.log_admin_action(AdminActionKind::CheckpointFinalized(week));
     }
 
-    /// Deposits rewards for the given project. The full amount has to be deposited all at once.
+    /// A pending checkpoint is finalized automatically once its dispute window ends
+    #[view(getCheckpointStatus)]
+    fn get_checkpoint_status(&self, week: Week) -> CheckpointStatus {
+        require!(self.has_checkpoint(week), "No checkpoint for week");
+
+        let status = self.checkpoint_status(week).get();
+        if status == CheckpointStatus::Pending
+            && self.blockchain().get_block_epoch() >= self.checkpoint_finalization_epoch(week).get()
+        {
+            return CheckpointStatus::Finalized;
+        }
+
+        status
+    }
+
+    fn is_checkpoint_finalized(&self, week: Week) -> bool {
+        self.get_checkpoint_status(week) == CheckpointStatus::Finalized
+    }
+
+    /// Sets the total energy for the given week's checkpoint, which enables
+    /// energy-weighted rewards for that week. For those weeks, energy-weighted projects use
+    /// the users' energy registered through registerSignedEnergy.
+    /// Only allowed while no user has claimed rewards for that week.
+    #[endpoint(setCheckpointTotalEnergy)]
+    fn set_checkpoint_total_energy(&self, week: Week, total_energy: BigUint) {
+        self.require_caller_checkpoint_submitter_or_signer();
+        self.require_not_globally_paused();
+        require!(
+            !self.week_has_claims(week).get(),
+            "Rewards were already claimed for this week"
+        );
+
+        let mut checkpoint = self.get_checkpoint_or_panic(week);
+        checkpoint.total_energy = total_energy;
+        self.set_checkpoint(week, checkpoint);
+        self.log_admin_action(AdminActionKind::CheckpointTotalEnergySet(week));
+    }
+
+    /// Registers the caller's energy for the given week, as signed by a signer.
+    /// It is used instead of the signed LKMEX amount by the energy-weighted projects,
+    /// in the weeks whose checkpoint includes the total energy, so it has to be registered before claiming the week.
+    /// Can only be registered once per week.
+    #[endpoint(registerSignedEnergy)]
+    fn register_signed_energy(
+        &self,
+        week: Week,
+        user_energy: BigUint,
+        valid_until_epoch: Epoch,
+        signature: Signature<Self::Api>,
+    ) {
+        self.require_not_globally_paused();
+        require!(
+            self.get_checkpoint_or_panic(week).has_energy(),
+            "No total energy for week"
+        );
+        require!(
+            self.week_registered_energy(week).get() == 0,
+            "Energy registered on-chain for week"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let user_energy_mapper = self.user_week_energy(&caller, week);
+        require!(
+            user_energy_mapper.is_empty(),
+            "Energy already registered for week"
+        );
+
+        self.verify_user_energy_signature(
+            &caller,
+            week,
+            &user_energy,
+            valid_until_epoch,
+            &signature,
+        );
+        user_energy_mapper.set(&user_energy);
+    }
+
+    /// Sets the sums of the square roots of all users' amounts for the given week's checkpoint,
+    /// which enables quadratic rewards for that week. Only allowed while no user has claimed rewards for that week.
+    /// Each sum must be between the square root of the checkpoint's total and the total itself.
+    /// Setting both sums to 0 disables quadratic rewards for that week.
+    /// Same authorization rules apply as for addRewardsCheckpoint, with the oracles signing the sums instead of the totals.
+    #[endpoint(setCheckpointSqrtSums)]
+    fn set_checkpoint_sqrt_sums(
+        &self,
+        week: Week,
+        total_delegation_sqrt_sum: BigUint,
+        total_lkmex_sqrt_sum: BigUint,
+        oracle_signatures: MultiValueEncoded<OracleSignature<Self::Api>>,
+    ) {
+        let message = self.build_checkpoint_message(
+            CHECKPOINT_SQRT_SUMS_TAG,
+            week,
+            &total_delegation_sqrt_sum,
+            &total_lkmex_sqrt_sum,
+        );
+        self.require_checkpoint_authorized(&message, oracle_signatures);
+        self.require_not_globally_paused();
+        require!(
+            !self.week_has_claims(week).get(),
+            "Rewards were already claimed for this week"
+        );
+
+        let mut checkpoint = self.get_checkpoint_or_panic(week);
+        if total_delegation_sqrt_sum > 0 || total_lkmex_sqrt_sum > 0 {
+            self.require_valid_sqrt_sum(
+                &total_delegation_sqrt_sum,
+                &checkpoint.total_delegation_supply,
+            );
+            self.require_valid_sqrt_sum(&total_lkmex_sqrt_sum, &checkpoint.total_lkmex_staked);
+        }
+        checkpoint.total_delegation_sqrt_sum = total_delegation_sqrt_sum;
+        checkpoint.total_lkmex_sqrt_sum = total_lkmex_sqrt_sum;
+        self.set_checkpoint(week, checkpoint);
+        self.log_admin_action(AdminActionKind::CheckpointSqrtSumsSet(week));
+    }
+
+    /// The users' square roots add up to at least the square root of their total,
+    /// and to at most the total itself, as each amount is at least 1
+    fn require_valid_sqrt_sum(&self, sqrt_sum: &BigUint, total: &BigUint) {
+        require!(
+            sqrt_sum >= &total.sqrt() && sqrt_sum <= total,
+            "Invalid square root sum"
+        );
+    }
+
+    /// Boosts the rewards of the given week by the multiplier, in basis points (10_000 = no boost).
+    /// The extra rewards are taken from each project's bonus pool, as far as it has funds.
+    /// Only allowed while no user has claimed rewards for that week.
+    #[endpoint(setBonusWeek)]
+    fn set_bonus_week(&self, week: Week, multiplier_bps: u64) {
+        self.require_not_globally_paused();
+        self.require_caller_role(Role::ProjectManager);
+
+        require!(week > 0, "Invalid week");
+        require!(multiplier_bps >= BONUS_WEEK_BASE_BPS, "Invalid multiplier");
+        require!(
+            !self.week_has_claims(week).get(),
+            "Rewards were already claimed for this week"
+        );
+
+        self.bonus_week_multiplier_bps(week).set(multiplier_bps);
+        self.log_admin_action(AdminActionKind::BonusWeekSet(week, multiplier_bps));
+    }
+
+    /// Returns the extra reward of a bonus week for the given amount, capped by the project's bonus pool.
+    fn get_bonus_week_amount(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        week: Week,
+        amount: &BigUint,
+    ) -> BigUint {
+        let multiplier_bps = self.bonus_week_multiplier_bps(week).get();
+        if multiplier_bps <= BONUS_WEEK_BASE_BPS {
+            return BigUint::zero();
+        }
+
+        let bonus_amount = amount * (multiplier_bps - BONUS_WEEK_BASE_BPS) / BONUS_WEEK_BASE_BPS;
+        core::cmp::min(bonus_amount, self.project_bonus_pool(project_id).get())
+    }
+
+    /// Sets the merkle root of the week's (user, amounts) data, which enables claimWithProof for that week.
+    /// The root authorizes any claim it includes, so it can only be set by the owner, through the timelock if one is set.
+    #[only_owner]
+    #[endpoint(setWeekMerkleRoot)]
+    fn set_week_merkle_root(&self, week: Week, root: MerkleHash<Self::Api>) {
+        self.require_no_timelock();
+        self.require_not_globally_paused();
+
+        self.apply_week_merkle_root(week, root);
+    }
+
+    /// Commits to the off-chain snapshot the week's signatures are issued from (e.g. the sha256 of the balances CSV),
+    /// so auditors can check the signatures against the committed dataset. The hash can only be set once.
+    #[endpoint(setWeekDataHash)]
+    fn set_week_data_hash(&self, week: Week, hash: DataHash<Self::Api>) {
+        self.require_not_globally_paused();
+        self.require_caller_role(Role::CheckpointSubmitter);
+
+        require!(self.has_checkpoint(week), "No checkpoint for week");
+
+        let data_hash_mapper = self.week_data_hash(week);
+        require!(data_hash_mapper.is_empty(), "Week data hash already set");
+
+        data_hash_mapper.set(&hash);
+        self.log_admin_action(AdminActionKind::WeekDataHashSet(week));
+    }
+
+    /// Deposits rewards for the given project. The rewards may be deposited in multiple payments,
+    /// and the project only produces rewards once the distributable supply is deposited.
+    /// The distributable supply is the weekly rewards times the number of weeks, which can be
+    /// slightly less than the total reward supply due to rounding.
+    /// Any amount above the distributable supply is refunded.
+    /// Locked tokens (MetaESDT) are accepted as well, in which case all deposits must have the same nonce.
+    /// EGLD is accepted for projects whose reward token is EGLD_REWARD_TOKEN_ID.
     #[payable("*")]
     #[endpoint(depositRewards)]
     fn deposit_rewards(&self, project_id: ProjectId<Self::Api>) {
+        self.require_not_globally_paused();
+
+        let payment = self.get_reward_payment();
+        let caller = self.blockchain().get_caller();
+        let project = self.get_deposit_project_or_panic(&caller, &project_id);
+        require!(
+            project.reward_token == payment.token_identifier,
+            "Invalid payment token"
+        );
+        require!(
+            self.is_valid_deposit_nonce(&project_id, payment.token_nonce),
+            "Invalid payment nonce"
+        );
+        require!(payment.amount > 0, "Invalid amount");
+
+        let missing_amount = self.get_missing_deposit_amount(&project_id, &project);
+        let deposit_amount = core::cmp::min(payment.amount.clone(), missing_amount);
+        self.record_deposit(&project_id, &project, payment.token_nonce, &deposit_amount);
+
+        let overshoot = payment.amount - deposit_amount;
+        if overshoot > 0 {
+            self.send_reward(
+                &caller,
+                &payment.token_identifier,
+                payment.token_nonce,
+                &overshoot,
+            );
+        }
+    }
+
+    /// Deposits the rewards for multiple projects in a single transfer.
+    /// Each project must have a payment of its reward token, for exactly the remaining amount to deposit.
+    #[payable("*")]
+    #[endpoint(depositRewardsMulti)]
+    fn deposit_rewards_multi(&self, project_ids: MultiValueEncoded<ProjectId<Self::Api>>) {
+        self.require_not_globally_paused();
+
+        let payments = self.call_value().all_esdt_transfers();
+        require!(
+            payments.len() == project_ids.len(),
+            "Invalid number of payments"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let mut used_payments = ManagedVec::<Self::Api, u32>::new();
+        for project_id in project_ids {
+            let project = self.get_deposit_project_or_panic(&caller, &project_id);
+            let missing_amount = self.get_missing_deposit_amount(&project_id, &project);
+
+            let mut opt_payment_index = None;
+            for (i, payment) in payments.iter().enumerate() {
+                if payment.token_identifier == project.reward_token
+                    && self.is_valid_deposit_nonce(&project_id, payment.token_nonce)
+                    && payment.amount == missing_amount
+                    && !used_payments.contains(&(i as u32))
+                {
+                    opt_payment_index = Some(i);
+                    break;
+                }
+            }
+
+            let payment_index =
+                opt_payment_index.unwrap_or_else(|| sc_panic!("No matching payment for project"));
+            used_payments.push(payment_index as u32);
+
+            let token_nonce = payments.get(payment_index).token_nonce;
+            self.record_deposit(&project_id, &project, token_nonce, &missing_amount);
+        }
+    }
+
+    fn get_deposit_project_or_panic(
+        &self,
+        caller: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+    ) -> Project<Self::Api> {
+        require!(
+            !self.funded_projects().contains(project_id),
+            "Rewards already deposited"
+        );
+
+        let project = self.get_project_or_panic(project_id);
+        let owner_may_deposit = self.owner_deposit_allowed(project_id).get()
+            && caller == &self.blockchain().get_owner_address();
+        require!(
+            caller == &self.project_owner(project_id).get() || owner_may_deposit,
+            "Only project owner may deposit the rewards"
+        );
+
+        let current_week = self.get_current_week();
+        let expiration_weeks = self.get_claim_expiration_weeks();
+        require!(
+            !project.is_expired(current_week, expiration_weeks),
+            "Project is expired"
+        );
         require!(
-            !self.rewards_deposited(&project_id).get(),
+            !self.is_deposit_deadline_passed(&project),
+            "Deposit deadline passed"
+        );
+
+        project
+    }
+
+    /// Allows the SC owner to deposit the rewards on behalf of the project owner,
+    /// e.g. for projects whose rewards are funded off-chain.
+    #[only_owner]
+    #[endpoint(setOwnerDepositAllowed)]
+    fn set_owner_deposit_allowed(&self, project_id: ProjectId<Self::Api>, allowed: bool) {
+        let _ = self.get_project_or_panic(&project_id);
+        self.owner_deposit_allowed(&project_id).set(allowed);
+        self.log_project_update(&project_id, b"setOwnerDepositAllowed");
+    }
+
+    /// Sets the number of epochs before a project's start by which its rewards must be deposited.
+    /// 0 disables the deadline.
+    #[only_owner]
+    #[endpoint(setDepositDeadlineEpochs)]
+    fn set_deposit_deadline_epochs(&self, deposit_deadline_epochs: Epoch) {
+        self.deposit_deadline_epochs().set(deposit_deadline_epochs);
+        self.log_admin_action(AdminActionKind::DepositDeadlineEpochsChanged(
+            deposit_deadline_epochs,
+        ));
+    }
+
+    /// Removes a project whose rewards were not fully deposited before the deadline.
+    /// Any partial deposit is refunded to the project owner, while the escrowed listing fee, if any,
+    /// is forfeited to the treasury. May be called by anyone.
+    #[endpoint(deactivateUnfundedProject)]
+    fn deactivate_unfunded_project(&self, project_id: ProjectId<Self::Api>) {
+        let project = self.get_project_or_panic(&project_id);
+        require!(
+            !self.funded_projects().contains(&project_id),
             "Rewards already deposited"
         );
+        require!(
+            self.is_deposit_deadline_passed(&project),
+            "Deposit deadline not passed"
+        );
 
-        let (payment_token, payment_amount) = self.call_value().single_fungible_esdt();
+        // missing the deadline counts as failing to deposit before the start week
+        if !self.listing_fee_escrow(&project_id).is_empty() && !self.treasury_address().is_empty() {
+            self.forfeit_listing_fee_escrow(&project_id);
+        }
+        self.clear_and_refund_project(&project_id, &project.reward_token);
+    }
+
+    #[view(getProjectDepositDeadline)]
+    fn get_project_deposit_deadline(&self, project_id: ProjectId<Self::Api>) -> Epoch {
         let project = self.get_project_or_panic(&project_id);
+        self.get_deposit_deadline_epoch(&project)
+    }
+
+    fn get_deposit_deadline_epoch(&self, project: &Project<Self::Api>) -> Epoch {
+        let start_epoch = self.first_week_start_epoch().get()
+            + project.start_week as Epoch * self.get_epochs_per_week();
+        start_epoch.saturating_sub(self.deposit_deadline_epochs().get())
+    }
+
+    fn is_deposit_deadline_passed(&self, project: &Project<Self::Api>) -> bool {
+        if self.deposit_deadline_epochs().get() == 0 {
+            return false;
+        }
+
+        self.blockchain().get_block_epoch() > self.get_deposit_deadline_epoch(project)
+    }
+
+    fn get_missing_deposit_amount(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        project: &Project<Self::Api>,
+    ) -> BigUint {
+        self.get_project_distributable_supply(project_id, project)
+            - self.deposited_amount(project_id).get()
+    }
+
+    /// Unless the nonce was set beforehand, the first deposit determines the nonce,
+    /// which all following deposits must match
+    fn is_valid_deposit_nonce(&self, project_id: &ProjectId<Self::Api>, token_nonce: u64) -> bool {
+        let nonce_fixed = self.project_reward_token_nonce_fixed(project_id).get();
+        (!nonce_fixed && self.deposited_amount(project_id).get() == 0)
+            || self.project_reward_token_nonce(project_id).get() == token_nonce
+    }
+
+    fn record_deposit(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        project: &Project<Self::Api>,
+        token_nonce: u64,
+        deposit_amount: &BigUint,
+    ) {
+        let missing_amount = self.get_missing_deposit_amount(project_id, project);
+        self.project_reward_token_nonce(project_id).set(token_nonce);
+        let total_deposited = self.deposited_amount(project_id).update(|deposited| {
+            *deposited += deposit_amount;
+            deposited.clone()
+        });
+        self.rewards_deposited_event(
+            project_id,
+            &self.blockchain().get_caller(),
+            &RewardsDepositedEventData {
+                token_nonce,
+                amount: deposit_amount.clone(),
+                total_deposited,
+            },
+        );
+        self.leftover_project_funds(project_id)
+            .update(|leftover| *leftover += deposit_amount);
+        if deposit_amount == &missing_amount {
+            let _ = self.funded_projects().insert(project_id.clone());
+            self.refund_listing_fee_escrow(project_id);
+        }
+    }
+
+    /// Requests extending the project until the given week. The extension is only applied
+    /// once the project owner deposits the extra rewards through depositProjectExtension.
+    #[endpoint(extendProject)]
+    fn extend_project(&self, project_id: ProjectId<Self::Api>, new_end_week: Week) {
+        self.require_caller_role(Role::ProjectManager);
+
+        let project = self.get_project_or_panic(&project_id);
+        require!(
+            self.funded_projects().contains(&project_id),
+            "Rewards not deposited"
+        );
+        require!(
+            self.get_current_week() <= project.end_week,
+            "Project already ended"
+        );
+        require!(new_end_week > project.end_week, "Invalid end week");
+        require!(
+            self.project_week_weights(&project_id).is_empty(),
+            "Project has an emission curve"
+        );
+        require!(
+            !self.has_edge_offsets(&project_id),
+            "Project has edge offsets"
+        );
+        require!(
+            self.project_partner_rewards(&project_id).is_empty(),
+            "Project has partner rewards"
+        );
+
+        self.pending_project_extension(&project_id)
+            .set(new_end_week);
+        self.log_project_update(&project_id, b"extendProject");
+    }
+
+    /// Adds the paid reward tokens to the project's bonus pool, which funds the claim streak bonuses,
+    /// the top-ups up to the minimum reward per user and the extra rewards of bonus weeks.
+    /// Unused bonus funds are refunded to the project owner along with the project's leftover funds.
+    #[payable("*")]
+    #[endpoint(depositProjectBonusPool)]
+    fn deposit_project_bonus_pool(&self, project_id: ProjectId<Self::Api>) {
+        self.require_not_globally_paused();
+
+        let payment = self.get_reward_payment();
+        let project = self.get_project_or_panic(&project_id);
+
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == self.project_owner(&project_id).get(),
+            "Only project owner may deposit the rewards"
+        );
+        require!(
+            self.funded_projects().contains(&project_id),
+            "Rewards not deposited"
+        );
+        require!(
+            payment.token_identifier == project.reward_token
+                && self.is_valid_deposit_nonce(&project_id, payment.token_nonce),
+            "Invalid payment token"
+        );
+
+        self.project_bonus_pool(&project_id)
+            .update(|bonus_pool| *bonus_pool += &payment.amount);
+        self.leftover_project_funds(&project_id)
+            .update(|leftover| *leftover += &payment.amount);
+    }
+
+    /// Deposits the project's partner rewards, added through addProjectPartnerReward.
+    /// Accepts several payments, each of one of the partner tokens.
+    /// Any amount above a partner token's total supply is refunded.
+    /// Like the project's rewards, the partner rewards have to be fully deposited before the project starts,
+    /// as the partner token is not paid for claims made before then. The first deposit sets the token's nonce.
+    #[payable("*")]
+    #[endpoint(depositPartnerRewards)]
+    fn deposit_partner_rewards(&self, project_id: ProjectId<Self::Api>) {
+        self.require_not_globally_paused();
+
+        let payments = self.call_value().all_esdt_transfers();
+        let project = self.get_project_or_panic(&project_id);
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == self.project_owner(&project_id).get(),
+            "Only project owner may deposit the rewards"
+        );
+        require!(
+            self.get_current_week() < project.start_week,
+            "Project already started"
+        );
+
+        let partner_rewards_mapper = self.project_partner_rewards(&project_id);
+        for payment in payments.iter() {
+            require!(payment.amount > 0, "Invalid payment");
+
+            let opt_index = partner_rewards_mapper
+                .iter()
+                .position(|partner_reward| partner_reward.token == payment.token_identifier);
+            let index = match opt_index {
+                Some(i) => i + 1,
+                None => sc_panic!("Invalid payment token"),
+            };
+
+            let mut partner_reward = partner_rewards_mapper.get(index);
+            if partner_reward.deposited_amount == 0 {
+                partner_reward.token_nonce = payment.token_nonce;
+            } else {
+                require!(
+                    payment.token_nonce == partner_reward.token_nonce,
+                    "Invalid payment"
+                );
+            }
+
+            let missing_amount =
+                partner_reward.get_total_supply() - &partner_reward.deposited_amount;
+            let deposit_amount = core::cmp::min(payment.amount.clone(), missing_amount);
+            partner_reward.deposited_amount += &deposit_amount;
+            partner_reward.leftover_funds += &deposit_amount;
+            self.project_partner_rewards(&project_id)
+                .set(index, &partner_reward);
+
+            let overshoot = payment.amount - deposit_amount;
+            if overshoot > 0 {
+                self.send().direct_esdt(
+                    &caller,
+                    &payment.token_identifier,
+                    payment.token_nonce,
+                    &overshoot,
+                );
+            }
+        }
+    }
+
+    /// Deposits the rewards for the pending extension of the project, and applies the extension.
+    /// The required amount is the project's current weekly rewards, times the number of extra weeks,
+    /// so the weekly rewards stay the same.
+    #[payable("*")]
+    #[endpoint(depositProjectExtension)]
+    fn deposit_project_extension(&self, project_id: ProjectId<Self::Api>) {
+        self.require_not_globally_paused();
+
+        let payment = self.get_reward_payment();
+        let mut project = self.get_project_or_panic(&project_id);
 
         let caller = self.blockchain().get_caller();
         let project_owner = self.project_owner(&project_id).get();
@@ -71,22 +930,50 @@ pub trait RewardsModule:
             "Only project owner may deposit the rewards"
         );
 
-        let current_week = self.get_current_week();
-        require!(!project.is_expired(current_week), "Project is expired");
+        let extension_mapper = self.pending_project_extension(&project_id);
+        require!(!extension_mapper.is_empty(), "No pending extension");
 
-        let total_reward_supply = project.lkmex_reward_supply + project.delegation_reward_supply;
+        let new_end_week = extension_mapper.take();
         require!(
-            project.reward_token == payment_token,
+            self.get_current_week() <= project.end_week,
+            "Project already ended"
+        );
+
+        let duration_weeks = project.get_duration_in_weeks() as u32;
+        let extra_weeks = (new_end_week - project.end_week) as u32;
+        let extra_delegation_supply =
+            &project.delegation_reward_supply / duration_weeks * extra_weeks;
+        let extra_lkmex_supply = &project.lkmex_reward_supply / duration_weeks * extra_weeks;
+        let extra_supply = &extra_delegation_supply + &extra_lkmex_supply;
+        require!(
+            project.reward_token == payment.token_identifier
+                && self.project_reward_token_nonce(&project_id).get() == payment.token_nonce,
             "Invalid payment token"
         );
-        require!(total_reward_supply == payment_amount, "Invalid amount");
+        require!(extra_supply == payment.amount, "Invalid amount");
+
+        self.project_extended_event(
+            &project_id,
+            &ProjectExtendedEventData {
+                old_end_week: project.end_week,
+                new_end_week,
+                extra_supply: extra_supply.clone(),
+            },
+        );
+
+        project.delegation_reward_supply += extra_delegation_supply;
+        project.lkmex_reward_supply += extra_lkmex_supply;
+        project.end_week = new_end_week;
+        let _ = self.projects().insert(project_id.clone(), project);
 
+        self.deposited_amount(&project_id)
+            .update(|deposited| *deposited += &extra_supply);
         self.leftover_project_funds(&project_id)
-            .set(&total_reward_supply);
-        self.rewards_deposited(&project_id).set(true);
+            .update(|leftover| *leftover += extra_supply);
     }
 
     /// Gets rewards for the given week, assuming the user has the given staked EGLD and LKMEX amounts.
+    /// Projects with a claimant whitelist are only included if the caller is whitelisted.
     /// Returned results are pairs of:
     /// - project_id
     /// - project_reward_token
@@ -98,74 +985,359 @@ pub trait RewardsModule:
         user_delegation_amount: BigUint,
         user_lkmex_staked_amount: BigUint,
     ) -> PrettyRewards<Self::Api> {
-        let checkpoint: RewardsCheckpoint<Self::Api> = self.rewards_checkpoints().get(week);
-        let current_week = self.get_current_week();
         let mut rewards_pretty = MultiValueEncoded::new();
+        let checkpoint = match self.get_checkpoint(week) {
+            Some(checkpoint) => checkpoint,
+            None => return rewards_pretty,
+        };
+        let current_week = self.get_current_week();
+        let caller = self.blockchain().get_caller();
+
+        self.for_each_user_reward_for_week(
+            &caller,
+            current_week,
+            week,
+            &user_delegation_amount,
+            &user_lkmex_staked_amount,
+            &checkpoint,
+            |id, reward_token, amount| rewards_pretty.push((id, reward_token, amount).into()),
+        );
+
+        rewards_pretty
+    }
+
+    /// Same as getRewardsForWeek, for several users at once.
+    /// Arguments are triples of:
+    /// - user
+    /// - user_delegation_amount
+    /// - user_lkmex_staked_amount
+    ///
+    /// Returned results are, for each user with rewards, in order:
+    /// - user
+    /// - project_id
+    /// - project_reward_token
+    /// - reward_amount
+    #[label("metabonding-external-view")]
+    #[view(getRewardsForWeekMultiUser)]
+    fn get_rewards_for_week_multi_user(
+        &self,
+        week: Week,
+        users: MultiValueEncoded<MultiValue3<ManagedAddress, BigUint, BigUint>>,
+    ) -> MultiUserRewards<Self::Api> {
+        let mut rewards = MultiValueEncoded::new();
+        let checkpoint = match self.get_checkpoint(week) {
+            Some(checkpoint) => checkpoint,
+            None => return rewards,
+        };
+        let current_week = self.get_current_week();
+
+        for user_args in users {
+            let (user, user_delegation_amount, user_lkmex_staked_amount) = user_args.into_tuple();
+            self.for_each_user_reward_for_week(
+                &user,
+                current_week,
+                week,
+                &user_delegation_amount,
+                &user_lkmex_staked_amount,
+                &checkpoint,
+                |id, reward_token, amount| {
+                    rewards.push((user.clone(), id, reward_token, amount).into())
+                },
+            );
+        }
+
+        rewards
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn for_each_user_reward_for_week<F>(
+        &self,
+        user: &ManagedAddress,
+        current_week: Week,
+        week: Week,
+        user_delegation_amount: &BigUint,
+        user_lkmex_staked_amount: &BigUint,
+        checkpoint: &RewardsCheckpoint<Self::Api>,
+        mut on_reward: F,
+    ) where
+        F: FnMut(ProjectId<Self::Api>, TokenIdentifier, BigUint),
+    {
+        for id in &self.get_week_project_ids(week) {
+            let project = match self.projects().get(&id) {
+                Some(project) => project,
+                None => continue,
+            };
+            if self.project_claims_paused(&id).get() {
+                continue;
+            }
 
-        for (id, project) in self.projects().iter() {
             let opt_weekly_reward = self.get_weekly_reward_for_project(
+                user,
                 &id,
                 &project,
                 current_week,
                 week,
-                &user_delegation_amount,
-                &user_lkmex_staked_amount,
-                &checkpoint.total_delegation_supply,
-                &checkpoint.total_lkmex_staked,
+                user_delegation_amount,
+                user_lkmex_staked_amount,
+                checkpoint,
             );
 
             if let Some(weekly_reward) = opt_weekly_reward {
-                rewards_pretty.push((id, project.reward_token, weekly_reward).into());
+                let bonus_week_amount =
+                    self.get_bonus_week_amount(&id, week, &weekly_reward.amount);
+                let amount = weekly_reward.amount + bonus_week_amount;
+                if amount > 0 {
+                    on_reward(id, project.reward_token, amount);
+                }
             }
         }
+    }
 
-        rewards_pretty
+    /// Returns the project's (delegation APR, LKMEX APR) for the given week, in basis points,
+    /// based on the week's reward supplies and the week's checkpoint.
+    /// The prices are those of the smallest unit of each token. They may be scaled
+    /// by any common factor, as only their ratios matter.
+    #[label("metabonding-external-view")]
+    #[view(getProjectApr)]
+    fn get_project_apr(
+        &self,
+        project_id: ProjectId<Self::Api>,
+        week: Week,
+        reward_token_price: BigUint,
+        delegation_token_price: BigUint,
+        lkmex_token_price: BigUint,
+    ) -> MultiValue2<BigUint, BigUint> {
+        let project = self.get_project_or_panic(&project_id);
+        require!(
+            self.is_in_range(week, project.start_week, project.end_week),
+            "Invalid week"
+        );
+        let checkpoint = self
+            .get_checkpoint(week)
+            .unwrap_or_else(|| sc_panic!("No checkpoint for week"));
+
+        let (delegation_supply, lkmex_supply) =
+            self.get_project_week_reward_supplies(&project_id, &project, week);
+        let delegation_apr = self.calculate_apr_bps(
+            &delegation_supply,
+            &reward_token_price,
+            &checkpoint.total_delegation_supply,
+            &delegation_token_price,
+        );
+        let lkmex_apr = self.calculate_apr_bps(
+            &lkmex_supply,
+            &reward_token_price,
+            &checkpoint.total_lkmex_staked,
+            &lkmex_token_price,
+        );
+
+        (delegation_apr, lkmex_apr).into()
     }
 
+    fn calculate_apr_bps(
+        &self,
+        weekly_supply: &BigUint,
+        reward_token_price: &BigUint,
+        total_staked: &BigUint,
+        staked_token_price: &BigUint,
+    ) -> BigUint {
+        let yearly_rewards_value = weekly_supply * reward_token_price * WEEKS_IN_YEAR;
+        let staked_value = total_staked * staked_token_price;
+
+        self.calculate_ratio(
+            &yearly_rewards_value,
+            &BigUint::from(APR_PRECISION_BPS),
+            &staked_value,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn get_weekly_reward_for_project(
         &self,
+        user: &ManagedAddress,
         project_id: &ProjectId<Self::Api>,
         project: &Project<Self::Api>,
         current_week: Week,
         week: Week,
         user_delegation_amount: &BigUint,
         user_lkmex_staked_amount: &BigUint,
-        total_delegation_supply: &BigUint,
-        total_lkmex_staked: &BigUint,
-    ) -> Option<BigUint> {
+        checkpoint: &RewardsCheckpoint<Self::Api>,
+    ) -> Option<WeeklyReward<Self::Api>> {
         if !self.is_in_range(week, project.start_week, project.end_week)
-            || !self.rewards_deposited(project_id).get()
-            || project.is_expired(current_week)
+            || !self.funded_projects().contains(project_id)
+            || project.is_expired(current_week, self.get_claim_expiration_weeks())
+            || week <= self.last_swept_week(project_id).get()
+            || !self.is_project_claimant_eligible(project_id, user)
         {
             return None;
         }
 
-        let reward_amount = self.calculate_reward_amount(
-            project,
+        let (
+            user_delegation_weight,
+            user_lkmex_weight,
+            total_delegation_weight,
+            total_lkmex_weight,
+        ) = self.get_user_week_weights(
+            user,
+            project_id,
+            week,
             user_delegation_amount,
             user_lkmex_staked_amount,
-            total_delegation_supply,
-            total_lkmex_staked,
+            checkpoint,
+        );
+        let mut weekly_reward = self.calculate_reward_amount(
+            project_id,
+            project,
+            week,
+            &user_delegation_weight,
+            &user_lkmex_weight,
+            &total_delegation_weight,
+            &total_lkmex_weight,
         );
-        if reward_amount > 0 {
-            Some(reward_amount)
+        weekly_reward.partner_amounts = self.calculate_partner_reward_amounts(
+            project_id,
+            project,
+            week,
+            &user_delegation_weight,
+            &user_lkmex_weight,
+            &total_delegation_weight,
+            &total_lkmex_weight,
+        );
+
+        let has_partner_rewards = weekly_reward
+            .partner_amounts
+            .iter()
+            .any(|amount| *amount > 0);
+        if weekly_reward.amount > 0 || has_partner_rewards {
+            Some(weekly_reward)
         } else {
             None
         }
     }
 
+    /// Returns the user's delegation and LKMEX weights, followed by the week's total weights,
+    /// according to the project's distribution mode
+    fn get_user_week_weights(
+        &self,
+        user: &ManagedAddress,
+        project_id: &ProjectId<Self::Api>,
+        week: Week,
+        user_delegation_amount: &BigUint,
+        user_lkmex_staked_amount: &BigUint,
+        checkpoint: &RewardsCheckpoint<Self::Api>,
+    ) -> (BigUint, BigUint, BigUint, BigUint) {
+        let distribution_mode = self.project_distribution_mode(project_id).get();
+        if distribution_mode == DistributionMode::Quadratic && checkpoint.has_sqrt_sums() {
+            return (
+                user_delegation_amount.sqrt(),
+                user_lkmex_staked_amount.sqrt(),
+                checkpoint.total_delegation_sqrt_sum.clone(),
+                checkpoint.total_lkmex_sqrt_sum.clone(),
+            );
+        }
+
+        let (user_lkmex_weight, total_lkmex_weight) =
+            if !self.project_energy_weighted(project_id).get() {
+                (
+                    user_lkmex_staked_amount.clone(),
+                    checkpoint.total_lkmex_staked.clone(),
+                )
+            } else {
+                // energy registered on-chain takes precedence over the checkpoint's total energy
+                let registered_energy = self.week_registered_energy(week).get();
+                if registered_energy > 0 {
+                    (self.user_week_energy(user, week).get(), registered_energy)
+                } else if checkpoint.has_energy() {
+                    (
+                        self.user_week_energy(user, week).get(),
+                        checkpoint.total_energy.clone(),
+                    )
+                } else {
+                    (
+                        user_lkmex_staked_amount.clone(),
+                        checkpoint.total_lkmex_staked.clone(),
+                    )
+                }
+            };
+        if distribution_mode == DistributionMode::Combined {
+            // both pools are distributed by the same weight
+            let lkmex_factor_bps = self.project_lkmex_factor_bps(project_id).get();
+            let user_weight = user_delegation_amount
+                + &(&user_lkmex_weight * lkmex_factor_bps / LKMEX_FACTOR_PRECISION);
+            let total_weight = &checkpoint.total_delegation_supply
+                + &(&total_lkmex_weight * lkmex_factor_bps / LKMEX_FACTOR_PRECISION);
+            return (
+                user_weight.clone(),
+                user_weight,
+                total_weight.clone(),
+                total_weight,
+            );
+        }
+
+        (
+            user_delegation_amount.clone(),
+            user_lkmex_weight,
+            checkpoint.total_delegation_supply.clone(),
+            total_lkmex_weight,
+        )
+    }
+
+    /// Each partner token's weekly rewards are computed from its own delegation and LKMEX supplies,
+    /// over the project's weeks, like the project's own rewards. They are not subject to the project's cap.
+    /// Partner rewards are only paid once their total supply is deposited.
+    #[allow(clippy::too_many_arguments)]
+    fn calculate_partner_reward_amounts(
+        &self,
+        project_id: &ProjectId<Self::Api>,
+        project: &Project<Self::Api>,
+        week: Week,
+        user_delegation_weight: &BigUint,
+        user_lkmex_weight: &BigUint,
+        total_delegation_weight: &BigUint,
+        total_lkmex_weight: &BigUint,
+    ) -> ManagedVec<BigUint> {
+        let mut partner_amounts = ManagedVec::new();
+        for partner_reward in self.project_partner_rewards(project_id).iter() {
+            if partner_reward.deposited_amount < partner_reward.get_total_supply() {
+                partner_amounts.push(BigUint::zero());
+                continue;
+            }
+
+            let partner_project = Project {
+                reward_token: partner_reward.token,
+                delegation_reward_supply: partner_reward.delegation_supply,
+                lkmex_reward_supply: partner_reward.lkmex_supply,
+                start_week: project.start_week,
+                end_week: project.end_week,
+            };
+            let (week_delegation_supply, week_lkmex_supply) =
+                self.get_project_week_reward_supplies(project_id, &partner_project, week);
+            let amount =
+                self.calculate_ratio(
+                    &week_delegation_supply,
+                    user_delegation_weight,
+                    total_delegation_weight,
+                ) + self.calculate_ratio(&week_lkmex_supply, user_lkmex_weight, total_lkmex_weight);
+            partner_amounts.push(amount);
+        }
+
+        partner_amounts
+    }
+
+    /// The computed amount is clamped to the project's max reward per user per week, if set.
+    #[allow(clippy::too_many_arguments)]
     fn calculate_reward_amount(
         &self,
+        project_id: &ProjectId<Self::Api>,
         project: &Project<Self::Api>,
+        week: Week,
         user_delegation_amount: &BigUint,
         user_lkmex_staked_amount: &BigUint,
         total_delegation_supply: &BigUint,
         total_lkmex_staked: &BigUint,
-    ) -> BigUint {
-        let project_duration_weeks = project.get_duration_in_weeks() as u32;
-        let rewards_supply_per_week_delegation =
-            &project.delegation_reward_supply / project_duration_weeks;
-        let rewards_supply_per_week_lkmex = &project.lkmex_reward_supply / project_duration_weeks;
+    ) -> WeeklyReward<Self::Api> {
+        let (rewards_supply_per_week_delegation, rewards_supply_per_week_lkmex) =
+            self.get_project_week_reward_supplies(project_id, project, week);
 
         let rewards_delegation = self.calculate_ratio(
             &rewards_supply_per_week_delegation,
@@ -177,8 +1349,73 @@ pub trait RewardsModule:
             user_lkmex_staked_amount,
             total_lkmex_staked,
         );
+        let delegation_remainder = RoundingRemainder {
+            numerator: self.calculate_ratio_remainder(
+                &rewards_supply_per_week_delegation,
+                user_delegation_amount,
+                total_delegation_supply,
+            ),
+            denominator: total_delegation_supply.clone(),
+        };
+        let lkmex_remainder = RoundingRemainder {
+            numerator: self.calculate_ratio_remainder(
+                &rewards_supply_per_week_lkmex,
+                user_lkmex_staked_amount,
+                total_lkmex_staked,
+            ),
+            denominator: total_lkmex_staked.clone(),
+        };
+
+        let reward_amount = rewards_delegation + rewards_lkmex;
+        let max_reward = self.project_max_reward_per_user_per_week(project_id).get();
+        let (amount, capped_amount) = if max_reward > 0 && reward_amount > max_reward {
+            let capped_amount = &reward_amount - &max_reward;
+            (max_reward, capped_amount)
+        } else {
+            (reward_amount, BigUint::zero())
+        };
 
-        rewards_delegation + rewards_lkmex
+        WeeklyReward {
+            amount,
+            capped_amount,
+            delegation_remainder,
+            lkmex_remainder,
+            partner_amounts: ManagedVec::new(),
+        }
+    }
+
+    /// Returns up to `limit` checkpoints, starting from `from_week`, as triples of
+    /// (week, total_delegation_supply, total_lkmex_staked). Skipped weeks are not included.
+    #[label("metabonding-external-view")]
+    #[view(getRewardsCheckpoints)]
+    fn get_rewards_checkpoints(
+        &self,
+        from_week: Week,
+        limit: usize,
+    ) -> MultiValueEncoded<CheckpointArgs<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        let last_checkpoint_week = self.get_last_checkpoint_week();
+        let first_week = core::cmp::max(from_week, 1);
+        for week in first_week..=last_checkpoint_week {
+            if result.len() == limit {
+                break;
+            }
+
+            let checkpoint = match self.get_checkpoint(week) {
+                Some(checkpoint) => checkpoint,
+                None => continue,
+            };
+            result.push(
+                (
+                    week,
+                    checkpoint.total_delegation_supply,
+                    checkpoint.total_lkmex_staked,
+                )
+                    .into(),
+            );
+        }
+
+        result
     }
 
     fn is_claim_in_time(
@@ -188,14 +1425,97 @@ pub trait RewardsModule:
         rewards_nr_first_grace_weeks: Week,
     ) -> bool {
         current_week <= rewards_nr_first_grace_weeks
-            || current_week <= claim_week + PROJECT_EXPIRATION_WEEKS
+            || current_week <= claim_week + self.get_claim_expiration_weeks()
+    }
+
+    /// Checkpoints added before weeks could be skipped are still read from the legacy storage
+    fn get_checkpoint(&self, week: Week) -> Option<RewardsCheckpoint<Self::Api>> {
+        if week <= self.pruned_checkpoints_up_to_week().get() {
+            return None;
+        }
+        if let Some(checkpoint) = self.rewards_checkpoints().get(&week) {
+            return Some(checkpoint);
+        }
+
+        let legacy_mapper = self.legacy_rewards_checkpoints();
+        if week > 0 && week <= legacy_mapper.len() {
+            Some(legacy_mapper.get(week))
+        } else {
+            None
+        }
+    }
+
+    fn get_checkpoint_or_panic(&self, week: Week) -> RewardsCheckpoint<Self::Api> {
+        match self.get_checkpoint(week) {
+            Some(checkpoint) => checkpoint,
+            None => sc_panic!("No checkpoint for week"),
+        }
+    }
+
+    fn has_checkpoint(&self, week: Week) -> bool {
+        if week <= self.pruned_checkpoints_up_to_week().get() {
+            return false;
+        }
+
+        self.rewards_checkpoints().contains_key(&week)
+            || (week > 0 && week <= self.legacy_rewards_checkpoints().len())
+    }
+
+    fn set_checkpoint(&self, week: Week, checkpoint: RewardsCheckpoint<Self::Api>) {
+        let _ = self.rewards_checkpoints().insert(week, checkpoint);
     }
 
-    #[inline]
     fn get_last_checkpoint_week(&self) -> Week {
-        self.rewards_checkpoints().len()
+        let mapper = self.last_checkpoint_week();
+        if mapper.is_empty() {
+            self.legacy_rewards_checkpoints().len()
+        } else {
+            mapper.get()
+        }
     }
 
+    #[view(getDepositDeadlineEpochs)]
+    #[storage_mapper("depositDeadlineEpochs")]
+    fn deposit_deadline_epochs(&self) -> SingleValueMapper<Epoch>;
+
+    #[storage_mapper("checkpointDisputeEpochs")]
+    fn checkpoint_dispute_epochs(&self) -> SingleValueMapper<Epoch>;
+
+    #[storage_mapper("checkpointStatus")]
+    fn checkpoint_status(&self, week: Week) -> SingleValueMapper<CheckpointStatus>;
+
+    #[storage_mapper("checkpointFinalizationEpoch")]
+    fn checkpoint_finalization_epoch(&self, week: Week) -> SingleValueMapper<Epoch>;
+
+    #[view(getUserWeekEnergy)]
+    #[storage_mapper("userWeekEnergy")]
+    fn user_week_energy(&self, user: &ManagedAddress, week: Week) -> SingleValueMapper<BigUint>;
+
+    #[view(getWeekRegisteredEnergy)]
+    #[storage_mapper("weekRegisteredEnergy")]
+    fn week_registered_energy(&self, week: Week) -> SingleValueMapper<BigUint>;
+
+    #[view(getBonusWeekMultiplierBps)]
+    #[storage_mapper("bonusWeekMultiplierBps")]
+    fn bonus_week_multiplier_bps(&self, week: Week) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("weekHasClaims")]
+    fn week_has_claims(&self, week: Week) -> SingleValueMapper<bool>;
+
+    #[storage_mapper("rewardsCheckpointsByWeek")]
+    fn rewards_checkpoints(&self) -> MapMapper<Week, RewardsCheckpoint<Self::Api>>;
+
+    #[view(getCheckpointUpdateNonce)]
+    #[storage_mapper("checkpointUpdateNonce")]
+    fn checkpoint_update_nonce(&self, week: Week) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("lastCheckpointWeek")]
+    fn last_checkpoint_week(&self) -> SingleValueMapper<Week>;
+
+    #[view(getPrunedCheckpointsUpToWeek)]
+    #[storage_mapper("prunedCheckpointsUpToWeek")]
+    fn pruned_checkpoints_up_to_week(&self) -> SingleValueMapper<Week>;
+
     #[storage_mapper("rewardsCheckpoints")]
-    fn rewards_checkpoints(&self) -> VecMapper<RewardsCheckpoint<Self::Api>>;
+    fn legacy_rewards_checkpoints(&self) -> VecMapper<RewardsCheckpoint<Self::Api>>;
 }
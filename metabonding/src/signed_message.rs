@@ -0,0 +1,86 @@
+elrond_wasm::imports!();
+
+pub const CLAIM_TAG: &[u8] = b"claim";
+pub const RELAYED_CLAIM_TAG: &[u8] = b"relayedClaim";
+pub const USER_ENERGY_TAG: &[u8] = b"userEnergy";
+pub const CHECKPOINT_TAG: &[u8] = b"checkpoint";
+pub const CHECKPOINT_UPDATE_TAG: &[u8] = b"checkpointUpdate";
+pub const KEEPER_CHECKPOINT_TAG: &[u8] = b"keeperCheckpoint";
+pub const CHECKPOINT_SQRT_SUMS_TAG: &[u8] = b"checkpointSqrtSums";
+
+// nested-encoded field lengths, with the amounts of at most 32 bytes, i.e. below 2^256
+const ADDRESS_LEN: usize = 32;
+const U64_LEN: usize = 8;
+const WEEK_LEN: usize = 4;
+const MAX_AMOUNT_LEN: usize = 4 + 32;
+
+// tag + contract address + nonce
+const fn header_len(tag: &[u8]) -> usize {
+    4 + tag.len() + ADDRESS_LEN + U64_LEN
+}
+
+const fn max_len(first: usize, second: usize) -> usize {
+    if first > second {
+        first
+    } else {
+        second
+    }
+}
+
+// header + valid_until_epoch + week + user + user_delegation_amount + user_lkmex_staked_amount
+const CLAIM_MESSAGE_MAX_LEN: usize =
+    header_len(CLAIM_TAG) + U64_LEN + WEEK_LEN + ADDRESS_LEN + 2 * MAX_AMOUNT_LEN;
+// header + week + user + user_delegation_amount + user_lkmex_staked_amount
+const RELAYED_CLAIM_MESSAGE_MAX_LEN: usize =
+    header_len(RELAYED_CLAIM_TAG) + WEEK_LEN + ADDRESS_LEN + 2 * MAX_AMOUNT_LEN;
+// header + valid_until_epoch + week + user + user_energy
+const USER_ENERGY_MESSAGE_MAX_LEN: usize =
+    header_len(USER_ENERGY_TAG) + U64_LEN + WEEK_LEN + ADDRESS_LEN + MAX_AMOUNT_LEN;
+// header, with the week as nonce + both totals, for the longest checkpoint tag
+const CHECKPOINT_MESSAGE_MAX_LEN: usize = max_len(
+    max_len(
+        header_len(CHECKPOINT_TAG),
+        header_len(KEEPER_CHECKPOINT_TAG),
+    ),
+    header_len(CHECKPOINT_SQRT_SUMS_TAG),
+) + 2 * MAX_AMOUNT_LEN;
+// header + week + both totals
+const CHECKPOINT_UPDATE_MESSAGE_MAX_LEN: usize =
+    header_len(CHECKPOINT_UPDATE_TAG) + WEEK_LEN + 2 * MAX_AMOUNT_LEN;
+
+/// The largest payload any of the signed flows builds, currently the claim message, of 165 bytes
+pub const SIGNED_MESSAGE_MAX_LEN: usize = max_len(
+    max_len(
+        max_len(CLAIM_MESSAGE_MAX_LEN, RELAYED_CLAIM_MESSAGE_MAX_LEN),
+        max_len(USER_ENERGY_MESSAGE_MAX_LEN, CHECKPOINT_MESSAGE_MAX_LEN),
+    ),
+    CHECKPOINT_UPDATE_MESSAGE_MAX_LEN,
+);
+
+/// Payload signed off-chain for one of the SC's signed flows.
+/// It starts with the flow's tag, the SC address and the signer's nonce for that flow,
+/// followed by the flow's fields. All parts are nested-encoded, so two different payloads
+/// can never have the same serialization.
+pub struct SignedMessage<M: ManagedTypeApi> {
+    buffer: ManagedBuffer<M>,
+}
+
+impl<M: ManagedTypeApi> SignedMessage<M> {
+    pub fn new(tag: &[u8], sc_address: &ManagedAddress<M>, nonce: u64) -> Self {
+        SignedMessage {
+            buffer: ManagedBuffer::new(),
+        }
+        .with_field(&ManagedBuffer::<M>::new_from_bytes(tag))
+        .with_field(sc_address)
+        .with_field(&nonce)
+    }
+
+    pub fn with_field<T: NestedEncode>(mut self, field: &T) -> Self {
+        let _ = field.dep_encode(&mut self.buffer);
+        self
+    }
+
+    pub fn as_buffer(&self) -> &ManagedBuffer<M> {
+        &self.buffer
+    }
+}
@@ -0,0 +1,190 @@
+elrond_wasm::imports!();
+
+use crate::{
+    audit_log::AdminActionKind, claim::ClaimArgsArray, project::Epoch, rewards::Week,
+    validation::Signature,
+};
+
+pub mod stake_provider_proxy {
+    elrond_wasm::imports!();
+
+    #[elrond_wasm::proxy]
+    pub trait StakeProviderProxy {
+        #[view(getUserActiveStake)]
+        fn get_user_active_stake(&self, user: ManagedAddress) -> BigUint;
+    }
+}
+
+/// A pending query is assumed lost after this many epochs, e.g. if its callback failed,
+/// so the user may send a new one
+const STAKE_QUERY_TIMEOUT_EPOCHS: Epoch = 1;
+
+#[elrond_wasm::module]
+pub trait StakeVerificationModule:
+    elrond_wasm_modules::pause::PauseModule
+    + crate::project::ProjectModule
+    + crate::access_control::AccessControlModule
+    + crate::common_storage::CommonStorageModule
+    + crate::math::MathModule
+    + crate::validation::ValidationModule
+    + crate::rewards::RewardsModule
+    + crate::claimed_weeks::ClaimedWeeksModule
+    + crate::energy_factory::EnergyFactoryModule
+    + crate::events::EventsModule
+    + crate::claim::ClaimModule
+    + sc_whitelist_module::SCWhitelistModule
+{
+    /// Adds delegation provider contracts whose user stake is checked by claimRewardsWithStakeQuery.
+    #[only_owner]
+    #[endpoint(addStakeProviders)]
+    fn add_stake_providers(&self, providers: MultiValueEncoded<ManagedAddress>) {
+        let providers = providers.to_vec();
+        let mut mapper = self.stake_providers();
+        for provider in providers.iter() {
+            require!(
+                self.blockchain().is_smart_contract(&provider),
+                "Invalid SC address"
+            );
+
+            let _ = mapper.insert(provider.clone_value());
+        }
+
+        self.log_admin_action(AdminActionKind::StakeProvidersAdded(providers));
+    }
+
+    #[only_owner]
+    #[endpoint(removeStakeProviders)]
+    fn remove_stake_providers(&self, providers: MultiValueEncoded<ManagedAddress>) {
+        let providers = providers.to_vec();
+        let mut mapper = self.stake_providers();
+        for provider in providers.iter() {
+            let _ = mapper.swap_remove(&provider);
+        }
+
+        self.log_admin_action(AdminActionKind::StakeProvidersRemoved(providers));
+    }
+
+    /// Same as claimRewards for a single week, but the signed delegation amount is also checked
+    /// against the caller's active stake in the given provider, read through an async call.
+    /// The rewards are computed with the lower of the two amounts, so the signed amount stays the upper bound,
+    /// and users who undelegated since the checkpoint are not paid for stake they no longer have.
+    /// The rewards are only sent in the callback.
+    #[endpoint(claimRewardsWithStakeQuery)]
+    fn claim_rewards_with_stake_query(
+        &self,
+        provider: ManagedAddress,
+        week: Week,
+        user_delegation_amount: BigUint,
+        user_lkmex_staked_amount: BigUint,
+        valid_until_epoch: Epoch,
+        signature: Signature<Self::Api>,
+    ) {
+        self.require_claims_not_paused();
+        require!(
+            self.stake_providers().contains(&provider),
+            "Provider not whitelisted"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let current_epoch = self.blockchain().get_block_epoch();
+        let pending_mapper = self.pending_stake_query_epoch(&caller);
+        require!(
+            pending_mapper.is_empty()
+                || pending_mapper.get() + STAKE_QUERY_TIMEOUT_EPOCHS <= current_epoch,
+            "Stake query already in progress"
+        );
+
+        let current_week = self.get_current_week();
+        require!(
+            !self.is_week_claimed(&caller, week),
+            "Already claimed rewards for this week"
+        );
+        require!(
+            self.is_claim_in_time(
+                week,
+                current_week,
+                self.rewards_nr_first_grace_weeks().get()
+            ),
+            "Claiming too late"
+        );
+        require!(
+            week <= self.get_last_checkpoint_week(),
+            "No checkpoint for week yet"
+        );
+        require!(
+            self.is_checkpoint_finalized(week),
+            "Checkpoint not finalized yet"
+        );
+
+        self.verify_signature(
+            week,
+            &caller,
+            &user_delegation_amount,
+            &user_lkmex_staked_amount,
+            valid_until_epoch,
+            &signature,
+        );
+
+        pending_mapper.set(current_epoch);
+        self.stake_provider_proxy(provider)
+            .get_user_active_stake(caller.clone())
+            .async_call()
+            .with_callback(self.callbacks().stake_query_callback(
+                caller,
+                week,
+                user_delegation_amount,
+                user_lkmex_staked_amount,
+            ))
+            .call_and_exit()
+    }
+
+    /// Nothing is sent if the query failed, if the claims were paused,
+    /// or if the week was claimed through another flow in the meantime.
+    /// If the callback fails, the pending query expires after STAKE_QUERY_TIMEOUT_EPOCHS.
+    #[callback]
+    fn stake_query_callback(
+        &self,
+        user: ManagedAddress,
+        week: Week,
+        signed_delegation_amount: BigUint,
+        user_lkmex_staked_amount: BigUint,
+        #[call_result] result: ManagedAsyncCallResult<BigUint>,
+    ) {
+        self.pending_stake_query_epoch(&user).clear();
+
+        if let ManagedAsyncCallResult::Ok(active_stake) = result {
+            if self.globally_paused().get()
+                || !self.not_paused()
+                || self.is_week_claimed(&user, week)
+            {
+                return;
+            }
+
+            let user_delegation_amount = core::cmp::min(active_stake, signed_delegation_amount);
+            let mut args = ClaimArgsArray::new();
+            args.push(self.mark_week_claimed(
+                &user,
+                week,
+                user_delegation_amount,
+                user_lkmex_staked_amount,
+            ));
+
+            let current_week = self.get_current_week();
+            let _ = self.send_claimed_rewards_to_destination(&user, current_week, &args);
+        }
+    }
+
+    #[proxy]
+    fn stake_provider_proxy(
+        &self,
+        sc_address: ManagedAddress,
+    ) -> stake_provider_proxy::Proxy<Self::Api>;
+
+    #[view(getStakeProviders)]
+    #[storage_mapper("stakeProviders")]
+    fn stake_providers(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[view(getPendingStakeQueryEpoch)]
+    #[storage_mapper("pendingStakeQueryEpoch")]
+    fn pending_stake_query_epoch(&self, user: &ManagedAddress) -> SingleValueMapper<Epoch>;
+}
@@ -0,0 +1,44 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::rewards::Week;
+
+#[derive(TypeAbi, TopEncode)]
+pub struct ContractStatus<M: ManagedTypeApi> {
+    pub current_week: Week,
+    pub last_checkpoint_week: Week,
+    pub nr_active_projects: usize,
+    pub claims_paused: bool,
+    pub globally_paused: bool,
+    pub signers: ManagedVec<M, ManagedAddress<M>>,
+}
+
+/// Summary of the SC's state, meant for monitoring, e.g. alerting when
+/// the last checkpoint week falls behind the current week.
+#[elrond_wasm::module]
+pub trait StatusModule:
+    elrond_wasm_modules::pause::PauseModule
+    + crate::project::ProjectModule
+    + crate::rewards::RewardsModule
+    + crate::global_pause::GlobalPauseModule
+    + crate::common_storage::CommonStorageModule
+{
+    #[label("metabonding-external-view")]
+    #[view(getContractStatus)]
+    fn get_contract_status(&self) -> ContractStatus<Self::Api> {
+        let current_week = self.get_current_week();
+        let mut signers = ManagedVec::new();
+        for signer in self.signers().iter() {
+            signers.push(signer);
+        }
+
+        ContractStatus {
+            current_week,
+            last_checkpoint_week: self.get_last_checkpoint_week(),
+            nr_active_projects: self.get_nr_active_projects(current_week),
+            claims_paused: self.is_paused(),
+            globally_paused: self.globally_paused().get(),
+            signers,
+        }
+    }
+}
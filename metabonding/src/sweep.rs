@@ -0,0 +1,454 @@
+elrond_wasm::imports!();
+
+use crate::{
+    admin::Role,
+    audit_log::AdminActionKind,
+    project::{Project, ProjectId},
+    rewards::Week,
+};
+
+#[elrond_wasm::module]
+pub trait SweepModule:
+    elrond_wasm_modules::pause::PauseModule
+    + crate::project::ProjectModule
+    + crate::access_control::AccessControlModule
+    + crate::common_storage::CommonStorageModule
+    + crate::math::MathModule
+    + crate::validation::ValidationModule
+    + crate::rewards::RewardsModule
+    + crate::claim::ClaimModule
+    + crate::claimed_weeks::ClaimedWeeksModule
+    + crate::energy_factory::EnergyFactoryModule
+    + crate::events::EventsModule
+    + crate::oracle::OracleModule
+    + crate::keeper::KeeperModule
+    + crate::project_proposal::ProjectProposalModule
+    + sc_whitelist_module::SCWhitelistModule
+{
+    /// Sets the number of weeks after which a week's rewards can no longer be claimed.
+    /// Also used to determine when a project is expired.
+    /// Can only be increased, so no claimable week expires early. Weeks already swept stay closed.
+    #[only_owner]
+    #[endpoint(setClaimExpirationWeeks)]
+    fn set_claim_expiration_weeks(&self, claim_expiration_weeks: Week) {
+        self.require_no_timelock();
+
+        self.apply_claim_expiration_weeks(claim_expiration_weeks);
+    }
+
+    fn apply_claim_expiration_weeks(&self, claim_expiration_weeks: Week) {
+        require!(
+            claim_expiration_weeks > self.get_claim_expiration_weeks(),
+            "Expiration weeks can only be increased"
+        );
+
+        self.claim_expiration_weeks().set(claim_expiration_weeks);
+        self.log_admin_action(AdminActionKind::ClaimExpirationWeeksChanged(
+            claim_expiration_weeks,
+        ));
+    }
+
+    #[only_owner]
+    #[endpoint(setTreasuryAddress)]
+    fn set_treasury_address(&self, treasury_address: ManagedAddress) {
+        self.require_no_timelock();
+
+        self.apply_treasury_address(treasury_address);
+    }
+
+    fn apply_treasury_address(&self, treasury_address: ManagedAddress) {
+        self.treasury_address().set(&treasury_address);
+        self.log_admin_action(AdminActionKind::TreasuryAddressChanged(treasury_address));
+    }
+
+    /// Records tokens that were transferred to the SC by mistake as a failed deposit of the given depositor,
+    /// who takes them back through reclaimFailedDeposit. Failed deposits through the SC's endpoints are reverted,
+    /// so this is only needed for direct transfers.
+    /// Tokens used by any project, pending project proposal, users' dust or the keeper bounty pool cannot be rescued.
+    #[only_owner]
+    #[endpoint(rescueToken)]
+    fn rescue_token(
+        &self,
+        token_id: EgldOrEsdtTokenIdentifier,
+        token_nonce: u64,
+        amount: BigUint,
+        depositor: ManagedAddress,
+    ) {
+        require!(amount > 0, "Invalid amount");
+        require!(!self.is_token_in_use(&token_id), "Token is used by the SC");
+
+        let balance = self.blockchain().get_sc_balance(&token_id, token_nonce);
+        let reward_token_id = self.egld_or_esdt_to_reward_token(token_id.clone());
+        let total_failed_mapper = self.total_failed_deposits(&reward_token_id, token_nonce);
+        let total_failed = total_failed_mapper.get();
+        require!(
+            balance >= total_failed && amount <= balance - &total_failed,
+            "Insufficient balance"
+        );
+
+        total_failed_mapper.set(&(total_failed + &amount));
+        self.failed_deposits(&depositor).update(|deposits| {
+            self.merge_payment(deposits, reward_token_id, token_nonce, amount.clone())
+        });
+        self.log_admin_action(AdminActionKind::TokenRescued(
+            token_id,
+            token_nonce,
+            amount,
+            depositor,
+        ));
+    }
+
+    /// Sends the caller's failed deposits, recorded through rescueToken, back to them.
+    #[endpoint(reclaimFailedDeposit)]
+    fn reclaim_failed_deposit(&self) -> ManagedVec<EsdtTokenPayment> {
+        let caller = self.blockchain().get_caller();
+        let deposits = self.failed_deposits(&caller).take();
+        require!(!deposits.is_empty(), "No failed deposits");
+
+        for deposit in &deposits {
+            self.total_failed_deposits(&deposit.token_identifier, deposit.token_nonce)
+                .update(|total| *total -= &deposit.amount);
+        }
+        self.send_rewards(&caller, &deposits);
+
+        deposits
+    }
+
+    /// Withdraws the part of the SC's balance of the given token and nonce which is not owed to anyone,
+    /// i.e. the amount above the token's liabilities, to the owner.
+    /// The nonce is 0 for EGLD and fungible tokens.
+    #[only_owner]
+    #[endpoint(emergencyWithdraw)]
+    fn emergency_withdraw(
+        &self,
+        token_id: EgldOrEsdtTokenIdentifier,
+        token_nonce: u64,
+        amount: BigUint,
+    ) {
+        self.require_no_timelock();
+
+        self.apply_emergency_withdraw(token_id, token_nonce, amount);
+    }
+
+    fn apply_emergency_withdraw(
+        &self,
+        token_id: EgldOrEsdtTokenIdentifier,
+        token_nonce: u64,
+        amount: BigUint,
+    ) {
+        require!(amount > 0, "Invalid amount");
+
+        let balance = self.blockchain().get_sc_balance(&token_id, token_nonce);
+        let liabilities = self.get_token_liabilities(token_id.clone(), token_nonce);
+        require!(
+            balance > liabilities && amount <= balance - liabilities,
+            "Amount exceeds the withdrawable balance"
+        );
+
+        let owner = self.blockchain().get_owner_address();
+        self.send().direct(&owner, &token_id, token_nonce, &amount);
+        self.log_admin_action(AdminActionKind::EmergencyWithdrawal(
+            token_id,
+            token_nonce,
+            amount,
+        ));
+    }
+
+    /// Returns the amount of the given token the SC owes: the projects' deposited rewards not yet paid out,
+    /// including the rewards kept for paused projects and the partner rewards, the users' dust,
+    /// the failed deposits, the listing fees and the keeper and cleanup bounty pools.
+    /// Projects can't be removed while they have paused or pending locked rewards, so those are never left out.
+    /// Only the projects and partner rewards paid in the given nonce are counted. The listing fees and bounty pools
+    /// are always fungible, so they are only counted for nonce 0. The users' dust is counted for every nonce.
+    #[label("metabonding-external-view")]
+    #[view(getTokenLiabilities)]
+    fn get_token_liabilities(
+        &self,
+        token_id: EgldOrEsdtTokenIdentifier,
+        token_nonce: u64,
+    ) -> BigUint {
+        let mut liabilities = BigUint::zero();
+        let is_fungible = token_nonce == 0;
+
+        let keeper_bounty_token_mapper = self.keeper_bounty_token();
+        if is_fungible
+            && !keeper_bounty_token_mapper.is_empty()
+            && keeper_bounty_token_mapper.get() == token_id
+        {
+            liabilities += self.keeper_bounty_pool().get();
+        }
+
+        let cleanup_bounty_token_mapper = self.cleanup_bounty_token();
+        if is_fungible
+            && !cleanup_bounty_token_mapper.is_empty()
+            && cleanup_bounty_token_mapper.get() == token_id
+        {
+            liabilities += self.cleanup_bounty_pool().get();
+        }
+
+        let reward_token_id = self.egld_or_esdt_to_reward_token(token_id.clone());
+        liabilities += self.total_dust(&reward_token_id).get();
+        liabilities += self
+            .total_failed_deposits(&reward_token_id, token_nonce)
+            .get();
+
+        for proposal in self.pending_projects().values() {
+            if is_fungible && proposal.fee_token == token_id {
+                liabilities += proposal.fee_amount;
+            }
+        }
+
+        for (project_id, project) in self.projects().iter() {
+            if reward_token_id == project.reward_token
+                && self.project_reward_token_nonce(&project_id).get() == token_nonce
+            {
+                liabilities += self.leftover_project_funds(&project_id).get();
+                liabilities += self.total_paused_project_rewards(&project_id).get();
+                liabilities += self.total_pending_locked_rewards(&project_id).get();
+            }
+
+            for partner_reward in self.project_partner_rewards(&project_id).iter() {
+                if reward_token_id == partner_reward.token
+                    && partner_reward.token_nonce == token_nonce
+                {
+                    liabilities += partner_reward.leftover_funds;
+                }
+            }
+
+            let escrow_mapper = self.listing_fee_escrow(&project_id);
+            if !escrow_mapper.is_empty() {
+                let escrow = escrow_mapper.get();
+                if is_fungible && escrow.token_identifier == token_id {
+                    liabilities += escrow.amount;
+                }
+            }
+        }
+
+        liabilities
+    }
+
+    fn is_token_in_use(&self, token_id: &EgldOrEsdtTokenIdentifier) -> bool {
+        let keeper_bounty_token_mapper = self.keeper_bounty_token();
+        if !keeper_bounty_token_mapper.is_empty() && &keeper_bounty_token_mapper.get() == token_id {
+            return true;
+        }
+
+        let cleanup_bounty_token_mapper = self.cleanup_bounty_token();
+        if !cleanup_bounty_token_mapper.is_empty() && &cleanup_bounty_token_mapper.get() == token_id
+        {
+            return true;
+        }
+
+        let reward_token_id = self.egld_or_esdt_to_reward_token(token_id.clone());
+        if self.total_dust(&reward_token_id).get() > 0 {
+            return true;
+        }
+
+        for proposal in self.pending_projects().values() {
+            if &proposal.fee_token == token_id {
+                return true;
+            }
+        }
+
+        for (project_id, project) in self.projects().iter() {
+            if reward_token_id == project.reward_token {
+                return true;
+            }
+
+            for partner_reward in self.project_partner_rewards(&project_id).iter() {
+                if reward_token_id == partner_reward.token {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Clears the checkpoints, and the related per-week data, of the weeks that can no longer be claimed.
+    /// Weeks that were already pruned are skipped.
+    #[endpoint(pruneCheckpoints)]
+    fn prune_checkpoints(&self, up_to_week: Week) {
+        self.require_caller_role(Role::CheckpointSubmitter);
+
+        let current_week = self.get_current_week();
+        let rewards_nr_first_grace_weeks = self.rewards_nr_first_grace_weeks().get();
+        require!(
+            up_to_week <= self.get_last_checkpoint_week()
+                && !self.is_claim_in_time(up_to_week, current_week, rewards_nr_first_grace_weeks),
+            "Week not expired"
+        );
+
+        let pruned_mapper = self.pruned_checkpoints_up_to_week();
+        let first_week = pruned_mapper.get() + 1;
+        require!(first_week <= up_to_week, "Nothing to prune");
+
+        let legacy_len = self.legacy_rewards_checkpoints().len();
+        for week in first_week..=up_to_week {
+            let _ = self.rewards_checkpoints().remove(&week);
+            if week <= legacy_len {
+                self.legacy_rewards_checkpoints().clear_entry(week);
+            }
+
+            self.checkpoint_status(week).clear();
+            self.checkpoint_finalization_epoch(week).clear();
+            self.week_has_claims(week).clear();
+            self.week_merkle_root(week).clear();
+            self.bonus_week_multiplier_bps(week).clear();
+            self.week_registered_energy(week).clear();
+            self.week_active_projects(week).clear();
+        }
+
+        pruned_mapper.set(up_to_week);
+        self.log_admin_action(AdminActionKind::CheckpointsPruned(up_to_week));
+    }
+
+    /// Sends the unclaimed rewards of the project's expired weeks to the treasury.
+    /// Each week is only swept once. Returns the swept amount.
+    #[endpoint(sweepExpiredRewards)]
+    fn sweep_expired_rewards(&self, project_id: ProjectId<Self::Api>) -> BigUint {
+        self.require_caller_role(Role::Treasurer);
+        self.require_no_timelock();
+
+        self.apply_sweep_expired_rewards(project_id)
+    }
+
+    fn apply_sweep_expired_rewards(&self, project_id: ProjectId<Self::Api>) -> BigUint {
+        let treasury_mapper = self.treasury_address();
+        require!(!treasury_mapper.is_empty(), "Treasury address not set");
+        require!(
+            self.funded_projects().contains(&project_id),
+            "Rewards not deposited"
+        );
+
+        let project = self.get_project_or_panic(&project_id);
+        let last_expired_week = self
+            .get_project_last_expired_week(&project)
+            .unwrap_or_else(|| sc_panic!("No expired weeks to sweep"));
+        let last_swept_week_mapper = self.last_swept_week(&project_id);
+        let first_week = core::cmp::max(last_swept_week_mapper.get() + 1, project.start_week);
+        require!(first_week <= last_expired_week, "No expired weeks to sweep");
+
+        let mut swept_amount = BigUint::zero();
+        for week in first_week..=last_expired_week {
+            let weekly_reward_supply =
+                self.get_project_week_reward_supply(&project_id, &project, week);
+            let claimed = self.claimed_project_week_rewards(&project_id, week).take();
+            self.delegation_rounding_remainder(&project_id, week)
+                .clear();
+            self.lkmex_rounding_remainder(&project_id, week).clear();
+            if claimed < weekly_reward_supply {
+                swept_amount += &weekly_reward_supply - &claimed;
+            }
+        }
+
+        last_swept_week_mapper.set(last_expired_week);
+
+        let leftover_funds_mapper = self.leftover_project_funds(&project_id);
+        let leftover_funds = leftover_funds_mapper.get();
+        if swept_amount > leftover_funds {
+            swept_amount = leftover_funds.clone();
+        }
+        leftover_funds_mapper.set(&(leftover_funds - &swept_amount));
+        self.total_swept_project_rewards(&project_id)
+            .update(|total_swept| *total_swept += &swept_amount);
+
+        let treasury_address = treasury_mapper.get();
+        if swept_amount > 0 {
+            let reward_token_nonce = self.project_reward_token_nonce(&project_id).get();
+            self.send_reward(
+                &treasury_address,
+                &project.reward_token,
+                reward_token_nonce,
+                &swept_amount,
+            );
+        }
+
+        self.sweep_event(
+            &project_id,
+            &treasury_address,
+            last_expired_week,
+            &swept_amount,
+        );
+        self.log_admin_action(AdminActionKind::ExpiredRewardsSwept(
+            project_id,
+            swept_amount.clone(),
+        ));
+
+        swept_amount
+    }
+
+    /// Sends the rewards that were kept due to the per-user cap in the project's expired weeks to the project owner.
+    /// Capped rewards of weeks that can still be claimed are kept until those weeks expire.
+    /// May be called by a project manager or the project owner.
+    #[endpoint(refundProjectCappedRewards)]
+    fn refund_project_capped_rewards(&self, project_id: ProjectId<Self::Api>) {
+        let project = self.get_project_or_panic(&project_id);
+        self.require_caller_project_manager_or_project_owner(&project_id);
+
+        let last_expired_week = self
+            .get_project_last_expired_week(&project)
+            .unwrap_or_else(|| sc_panic!("No expired weeks to refund"));
+        let last_refund_week_mapper = self.last_capped_rewards_refund_week(&project_id);
+        let first_week = core::cmp::max(last_refund_week_mapper.get() + 1, project.start_week);
+        require!(
+            first_week <= last_expired_week,
+            "No expired weeks to refund"
+        );
+
+        let mut capped_rewards = BigUint::zero();
+        for week in first_week..=last_expired_week {
+            capped_rewards += self.project_week_capped_rewards(&project_id, week).take();
+        }
+        last_refund_week_mapper.set(last_expired_week);
+        self.project_capped_rewards(&project_id)
+            .update(|capped| *capped -= &capped_rewards);
+
+        let leftover_funds_mapper = self.leftover_project_funds(&project_id);
+        let leftover_funds = leftover_funds_mapper.get();
+        let refund_amount = core::cmp::min(capped_rewards, leftover_funds.clone());
+        require!(refund_amount > 0, "No capped rewards");
+
+        leftover_funds_mapper.set(&(leftover_funds - &refund_amount));
+
+        let project_owner = self.project_owner(&project_id).get();
+        let reward_token_nonce = self.project_reward_token_nonce(&project_id).get();
+        self.send_reward(
+            &project_owner,
+            &project.reward_token,
+            reward_token_nonce,
+            &refund_amount,
+        );
+        self.log_project_update(&project_id, b"refundProjectCappedRewards");
+    }
+
+    /// Returns the project's last week which can no longer be claimed, if any
+    fn get_project_last_expired_week(&self, project: &Project<Self::Api>) -> Option<Week> {
+        let current_week = self.get_current_week();
+        let expiration_weeks = self.get_claim_expiration_weeks();
+        if current_week <= self.rewards_nr_first_grace_weeks().get()
+            || current_week <= expiration_weeks
+        {
+            return None;
+        }
+
+        Some(core::cmp::min(
+            current_week - expiration_weeks - 1,
+            project.end_week,
+        ))
+    }
+
+    #[view(getFailedDeposits)]
+    #[storage_mapper("failedDeposits")]
+    fn failed_deposits(
+        &self,
+        depositor: &ManagedAddress,
+    ) -> SingleValueMapper<ManagedVec<EsdtTokenPayment>>;
+
+    #[storage_mapper("totalFailedDeposits")]
+    fn total_failed_deposits(
+        &self,
+        token_id: &TokenIdentifier,
+        token_nonce: u64,
+    ) -> SingleValueMapper<BigUint>;
+}
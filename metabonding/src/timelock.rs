@@ -0,0 +1,172 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::{
+    admin::Role,
+    audit_log::AdminActionKind,
+    project::{Epoch, ProjectId},
+    rewards::Week,
+    validation::MerkleHash,
+};
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub enum TimelockAction<M: ManagedTypeApi> {
+    AddSigner(ManagedAddress<M>),
+    RemoveSigner(ManagedAddress<M>),
+    ChangeSigner(ManagedAddress<M>),
+    SetProjectClaimFee(ProjectId<M>, u64),
+    SetListingFee(EgldOrEsdtTokenIdentifier<M>, BigUint<M>),
+    SweepExpiredRewards(ProjectId<M>),
+    SetTimelockDelayEpochs(Epoch),
+    EmergencyWithdraw(EgldOrEsdtTokenIdentifier<M>, u64, BigUint<M>),
+    SetWeekSchedule(Epoch, Epoch),
+    SetClaimExpirationWeeks(Week),
+    SetTreasuryAddress(ManagedAddress<M>),
+    AddOracles(ManagedVec<M, ManagedAddress<M>>),
+    RemoveOracles(ManagedVec<M, ManagedAddress<M>>),
+    SetOracleQuorum(usize),
+    AddAdmins(ManagedVec<M, ManagedAddress<M>>),
+    RemoveAdmins(ManagedVec<M, ManagedAddress<M>>),
+    GrantRole(ManagedAddress<M>, Role),
+    RevokeRole(ManagedAddress<M>, Role),
+    SetClaimSignatureThreshold(usize),
+    SetSigCheckDisabled(bool),
+    SetWeekMerkleRoot(Week, MerkleHash<M>),
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode)]
+pub struct ScheduledAction<M: ManagedTypeApi> {
+    pub action: TimelockAction<M>,
+    pub executable_from_epoch: Epoch,
+}
+
+/// Once a delay is set, the signer, oracle, admin and role changes, fee updates, sweeps of expired rewards,
+/// emergency withdrawals, and the changes to the week schedule, claim expiration, treasury address,
+/// signature threshold and signature check may no longer be called directly. The owner schedules them instead,
+/// and anyone may execute them once the delay has passed.
+#[elrond_wasm::module]
+pub trait TimelockModule:
+    crate::access_control::AccessControlModule
+    + crate::admin::AdminModule
+    + crate::project::ProjectModule
+    + crate::project_proposal::ProjectProposalModule
+    + crate::sweep::SweepModule
+    + crate::common_storage::CommonStorageModule
+    + crate::events::EventsModule
+{
+    /// Sets the initial delay. Once set, the delay can only be changed through the timelock.
+    #[only_owner]
+    #[endpoint(setTimelockDelayEpochs)]
+    fn set_timelock_delay_epochs(&self, delay_epochs: Epoch) {
+        self.require_no_timelock();
+
+        self.apply_timelock_delay_epochs(delay_epochs);
+    }
+
+    fn apply_timelock_delay_epochs(&self, delay_epochs: Epoch) {
+        self.timelock_delay_epochs().set(delay_epochs);
+        self.log_admin_action(AdminActionKind::TimelockDelayChanged(delay_epochs));
+    }
+
+    /// Schedules the action, which may be executed once the current delay has passed. Returns the action's ID.
+    #[only_owner]
+    #[endpoint(scheduleAction)]
+    fn schedule_action(&self, action: TimelockAction<Self::Api>) -> u64 {
+        let delay_epochs = self.timelock_delay_epochs().get();
+        require!(delay_epochs > 0, "Timelock not enabled");
+
+        let action_id = self.last_action_id().update(|id| {
+            *id += 1;
+            *id
+        });
+        let executable_from_epoch = self.blockchain().get_block_epoch() + delay_epochs;
+        let scheduled_action = ScheduledAction {
+            action,
+            executable_from_epoch,
+        };
+        self.action_scheduled_event(action_id, &scheduled_action);
+        self.scheduled_action(action_id).set(&scheduled_action);
+        self.log_admin_action(AdminActionKind::TimelockActionScheduled(action_id));
+
+        action_id
+    }
+
+    #[endpoint(executeAction)]
+    fn execute_action(&self, action_id: u64) {
+        let scheduled_action_mapper = self.scheduled_action(action_id);
+        require!(!scheduled_action_mapper.is_empty(), "Unknown action");
+
+        let scheduled_action = scheduled_action_mapper.take();
+        let current_epoch = self.blockchain().get_block_epoch();
+        require!(
+            current_epoch >= scheduled_action.executable_from_epoch,
+            "Action not executable yet"
+        );
+
+        match scheduled_action.action {
+            TimelockAction::AddSigner(signer) => self.apply_add_signer(signer),
+            TimelockAction::RemoveSigner(signer) => self.apply_remove_signer(signer),
+            TimelockAction::ChangeSigner(new_signer) => self.apply_change_signer(new_signer),
+            TimelockAction::SetProjectClaimFee(project_id, fee_bps) => {
+                self.apply_project_claim_fee(project_id, fee_bps)
+            }
+            TimelockAction::SetListingFee(token_id, amount) => {
+                self.apply_listing_fee(token_id, amount)
+            }
+            TimelockAction::SweepExpiredRewards(project_id) => {
+                let _ = self.apply_sweep_expired_rewards(project_id);
+            }
+            TimelockAction::SetTimelockDelayEpochs(delay_epochs) => {
+                self.apply_timelock_delay_epochs(delay_epochs)
+            }
+            TimelockAction::EmergencyWithdraw(token_id, token_nonce, amount) => {
+                self.apply_emergency_withdraw(token_id, token_nonce, amount)
+            }
+            TimelockAction::SetWeekSchedule(first_week_start_epoch, epochs_per_week) => {
+                self.apply_week_schedule(first_week_start_epoch, epochs_per_week)
+            }
+            TimelockAction::SetClaimExpirationWeeks(claim_expiration_weeks) => {
+                self.apply_claim_expiration_weeks(claim_expiration_weeks)
+            }
+            TimelockAction::SetTreasuryAddress(treasury_address) => {
+                self.apply_treasury_address(treasury_address)
+            }
+            TimelockAction::AddOracles(oracles) => self.apply_add_oracles(oracles),
+            TimelockAction::RemoveOracles(oracles) => self.apply_remove_oracles(oracles),
+            TimelockAction::SetOracleQuorum(quorum) => self.apply_oracle_quorum(quorum),
+            TimelockAction::AddAdmins(admins) => self.apply_add_admins(admins),
+            TimelockAction::RemoveAdmins(admins) => self.apply_remove_admins(admins),
+            TimelockAction::GrantRole(address, role) => self.apply_grant_role(address, role),
+            TimelockAction::RevokeRole(address, role) => self.apply_revoke_role(address, role),
+            TimelockAction::SetClaimSignatureThreshold(threshold) => {
+                self.apply_claim_signature_threshold(threshold)
+            }
+            TimelockAction::SetSigCheckDisabled(disabled) => {
+                self.apply_sig_check_disabled(disabled)
+            }
+            TimelockAction::SetWeekMerkleRoot(week, root) => {
+                self.apply_week_merkle_root(week, root)
+            }
+        }
+
+        self.action_executed_event(action_id);
+    }
+
+    #[only_owner]
+    #[endpoint(cancelAction)]
+    fn cancel_action(&self, action_id: u64) {
+        let scheduled_action_mapper = self.scheduled_action(action_id);
+        require!(!scheduled_action_mapper.is_empty(), "Unknown action");
+
+        scheduled_action_mapper.clear();
+        self.action_cancelled_event(action_id);
+        self.log_admin_action(AdminActionKind::TimelockActionCancelled(action_id));
+    }
+
+    #[view(getScheduledAction)]
+    #[storage_mapper("scheduledAction")]
+    fn scheduled_action(&self, action_id: u64) -> SingleValueMapper<ScheduledAction<Self::Api>>;
+
+    #[storage_mapper("lastActionId")]
+    fn last_action_id(&self) -> SingleValueMapper<u64>;
+}
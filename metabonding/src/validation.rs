@@ -1,36 +1,379 @@
 elrond_wasm::imports!();
 
-use crate::rewards::Week;
-use elrond_wasm::api::ED25519_SIGNATURE_BYTE_LEN;
-
-// week + caller + user_delegation_amount + user_lkmex_staked_amount
-// 4 + 32 + (4 + 32) + (4 + 32) = 108, with some extra for high BigUint values
-const MAX_DATA_LEN: usize = 120;
+use crate::{
+    audit_log::AdminActionKind,
+    project::Epoch,
+    rewards::Week,
+    signed_message::{
+        SignedMessage, CLAIM_TAG, RELAYED_CLAIM_TAG, SIGNED_MESSAGE_MAX_LEN, USER_ENERGY_TAG,
+    },
+};
+use elrond_wasm::api::{ED25519_SIGNATURE_BYTE_LEN, SHA256_RESULT_LEN};
 
 pub type Signature<M> = ManagedByteArray<M, ED25519_SIGNATURE_BYTE_LEN>;
+pub type MerkleHash<M> = ManagedByteArray<M, SHA256_RESULT_LEN>;
+pub type DataHash<M> = ManagedByteArray<M, SHA256_RESULT_LEN>;
+pub type SignerSignature<M> = MultiValue2<ManagedAddress<M>, Signature<M>>;
 
 #[elrond_wasm::module]
-pub trait ValidationModule: crate::common_storage::CommonStorageModule {
+pub trait ValidationModule:
+    crate::audit_log::AuditLogModule + crate::common_storage::CommonStorageModule
+{
+    /// Disables the claim signature checks, so claims pass with any amounts. Meant for integration testing,
+    /// and only available in builds with the `devnet` feature enabled.
+    #[only_owner]
+    #[endpoint(setSigCheckDisabled)]
+    fn set_sig_check_disabled(&self, disabled: bool) {
+        self.require_no_timelock();
+
+        self.apply_sig_check_disabled(disabled);
+    }
+
+    fn apply_week_merkle_root(&self, week: Week, root: MerkleHash<Self::Api>) {
+        self.week_merkle_root(week).set(&root);
+        self.log_admin_action(AdminActionKind::WeekMerkleRootSet(week));
+    }
+
+    fn apply_sig_check_disabled(&self, disabled: bool) {
+        require!(
+            cfg!(feature = "devnet"),
+            "Signature checks can only be disabled on devnet builds"
+        );
+
+        self.sig_check_disabled().set(disabled);
+        self.log_admin_action(AdminActionKind::SigCheckDisabledChanged(disabled));
+    }
+
+    fn is_sig_check_disabled(&self) -> bool {
+        cfg!(feature = "devnet") && self.sig_check_disabled().get()
+    }
+
+    /// The signer signs the claim message, which contains the SC address, the user's current claim nonce,
+    /// the signature's expiration epoch and the claim data, so a signature can neither be replayed
+    /// on another deployment nor used twice, nor used after the expiration epoch.
+    /// The nonce is incremented once the signature is verified.
     fn verify_signature(
         &self,
         week: Week,
         caller: &ManagedAddress,
         user_delegation_amount: &BigUint,
         user_lkmex_staked_amount: &BigUint,
+        valid_until_epoch: Epoch,
+        signature: &Signature<Self::Api>,
+    ) {
+        self.verify_batch_signature(
+            week,
+            caller,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+            valid_until_epoch,
+            signature,
+        );
+        self.increment_claim_nonce(caller);
+    }
+
+    /// Same as verify_signature, but leaves the nonce unchanged, so the signatures of a claim for several weeks
+    /// are all given for the same nonce. The caller increments the nonce once the whole batch is verified,
+    /// through increment_claim_nonce.
+    fn verify_batch_signature(
+        &self,
+        week: Week,
+        caller: &ManagedAddress,
+        user_delegation_amount: &BigUint,
+        user_lkmex_staked_amount: &BigUint,
+        valid_until_epoch: Epoch,
+        signature: &Signature<Self::Api>,
+    ) {
+        if self.is_sig_check_disabled() {
+            return;
+        }
+
+        require!(
+            self.claim_signature_threshold().get() <= 1,
+            "Multiple signatures required"
+        );
+        require!(
+            self.blockchain().get_block_epoch() <= valid_until_epoch,
+            "Signature expired"
+        );
+
+        let message = self.build_claim_message(
+            self.claim_nonce(caller).get(),
+            week,
+            caller,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+            valid_until_epoch,
+        );
+        self.require_signed_by_signer(&message, signature);
+    }
+
+    fn increment_claim_nonce(&self, user: &ManagedAddress) {
+        if self.is_sig_check_disabled() {
+            return;
+        }
+
+        self.claim_nonce(user).update(|nonce| *nonce += 1);
+    }
+
+    /// Verifies the signer's signature over the user's energy for the given week.
+    /// Uses its own nonce, so it does not interfere with the claim signatures.
+    fn verify_user_energy_signature(
+        &self,
+        user: &ManagedAddress,
+        week: Week,
+        user_energy: &BigUint,
+        valid_until_epoch: Epoch,
+        signature: &Signature<Self::Api>,
+    ) {
+        if self.is_sig_check_disabled() {
+            return;
+        }
+
+        require!(
+            self.claim_signature_threshold().get() <= 1,
+            "Multiple signatures required"
+        );
+        require!(
+            self.blockchain().get_block_epoch() <= valid_until_epoch,
+            "Signature expired"
+        );
+
+        let nonce_mapper = self.user_energy_nonce(user);
+        let nonce = nonce_mapper.get();
+        let message =
+            self.build_user_energy_message(nonce, week, user, user_energy, valid_until_epoch);
+        self.require_signed_by_signer(&message, signature);
+
+        nonce_mapper.set(nonce + 1);
+    }
+
+    fn require_signed_by_signer(
+        &self,
+        message: &SignedMessage<Self::Api>,
         signature: &Signature<Self::Api>,
     ) {
+        let valid_signature = self.get_valid_signers().iter().any(|signer| {
+            self.crypto()
+                .verify_ed25519_legacy_managed::<SIGNED_MESSAGE_MAX_LEN>(
+                    signer.as_managed_byte_array(),
+                    message.as_buffer(),
+                    signature,
+                )
+        });
+        require!(valid_signature, "Invalid signature");
+    }
+
+    /// Same as verify_signature, but requires the signatures of at least
+    /// claim signature threshold distinct signers, given along with their addresses.
+    fn verify_signatures(
+        &self,
+        week: Week,
+        caller: &ManagedAddress,
+        user_delegation_amount: &BigUint,
+        user_lkmex_staked_amount: &BigUint,
+        valid_until_epoch: Epoch,
+        signatures: MultiValueEncoded<SignerSignature<Self::Api>>,
+    ) {
+        if self.is_sig_check_disabled() {
+            return;
+        }
+
+        require!(
+            self.blockchain().get_block_epoch() <= valid_until_epoch,
+            "Signature expired"
+        );
+
+        let nonce_mapper = self.claim_nonce(caller);
+        let nonce = nonce_mapper.get();
+        let message = self.build_claim_message(
+            nonce,
+            week,
+            caller,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+            valid_until_epoch,
+        );
+
+        let valid_signers = self.get_valid_signers();
+        let mut signed_by = ManagedVec::<Self::Api, ManagedAddress>::new();
+        for signer_signature in signatures {
+            let (signer, signature) = signer_signature.into_tuple();
+            require!(valid_signers.contains(&signer), "Unknown signer");
+            require!(!signed_by.contains(&signer), "Duplicate signer signature");
+
+            let valid_signature = self
+                .crypto()
+                .verify_ed25519_legacy_managed::<SIGNED_MESSAGE_MAX_LEN>(
+                    signer.as_managed_byte_array(),
+                    message.as_buffer(),
+                    &signature,
+                );
+            require!(valid_signature, "Invalid signature");
+
+            signed_by.push(signer);
+        }
+
+        let threshold = core::cmp::max(self.claim_signature_threshold().get(), 1);
+        require!(signed_by.len() >= threshold, "Not enough signatures");
+
+        nonce_mapper.set(nonce + 1);
+    }
+
+    fn build_claim_message(
+        &self,
+        nonce: u64,
+        week: Week,
+        caller: &ManagedAddress,
+        user_delegation_amount: &BigUint,
+        user_lkmex_staked_amount: &BigUint,
+        valid_until_epoch: Epoch,
+    ) -> SignedMessage<Self::Api> {
+        SignedMessage::new(CLAIM_TAG, &self.blockchain().get_sc_address(), nonce)
+            .with_field(&valid_until_epoch)
+            .with_field(&week)
+            .with_field(caller)
+            .with_field(user_delegation_amount)
+            .with_field(user_lkmex_staked_amount)
+    }
+
+    fn build_user_energy_message(
+        &self,
+        nonce: u64,
+        week: Week,
+        user: &ManagedAddress,
+        user_energy: &BigUint,
+        valid_until_epoch: Epoch,
+    ) -> SignedMessage<Self::Api> {
+        SignedMessage::new(USER_ENERGY_TAG, &self.blockchain().get_sc_address(), nonce)
+            .with_field(&valid_until_epoch)
+            .with_field(&week)
+            .with_field(user)
+            .with_field(user_energy)
+    }
+
+    fn build_relayed_claim_message(
+        &self,
+        nonce: u64,
+        week: Week,
+        user: &ManagedAddress,
+        user_delegation_amount: &BigUint,
+        user_lkmex_staked_amount: &BigUint,
+    ) -> SignedMessage<Self::Api> {
+        SignedMessage::new(
+            RELAYED_CLAIM_TAG,
+            &self.blockchain().get_sc_address(),
+            nonce,
+        )
+        .with_field(&week)
+        .with_field(user)
+        .with_field(user_delegation_amount)
+        .with_field(user_lkmex_staked_amount)
+    }
+
+    /// Verifies that the user agreed to have the claim relayed by someone else.
+    /// The user signs the relayed claim message, which contains the SC address,
+    /// their current relayed claim nonce and the claim data, using their own address' key.
+    fn verify_relayed_claim_intent(
+        &self,
+        user: &ManagedAddress,
+        nonce: u64,
+        week: Week,
+        user_delegation_amount: &BigUint,
+        user_lkmex_staked_amount: &BigUint,
+        user_signature: &Signature<Self::Api>,
+    ) {
+        let message = self.build_relayed_claim_message(
+            nonce,
+            week,
+            user,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+        );
+
+        let valid_signature = self
+            .crypto()
+            .verify_ed25519_legacy_managed::<SIGNED_MESSAGE_MAX_LEN>(
+                user.as_managed_byte_array(),
+                message.as_buffer(),
+                user_signature,
+            );
+        require!(valid_signature, "Invalid user signature");
+    }
+
+    /// The leaf is the sha256 of the claim data, i.e. the week, the user and their amounts.
+    /// Pairs are hashed in sorted order, so the proof does not need to contain the leaf's position.
+    fn verify_merkle_proof(
+        &self,
+        week: Week,
+        caller: &ManagedAddress,
+        user_delegation_amount: &BigUint,
+        user_lkmex_staked_amount: &BigUint,
+        proof: MultiValueEncoded<MerkleHash<Self::Api>>,
+    ) {
+        let root_mapper = self.week_merkle_root(week);
+        require!(!root_mapper.is_empty(), "No merkle root for week");
+
+        let data = self.encode_claim_data(
+            week,
+            caller,
+            user_delegation_amount,
+            user_lkmex_staked_amount,
+        );
+        let mut computed_hash = self.crypto().sha256(&data);
+        for proof_hash in proof {
+            let mut pair = ManagedBuffer::new();
+            if computed_hash.to_byte_array() <= proof_hash.to_byte_array() {
+                pair.append(computed_hash.as_managed_buffer());
+                pair.append(proof_hash.as_managed_buffer());
+            } else {
+                pair.append(proof_hash.as_managed_buffer());
+                pair.append(computed_hash.as_managed_buffer());
+            }
+
+            computed_hash = self.crypto().sha256(&pair);
+        }
+
+        require!(computed_hash == root_mapper.get(), "Invalid merkle proof");
+    }
+
+    fn encode_claim_data(
+        &self,
+        week: Week,
+        caller: &ManagedAddress,
+        user_delegation_amount: &BigUint,
+        user_lkmex_staked_amount: &BigUint,
+    ) -> ManagedBuffer {
         let mut data = ManagedBuffer::new();
         let _ = week.dep_encode(&mut data);
         data.append(caller.as_managed_buffer());
         let _ = user_delegation_amount.dep_encode(&mut data);
         let _ = user_lkmex_staked_amount.dep_encode(&mut data);
 
-        let signer: ManagedAddress = self.signer().get();
-        let valid_signature = self.crypto().verify_ed25519_legacy_managed::<MAX_DATA_LEN>(
-            signer.as_managed_byte_array(),
-            &data,
-            signature,
-        );
-        require!(valid_signature, "Invalid signature");
+        data
     }
+
+    #[view(isSigCheckDisabled)]
+    #[storage_mapper("sigCheckDisabled")]
+    fn sig_check_disabled(&self) -> SingleValueMapper<bool>;
+
+    #[view(getClaimNonce)]
+    #[storage_mapper("claimNonce")]
+    fn claim_nonce(&self, user: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[view(getUserEnergyNonce)]
+    #[storage_mapper("userEnergyNonce")]
+    fn user_energy_nonce(&self, user: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[view(getRelayedClaimNonce)]
+    #[storage_mapper("relayedClaimNonce")]
+    fn relayed_claim_nonce(&self, user: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[view(getWeekMerkleRoot)]
+    #[storage_mapper("weekMerkleRoot")]
+    fn week_merkle_root(&self, week: Week) -> SingleValueMapper<MerkleHash<Self::Api>>;
+
+    #[view(getWeekDataHash)]
+    #[storage_mapper("weekDataHash")]
+    fn week_data_hash(&self, week: Week) -> SingleValueMapper<DataHash<Self::Api>>;
 }
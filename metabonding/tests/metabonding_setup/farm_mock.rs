@@ -0,0 +1,41 @@
+elrond_wasm::imports!();
+
+/// Enters the farm by sending back one farm token for each farming token received.
+/// The mock's farm token balance is set by the tests.
+#[elrond_wasm::contract]
+pub trait FarmMock {
+    #[init]
+    fn init(&self, farm_token_id: TokenIdentifier) {
+        self.farm_token_id().set(&farm_token_id);
+    }
+
+    #[payable("*")]
+    #[endpoint(enterFarm)]
+    fn enter_farm(
+        &self,
+        opt_orig_caller: OptionalValue<ManagedAddress>,
+    ) -> MultiValue2<EsdtTokenPayment, EsdtTokenPayment> {
+        let payment = self.call_value().single_esdt();
+        if let OptionalValue::Some(orig_caller) = opt_orig_caller {
+            self.last_orig_caller().set(&orig_caller);
+        }
+
+        let farm_tokens = EsdtTokenPayment::new(self.farm_token_id().get(), 1, payment.amount);
+        let caller = self.blockchain().get_caller();
+        self.send().direct_esdt(
+            &caller,
+            &farm_tokens.token_identifier,
+            farm_tokens.token_nonce,
+            &farm_tokens.amount,
+        );
+
+        let boosted_rewards = EsdtTokenPayment::new(payment.token_identifier, 0, BigUint::zero());
+        (farm_tokens, boosted_rewards).into()
+    }
+
+    #[storage_mapper("farmTokenId")]
+    fn farm_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
+
+    #[storage_mapper("lastOrigCaller")]
+    fn last_orig_caller(&self) -> SingleValueMapper<ManagedAddress>;
+}
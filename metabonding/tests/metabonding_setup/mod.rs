@@ -1,27 +1,57 @@
+pub mod farm_mock;
+
 use elrond_wasm::{
     api::ED25519_SIGNATURE_BYTE_LEN,
     elrond_codec::multi_types::OptionalValue,
-    types::{Address, MultiValueEncoded},
+    types::{
+        Address, EgldOrEsdtTokenIdentifier, ManagedAsyncCallError, ManagedAsyncCallResult,
+        MultiValueEncoded,
+    },
 };
 use elrond_wasm_debug::{
     managed_address, managed_biguint, managed_buffer, managed_token_id, rust_biguint,
     testing_framework::{BlockchainStateWrapper, ContractObjWrapper},
-    tx_mock::TxResult,
+    tx_mock::{TxInputESDT, TxResult},
     DebugApi,
 };
 use elrond_wasm_modules::pause::PauseModule;
 use metabonding::rewards::RewardsModule;
 use metabonding::*;
-use metabonding::{claim::ClaimModule, project::ProjectModule};
+use metabonding::{
+    access_control::AccessControlModule,
+    admin::{AdminModule, Role},
+    auto_checkpoint::AutoCheckpointModule,
+    claim::ClaimModule,
+    claimed_weeks::ClaimedWeeksModule,
+    compound::CompoundModule,
+    energy_factory::EnergyFactoryModule,
+    global_pause::GlobalPauseModule,
+    keeper::KeeperModule,
+    oracle::OracleModule,
+    ownership::OwnershipModule,
+    project::DistributionMode,
+    project::ProjectModule,
+    project_proposal::ProjectProposalModule,
+    reward_token_whitelist::RewardTokenWhitelistModule,
+    stake_verification::StakeVerificationModule,
+    sweep::SweepModule,
+};
 use metabonding::{
     common_storage::{CommonStorageModule, EPOCHS_IN_WEEK},
-    rewards::Week,
+    rewards::{CheckpointStatus, Week},
+    validation::ValidationModule,
 };
+use sc_whitelist_module::SCWhitelistModule;
 
 // associated private key - used for generating the signatures (please don't steal my funds)
 // 3eb200ef228e593d49a522f92587889fedfc091629d175873b64ca0ab3b4514d52773868c13654355cca16adb389b09201fabf5d9d4b795ebbdae5b361b46f20
 pub static SIGNER_ADDRESS: [u8; 32] =
     hex_literal::hex!("52773868c13654355cca16adb389b09201fabf5d9d4b795ebbdae5b361b46f20");
+// expiration epoch of the claim signatures used in tests
+pub const SIGNATURE_VALID_UNTIL_EPOCH: u64 = 1_000;
+// fixed, so the SC address can be part of signed messages
+pub static METABONDING_SC_ADDRESS: [u8; 32] =
+    hex_literal::hex!("000000000000000005006d657461626f6e64696e675f5f5f5f5f5f5f5f5f5f5f");
 pub static FIRST_PROJ_ID: &[u8] = b"FirstProj";
 pub static SECOND_PROJ_ID: &[u8] = b"SecondProj";
 pub static FIRST_PROJ_TOKEN: &[u8] = b"PROJ-123456";
@@ -29,6 +59,15 @@ pub static SECOND_PROJ_TOKEN: &[u8] = b"COOL-123456";
 pub const TOTAL_FIRST_PROJ_TOKENS: u64 = 1_000_000_000;
 pub const TOTAL_SECOND_PROJ_TOKENS: u64 = 2_000_000_000;
 
+/// Returns the identifiers of the events emitted by the transaction, in order
+pub fn get_event_identifiers(tx_result: &TxResult) -> Vec<Vec<u8>> {
+    tx_result
+        .result_logs
+        .iter()
+        .map(|log| log.topics[0].clone())
+        .collect()
+}
+
 pub struct MetabondingSetup<MetabondingObjBuilder>
 where
     MetabondingObjBuilder: 'static + Copy + Fn() -> metabonding::ContractObj<DebugApi>,
@@ -81,7 +120,8 @@ where
         let current_epoch = 5;
         b_mock.set_block_epoch(current_epoch);
 
-        let mb_wrapper = b_mock.create_sc_account(
+        let mb_wrapper = b_mock.create_sc_account_fixed_address(
+            &Address::from(&METABONDING_SC_ADDRESS),
             &rust_zero,
             Some(&owner_addr),
             builder,
@@ -97,7 +137,7 @@ where
                 );
 
                 assert_eq!(sc.first_week_start_epoch().get(), 5);
-                assert_eq!(sc.signer().get(), signer_addr);
+                assert!(sc.signers().contains(&signer_addr));
                 assert!(sc.is_paused());
             })
             .assert_ok();
@@ -177,6 +217,21 @@ where
         self.b_mock.set_block_epoch(epoch);
     }
 
+    pub fn call_set_week_schedule(
+        &mut self,
+        first_week_start_epoch: u64,
+        epochs_per_week: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_week_schedule(first_week_start_epoch, epochs_per_week);
+            },
+        )
+    }
+
     pub fn advance_one_week(&mut self) {
         self.current_epoch += EPOCHS_IN_WEEK;
         self.b_mock.set_block_epoch(self.current_epoch);
@@ -193,6 +248,17 @@ where
         week
     }
 
+    pub fn get_last_checkpoint_week(&mut self) -> Week {
+        let mut week = 0;
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                week = sc.get_last_checkpoint_week();
+            })
+            .assert_ok();
+
+        week
+    }
+
     pub fn call_unpause(&mut self) -> TxResult {
         self.b_mock.execute_tx(
             &self.owner_addr,
@@ -204,6 +270,39 @@ where
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn call_add_project_with_details(
+        &mut self,
+        project_id: &[u8],
+        project_owner: &Address,
+        name: &[u8],
+        website_hash: &[u8; 32],
+        reward_token: &[u8],
+        delegation_reward_supply: u64,
+        lkmex_reward_supply: u64,
+        start_week: Week,
+        end_week: Week,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.add_project_with_details(
+                    managed_buffer!(project_id),
+                    managed_address!(project_owner),
+                    managed_buffer!(name),
+                    website_hash.into(),
+                    managed_token_id!(reward_token),
+                    managed_biguint!(delegation_reward_supply),
+                    managed_biguint!(lkmex_reward_supply),
+                    start_week,
+                    end_week,
+                );
+            },
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn call_add_project(
         &mut self,
@@ -220,7 +319,7 @@ where
             &self.mb_wrapper,
             &rust_biguint!(0),
             |sc| {
-                sc.add_project(
+                let _ = sc.add_project(
                     managed_buffer!(project_id),
                     managed_address!(project_owner),
                     managed_token_id!(reward_token),
@@ -228,189 +327,2339 @@ where
                     start_week,
                     duration_weeks,
                     lkmex_rewards_percentage,
+                    OptionalValue::None,
                 );
             },
         )
     }
 
-    pub fn call_remove_project(&mut self, project_id: &[u8]) -> TxResult {
+    pub fn call_set_egld_listing_fee(&mut self, amount: u64) -> TxResult {
         self.b_mock.execute_tx(
             &self.owner_addr,
             &self.mb_wrapper,
             &rust_biguint!(0),
             |sc| {
-                sc.remove_project(managed_buffer!(project_id));
+                sc.set_listing_fee(EgldOrEsdtTokenIdentifier::egld(), managed_biguint!(amount));
             },
         )
     }
 
-    pub fn call_clear_expired_projects(&mut self) -> TxResult {
+    #[allow(clippy::too_many_arguments)]
+    pub fn call_propose_project(
+        &mut self,
+        caller: &Address,
+        egld_fee: u64,
+        project_id: &[u8],
+        reward_token: &[u8],
+        reward_supply: u64,
+        start_week: Week,
+        duration_weeks: Week,
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(egld_fee), |sc| {
+                sc.propose_project(
+                    managed_buffer!(project_id),
+                    managed_token_id!(reward_token),
+                    managed_biguint!(reward_supply),
+                    start_week,
+                    duration_weeks,
+                    0,
+                );
+            })
+    }
+
+    pub fn call_approve_project(&mut self, project_id: &[u8]) -> TxResult {
         self.b_mock.execute_tx(
             &self.owner_addr,
             &self.mb_wrapper,
             &rust_biguint!(0),
             |sc| {
-                let _ = sc.clear_expired_projects();
+                sc.approve_project(managed_buffer!(project_id));
             },
         )
     }
 
-    pub fn get_all_project_ids(&mut self) -> Vec<Vec<u8>> {
-        let mut all_ids = Vec::new();
+    pub fn call_reject_project(&mut self, project_id: &[u8]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.reject_project(managed_buffer!(project_id));
+            },
+        )
+    }
+
+    pub fn call_forfeit_listing_fee(&mut self, project_id: &[u8]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.first_user_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.forfeit_listing_fee(managed_buffer!(project_id));
+            },
+        )
+    }
+
+    pub fn get_pending_project_ids(&mut self) -> Vec<Vec<u8>> {
+        let mut pending_ids = Vec::new();
 
         self.b_mock
             .execute_query(&self.mb_wrapper, |sc| {
-                let result = sc.get_all_project_ids();
+                let result = sc.get_pending_project_ids();
 
                 for id in &result.to_vec() {
-                    all_ids.push(id.to_boxed_bytes().as_slice().to_vec());
+                    pending_ids.push(id.to_boxed_bytes().as_slice().to_vec());
                 }
             })
             .assert_ok();
 
-        all_ids
+        pending_ids
     }
 
-    pub fn get_project_by_id(&mut self, proj_id: &[u8]) -> (Vec<u8>, u64, u64, Week, Week) {
-        let mut token = Vec::new();
-        let mut reward_amount = 0;
-        let mut lkmex_rewards_supply = 0;
-        let mut start_week = 0;
-        let mut duration = 0;
+    pub fn call_set_project_category(&mut self, project_id: &[u8], category: &[u8]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_project_category(managed_buffer!(project_id), managed_buffer!(category));
+            },
+        )
+    }
+
+    pub fn get_projects_by_category(&mut self, category: &[u8]) -> Vec<Vec<u8>> {
+        let mut ids = Vec::new();
 
         self.b_mock
             .execute_query(&self.mb_wrapper, |sc| {
-                let result = sc.get_project_by_id(managed_buffer!(proj_id));
-                let (first, second, third, fourth, fifth) = result.into_tuple();
-                token = first.to_boxed_bytes().as_slice().to_vec();
-                reward_amount = second.to_u64().unwrap();
-                lkmex_rewards_supply = third.to_u64().unwrap();
-                start_week = fourth;
-                duration = fifth;
+                let result = sc.get_projects_by_category(managed_buffer!(category));
+
+                for id in &result.to_vec() {
+                    ids.push(id.to_boxed_bytes().as_slice().to_vec());
+                }
             })
             .assert_ok();
 
-        (
-            token,
-            reward_amount,
-            lkmex_rewards_supply,
-            start_week,
-            duration,
-        )
+        ids
     }
 
-    pub fn call_add_rewards_checkpoint(
-        &mut self,
-        week: Week,
-        total_delegation_supply: u64,
-        total_lkmex_staked: u64,
-    ) -> TxResult {
+    pub fn get_active_projects(&mut self, week: Week) -> Vec<Vec<u8>> {
+        let mut ids = Vec::new();
+
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                let result = sc.get_active_projects(week);
+
+                for id in &result.to_vec() {
+                    ids.push(id.to_boxed_bytes().as_slice().to_vec());
+                }
+            })
+            .assert_ok();
+
+        ids
+    }
+
+    pub fn call_set_max_active_projects(&mut self, max_active_projects: usize) -> TxResult {
         self.b_mock.execute_tx(
             &self.owner_addr,
             &self.mb_wrapper,
             &rust_biguint!(0),
             |sc| {
-                sc.add_rewards_checkpoint(
-                    week,
-                    managed_biguint!(total_delegation_supply),
-                    managed_biguint!(total_lkmex_staked),
-                );
+                sc.set_max_active_projects(max_active_projects);
             },
         )
     }
 
-    pub fn call_deposit_rewards(
+    pub fn call_add_named_project(
         &mut self,
-        caller: &Address,
-        project_id: &[u8],
-        token_id: &[u8],
-        amount: u64,
-    ) -> TxResult {
-        self.b_mock.execute_esdt_transfer(
-            caller,
+        name: &[u8],
+        project_owner: &Address,
+        reward_token: &[u8],
+        reward_supply: u64,
+        start_week: Week,
+        duration_weeks: Week,
+    ) -> (TxResult, Vec<u8>) {
+        let mut project_id = Vec::new();
+        let tx_result = self.b_mock.execute_tx(
+            &self.owner_addr,
             &self.mb_wrapper,
-            token_id,
-            0,
-            &rust_biguint!(amount),
+            &rust_biguint!(0),
             |sc| {
-                sc.deposit_rewards(managed_buffer!(project_id));
+                let result = sc.add_project(
+                    managed_buffer!(b""),
+                    managed_address!(project_owner),
+                    managed_token_id!(reward_token),
+                    managed_biguint!(reward_supply),
+                    start_week,
+                    duration_weeks,
+                    0,
+                    OptionalValue::Some(managed_buffer!(name)),
+                );
+                project_id = result.to_boxed_bytes().as_slice().to_vec();
             },
-        )
+        );
+
+        (tx_result, project_id)
     }
 
-    pub fn call_claim_rewards(
+    pub fn get_project_id_by_name(&mut self, name: &[u8]) -> Vec<u8> {
+        let mut project_id = Vec::new();
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                project_id = sc
+                    .project_id_by_name(&managed_buffer!(name))
+                    .get()
+                    .to_boxed_bytes()
+                    .as_slice()
+                    .to_vec();
+            })
+            .assert_ok();
+
+        project_id
+    }
+
+    pub fn call_transfer_project_ownership(
         &mut self,
         caller: &Address,
-        week: Week,
-        user_delegation_supply: u64,
-        user_lkmex_staked: u64,
-        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+        project_id: &[u8],
+        new_owner: &Address,
     ) -> TxResult {
         self.b_mock
             .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
-                let mut args = MultiValueEncoded::new();
-                args.push(
-                    (
-                        week,
-                        managed_biguint!(user_delegation_supply),
-                        managed_biguint!(user_lkmex_staked),
-                        signature.into(),
-                    )
-                        .into(),
+                sc.transfer_project_ownership(
+                    managed_buffer!(project_id),
+                    managed_address!(new_owner),
                 );
-
-                let _ = sc.claim_rewards(managed_address!(caller), args);
             })
     }
 
-    pub fn call_claim_rewards_multiple(
+    pub fn call_accept_project_ownership(
         &mut self,
         caller: &Address,
-        args: &[(Week, u64, u64, &[u8; ED25519_SIGNATURE_BYTE_LEN])],
+        project_id: &[u8],
     ) -> TxResult {
         self.b_mock
             .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
-                let mut encoded_args = MultiValueEncoded::new();
-                for arg in args {
-                    let (week, user_delegation_supply, user_lkmex_staked, signature) = *arg;
-
-                    encoded_args.push(
-                        (
-                            week,
-                            managed_biguint!(user_delegation_supply),
-                            managed_biguint!(user_lkmex_staked),
-                            signature.into(),
-                        )
-                            .into(),
-                    );
-                }
-
-                let _ = sc.claim_rewards(managed_address!(caller), encoded_args);
+                sc.accept_project_ownership(managed_buffer!(project_id));
             })
     }
 
-    pub fn get_user_claimable_weeks(&mut self, user_addr: &Address) -> Vec<Week> {
-        let mut weeks = Vec::new();
-
-        self.b_mock
-            .execute_query(&self.mb_wrapper, |sc| {
-                let result = sc.get_user_claimable_weeks(managed_address!(user_addr));
+    pub fn call_renew_project(
+        &mut self,
+        project_id: &[u8],
+        new_start_week: Week,
+        new_end_week: Week,
+        new_delegation_reward_supply: u64,
+    ) -> (TxResult, Vec<u8>) {
+        let mut new_project_id = Vec::new();
+        let tx_result = self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let result = sc.renew_project(
+                    managed_buffer!(project_id),
+                    new_start_week,
+                    new_end_week,
+                    managed_biguint!(new_delegation_reward_supply),
+                    managed_biguint!(0),
+                );
+                new_project_id = result.to_boxed_bytes().as_slice().to_vec();
+            },
+        );
 
-                for week in &result.to_vec() {
-                    weeks.push(week);
-                }
-            })
-            .assert_ok();
+        (tx_result, new_project_id)
+    }
 
-        weeks
+    pub fn call_remove_project(&mut self, project_id: &[u8]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.remove_project(managed_buffer!(project_id));
+            },
+        )
     }
 
-    pub fn get_pretty_rewards(
-        &mut self,
-        week: Week,
-        user_delegation_amount: u64,
-        user_lkmex_staked_amount: u64,
-    ) -> Vec<(Vec<u8>, Vec<u8>, u64)> {
+    pub fn call_pause_project_claims(&mut self, project_id: &[u8]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.pause_project_claims(managed_buffer!(project_id));
+            },
+        )
+    }
+
+    pub fn call_pause_project_claims_as(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.pause_project_claims(managed_buffer!(project_id));
+            })
+    }
+
+    pub fn call_update_project_reward_supply(
+        &mut self,
+        project_id: &[u8],
+        new_delegation_reward_supply: u64,
+        new_lkmex_reward_supply: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.update_project_reward_supply(
+                    managed_buffer!(project_id),
+                    managed_biguint!(new_delegation_reward_supply),
+                    managed_biguint!(new_lkmex_reward_supply),
+                );
+            },
+        )
+    }
+
+    pub fn call_set_project_reward_split(
+        &mut self,
+        project_id: &[u8],
+        lkmex_rewards_bps: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_project_reward_split(managed_buffer!(project_id), lkmex_rewards_bps);
+            },
+        )
+    }
+
+    pub fn call_set_project_emission_curve(
+        &mut self,
+        project_id: &[u8],
+        weights: &[u32],
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut args = MultiValueEncoded::new();
+                for weight in weights {
+                    args.push(*weight);
+                }
+
+                sc.set_project_emission_curve(managed_buffer!(project_id), args);
+            },
+        )
+    }
+
+    pub fn call_set_project_edge_offsets(
+        &mut self,
+        project_id: &[u8],
+        start_offset_epochs: u64,
+        end_offset_epochs: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_project_edge_offsets(
+                    managed_buffer!(project_id),
+                    start_offset_epochs,
+                    end_offset_epochs,
+                );
+            },
+        )
+    }
+
+    pub fn call_update_project_metadata(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        name: &[u8],
+        website_hash: &[u8; 32],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.update_project_metadata(
+                    managed_buffer!(project_id),
+                    managed_buffer!(name),
+                    website_hash.into(),
+                );
+            })
+    }
+
+    pub fn call_resume_project_claims(&mut self, project_id: &[u8]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.resume_project_claims(managed_buffer!(project_id));
+            },
+        )
+    }
+
+    pub fn call_resume_project_claims_as(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.resume_project_claims(managed_buffer!(project_id));
+            })
+    }
+
+    pub fn call_claim_paused_project_rewards(
+        &mut self,
+        caller: &Address,
+        project_ids: &[&[u8]],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut encoded_ids = MultiValueEncoded::new();
+                for id in project_ids {
+                    encoded_ids.push(managed_buffer!(id));
+                }
+
+                let _ = sc.claim_paused_project_rewards(encoded_ids);
+            })
+    }
+
+    pub fn call_clear_expired_projects(&mut self) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.clear_expired_projects();
+            },
+        )
+    }
+
+    pub fn call_clear_expired_project(&mut self, caller: &Address, project_id: &[u8]) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.clear_expired_project(managed_buffer!(project_id));
+            })
+    }
+
+    pub fn call_set_egld_cleanup_bounty(&mut self, amount: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_cleanup_bounty(EgldOrEsdtTokenIdentifier::egld(), managed_biguint!(amount));
+            },
+        )
+    }
+
+    pub fn call_fund_egld_cleanup_bounty(&mut self, amount: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.fund_cleanup_bounty();
+            },
+        )
+    }
+
+    pub fn call_withdraw_cleanup_bounty(&mut self, amount: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.withdraw_cleanup_bounty(managed_biguint!(amount));
+            },
+        )
+    }
+
+    pub fn call_set_energy_lookup_enabled(&mut self, enabled: bool) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_energy_lookup_enabled(enabled);
+            },
+        )
+    }
+
+    pub fn call_set_energy_factory_address(
+        &mut self,
+        energy_factory_address: &Address,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_energy_factory_address(managed_address!(energy_factory_address));
+            },
+        )
+    }
+
+    pub fn call_set_project_rewards_lock_epochs(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        lock_epochs: u64,
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.set_project_rewards_lock_epochs(managed_buffer!(project_id), lock_epochs);
+            })
+    }
+
+    pub fn call_claim_locked_rewards(&mut self, caller: &Address, project_id: &[u8]) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let _ = sc.claim_locked_rewards(managed_buffer!(project_id));
+            })
+    }
+
+    pub fn get_project_rewards_lock_epochs(&mut self, project_id: &[u8]) -> u64 {
+        let mut lock_epochs = 0;
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                lock_epochs = sc
+                    .project_rewards_lock_epochs(&managed_buffer!(project_id))
+                    .get();
+            })
+            .assert_ok();
+
+        lock_epochs
+    }
+
+    pub fn call_set_treasury_address(&mut self, treasury_address: &Address) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_treasury_address(managed_address!(treasury_address));
+            },
+        )
+    }
+
+    pub fn call_set_claim_expiration_weeks(&mut self, claim_expiration_weeks: Week) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_claim_expiration_weeks(claim_expiration_weeks);
+            },
+        )
+    }
+
+    pub fn call_prune_checkpoints(&mut self, up_to_week: Week) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.prune_checkpoints(up_to_week);
+            },
+        )
+    }
+
+    pub fn call_rescue_token(
+        &mut self,
+        token_id: &[u8],
+        amount: u64,
+        depositor: &Address,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.rescue_token(
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(token_id)),
+                    0,
+                    managed_biguint!(amount),
+                    managed_address!(depositor),
+                );
+            },
+        )
+    }
+
+    pub fn call_reclaim_failed_deposit(&mut self, caller: &Address) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let _ = sc.reclaim_failed_deposit();
+            })
+    }
+
+    pub fn call_emergency_withdraw(
+        &mut self,
+        token_id: &[u8],
+        token_nonce: u64,
+        amount: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.emergency_withdraw(
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(token_id)),
+                    token_nonce,
+                    managed_biguint!(amount),
+                );
+            },
+        )
+    }
+
+    pub fn get_token_liabilities(&mut self, token_id: &[u8], token_nonce: u64) -> u64 {
+        let mut liabilities = 0;
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                liabilities = sc
+                    .get_token_liabilities(
+                        EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(token_id)),
+                        token_nonce,
+                    )
+                    .to_u64()
+                    .unwrap();
+            })
+            .assert_ok();
+
+        liabilities
+    }
+
+    pub fn get_token_liabilities_egld(&mut self) -> u64 {
+        let mut liabilities = 0;
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                liabilities = sc
+                    .get_token_liabilities(EgldOrEsdtTokenIdentifier::egld(), 0)
+                    .to_u64()
+                    .unwrap();
+            })
+            .assert_ok();
+
+        liabilities
+    }
+
+    pub fn call_sweep_expired_rewards(&mut self, project_id: &[u8]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.sweep_expired_rewards(managed_buffer!(project_id));
+            },
+        )
+    }
+
+    pub fn get_all_project_ids(&mut self) -> Vec<Vec<u8>> {
+        let mut all_ids = Vec::new();
+
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                let result = sc.get_all_project_ids();
+
+                for id in &result.to_vec() {
+                    all_ids.push(id.to_boxed_bytes().as_slice().to_vec());
+                }
+            })
+            .assert_ok();
+
+        all_ids
+    }
+
+    pub fn get_project_details(
+        &mut self,
+        proj_id: &[u8],
+    ) -> (Vec<u8>, Vec<u8>, u64, u64, Week, Week) {
+        let mut details = (Vec::new(), Vec::new(), 0, 0, 0, 0);
+
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                let (name, website_hash, _, delegation_supply, lkmex_supply, start_week, end_week) =
+                    sc.get_project_details(managed_buffer!(proj_id))
+                        .into_tuple();
+                details = (
+                    name.to_boxed_bytes().as_slice().to_vec(),
+                    website_hash.to_byte_array().to_vec(),
+                    delegation_supply.to_u64().unwrap(),
+                    lkmex_supply.to_u64().unwrap(),
+                    start_week,
+                    end_week,
+                );
+            })
+            .assert_ok();
+
+        details
+    }
+
+    pub fn get_project_by_id(&mut self, proj_id: &[u8]) -> (Vec<u8>, u64, u64, Week, Week) {
+        let mut token = Vec::new();
+        let mut reward_amount = 0;
+        let mut lkmex_rewards_supply = 0;
+        let mut start_week = 0;
+        let mut duration = 0;
+
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                let result = sc.get_project_by_id(managed_buffer!(proj_id));
+                let (first, second, third, fourth, fifth) = result.into_tuple();
+                token = first.to_boxed_bytes().as_slice().to_vec();
+                reward_amount = second.to_u64().unwrap();
+                lkmex_rewards_supply = third.to_u64().unwrap();
+                start_week = fourth;
+                duration = fifth;
+            })
+            .assert_ok();
+
+        (
+            token,
+            reward_amount,
+            lkmex_rewards_supply,
+            start_week,
+            duration,
+        )
+    }
+
+    pub fn call_add_rewards_checkpoint(
+        &mut self,
+        week: Week,
+        total_delegation_supply: u64,
+        total_lkmex_staked: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.add_rewards_checkpoint(
+                    week,
+                    managed_biguint!(total_delegation_supply),
+                    managed_biguint!(total_lkmex_staked),
+                    MultiValueEncoded::new(),
+                );
+            },
+        )
+    }
+
+    pub fn call_add_signed_rewards_checkpoint(
+        &mut self,
+        caller: &Address,
+        week: Week,
+        total_delegation_supply: u64,
+        total_lkmex_staked: u64,
+        oracle_signatures: &[(&Address, &[u8; ED25519_SIGNATURE_BYTE_LEN])],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut encoded_signatures = MultiValueEncoded::new();
+                for (oracle, signature) in oracle_signatures {
+                    encoded_signatures
+                        .push((managed_address!(*oracle), (*signature).into()).into());
+                }
+
+                sc.add_rewards_checkpoint(
+                    week,
+                    managed_biguint!(total_delegation_supply),
+                    managed_biguint!(total_lkmex_staked),
+                    encoded_signatures,
+                );
+            })
+    }
+
+    pub fn call_set_checkpoint_dispute_epochs(&mut self, dispute_epochs: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_checkpoint_dispute_epochs(dispute_epochs);
+            },
+        )
+    }
+
+    pub fn call_finalize_checkpoint(&mut self, week: Week) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.finalize_checkpoint(week);
+            },
+        )
+    }
+
+    pub fn call_set_checkpoint_sources(
+        &mut self,
+        delegation_address: &Address,
+        lkmex_staking_address: &Address,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_checkpoint_sources(
+                    managed_address!(delegation_address),
+                    managed_address!(lkmex_staking_address),
+                );
+            },
+        )
+    }
+
+    pub fn call_request_rewards_checkpoint(&mut self) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.request_rewards_checkpoint();
+            },
+        )
+    }
+
+    pub fn call_auto_checkpoint_callback(
+        &mut self,
+        week: Week,
+        total_lkmex_staked: u64,
+        opt_total_delegation_supply: Option<u64>,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let result = match opt_total_delegation_supply {
+                    Some(total_delegation_supply) => {
+                        ManagedAsyncCallResult::Ok(managed_biguint!(total_delegation_supply))
+                    }
+                    None => ManagedAsyncCallResult::Err(ManagedAsyncCallError {
+                        err_code: 4,
+                        err_msg: managed_buffer!(b"error"),
+                    }),
+                };
+                sc.auto_checkpoint_callback(week, managed_biguint!(total_lkmex_staked), result);
+            },
+        )
+    }
+
+    pub fn call_set_reward_token_whitelist_enabled(&mut self, enabled: bool) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_reward_token_whitelist_enabled(enabled);
+            },
+        )
+    }
+
+    pub fn call_reward_token_properties_callback(
+        &mut self,
+        token_id: &[u8],
+        opt_properties: Option<&[&[u8]]>,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let result = match opt_properties {
+                    Some(properties) => {
+                        let mut encoded_properties = MultiValueEncoded::new();
+                        for property in properties {
+                            encoded_properties.push(managed_buffer!(property));
+                        }
+
+                        ManagedAsyncCallResult::Ok(encoded_properties)
+                    }
+                    None => ManagedAsyncCallResult::Err(ManagedAsyncCallError {
+                        err_code: 4,
+                        err_msg: managed_buffer!(b"error"),
+                    }),
+                };
+                sc.reward_token_properties_callback(managed_token_id!(token_id), result);
+            },
+        )
+    }
+
+    pub fn call_add_stake_providers(&mut self, providers: &[&Address]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut encoded_providers = MultiValueEncoded::new();
+                for provider in providers {
+                    encoded_providers.push(managed_address!(*provider));
+                }
+
+                sc.add_stake_providers(encoded_providers);
+            },
+        )
+    }
+
+    pub fn call_claim_rewards_with_stake_query(
+        &mut self,
+        caller: &Address,
+        provider: &Address,
+        week: Week,
+        user_delegation_supply: u64,
+        user_lkmex_staked: u64,
+        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.claim_rewards_with_stake_query(
+                    managed_address!(provider),
+                    week,
+                    managed_biguint!(user_delegation_supply),
+                    managed_biguint!(user_lkmex_staked),
+                    SIGNATURE_VALID_UNTIL_EPOCH,
+                    signature.into(),
+                );
+            })
+    }
+
+    pub fn call_stake_query_callback(
+        &mut self,
+        user: &Address,
+        week: Week,
+        signed_delegation_amount: u64,
+        opt_active_stake: Option<u64>,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let result = match opt_active_stake {
+                    Some(amount) => ManagedAsyncCallResult::Ok(managed_biguint!(amount)),
+                    None => ManagedAsyncCallResult::Err(ManagedAsyncCallError {
+                        err_code: 4,
+                        err_msg: managed_buffer!(b"error"),
+                    }),
+                };
+                sc.stake_query_callback(
+                    managed_address!(user),
+                    week,
+                    managed_biguint!(signed_delegation_amount),
+                    managed_biguint!(0),
+                    result,
+                );
+            },
+        )
+    }
+
+    pub fn call_add_keepers(&mut self, keepers: &[&Address]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut encoded_keepers = MultiValueEncoded::new();
+                for keeper in keepers {
+                    encoded_keepers.push(managed_address!(*keeper));
+                }
+
+                sc.add_keepers(encoded_keepers);
+            },
+        )
+    }
+
+    pub fn call_set_egld_keeper_bounty(&mut self, amount: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_keeper_bounty(EgldOrEsdtTokenIdentifier::egld(), managed_biguint!(amount));
+            },
+        )
+    }
+
+    pub fn call_fund_egld_keeper_bounty(&mut self, amount: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.fund_keeper_bounty();
+            },
+        )
+    }
+
+    pub fn call_keeper_add_rewards_checkpoint(
+        &mut self,
+        caller: &Address,
+        week: Week,
+        total_delegation_supply: u64,
+        total_lkmex_staked: u64,
+        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.keeper_add_rewards_checkpoint(
+                    week,
+                    managed_biguint!(total_delegation_supply),
+                    managed_biguint!(total_lkmex_staked),
+                    signature.into(),
+                    MultiValueEncoded::new(),
+                );
+            })
+    }
+
+    pub fn call_add_oracles(&mut self, oracles: &[&Address]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut encoded_oracles = MultiValueEncoded::new();
+                for oracle in oracles {
+                    encoded_oracles.push(managed_address!(*oracle));
+                }
+
+                sc.add_oracles(encoded_oracles);
+            },
+        )
+    }
+
+    pub fn call_set_oracle_quorum(&mut self, quorum: usize) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_oracle_quorum(quorum);
+            },
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn call_relayed_claim(
+        &mut self,
+        relayer: &Address,
+        user: &Address,
+        week: Week,
+        user_delegation_supply: u64,
+        user_lkmex_staked: u64,
+        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+        user_signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(relayer, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let _ = sc.relayed_claim(
+                    managed_address!(user),
+                    week,
+                    managed_biguint!(user_delegation_supply),
+                    managed_biguint!(user_lkmex_staked),
+                    SIGNATURE_VALID_UNTIL_EPOCH,
+                    signature.into(),
+                    user_signature.into(),
+                );
+            })
+    }
+
+    pub fn call_add_rewards_checkpoints(&mut self, checkpoints: &[(Week, u64, u64)]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut args = MultiValueEncoded::new();
+                for checkpoint in checkpoints {
+                    let (week, total_delegation_supply, total_lkmex_staked) = *checkpoint;
+                    args.push(
+                        (
+                            week,
+                            managed_biguint!(total_delegation_supply),
+                            managed_biguint!(total_lkmex_staked),
+                        )
+                            .into(),
+                    );
+                }
+
+                sc.add_rewards_checkpoints(args);
+            },
+        )
+    }
+
+    pub fn call_update_rewards_checkpoint(
+        &mut self,
+        week: Week,
+        total_delegation_supply: u64,
+        total_lkmex_staked: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.update_rewards_checkpoint(
+                    week,
+                    managed_biguint!(total_delegation_supply),
+                    managed_biguint!(total_lkmex_staked),
+                    MultiValueEncoded::new(),
+                );
+            },
+        )
+    }
+
+    pub fn call_update_signed_rewards_checkpoint(
+        &mut self,
+        caller: &Address,
+        week: Week,
+        total_delegation_supply: u64,
+        total_lkmex_staked: u64,
+        oracle_signatures: &[(&Address, &[u8; ED25519_SIGNATURE_BYTE_LEN])],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut encoded_signatures = MultiValueEncoded::new();
+                for (oracle, signature) in oracle_signatures {
+                    encoded_signatures
+                        .push((managed_address!(*oracle), (*signature).into()).into());
+                }
+
+                sc.update_rewards_checkpoint(
+                    week,
+                    managed_biguint!(total_delegation_supply),
+                    managed_biguint!(total_lkmex_staked),
+                    encoded_signatures,
+                );
+            })
+    }
+
+    pub fn call_set_week_merkle_root(&mut self, week: Week, root: &[u8; 32]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_week_merkle_root(week, root.into());
+            },
+        )
+    }
+
+    pub fn call_set_week_data_hash(&mut self, week: Week, hash: &[u8; 32]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_week_data_hash(week, hash.into());
+            },
+        )
+    }
+
+    pub fn call_claim_with_proof(
+        &mut self,
+        caller: &Address,
+        week: Week,
+        user_delegation_supply: u64,
+        user_lkmex_staked: u64,
+        proof: &[[u8; 32]],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut encoded_proof = MultiValueEncoded::new();
+                for hash in proof {
+                    encoded_proof.push(hash.into());
+                }
+
+                let _ = sc.claim_with_proof(
+                    week,
+                    managed_biguint!(user_delegation_supply),
+                    managed_biguint!(user_lkmex_staked),
+                    encoded_proof,
+                );
+            })
+    }
+
+    pub fn call_register_signed_energy(
+        &mut self,
+        caller: &Address,
+        week: Week,
+        user_energy: u64,
+        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.register_signed_energy(
+                    week,
+                    managed_biguint!(user_energy),
+                    SIGNATURE_VALID_UNTIL_EPOCH,
+                    signature.into(),
+                );
+            })
+    }
+
+    pub fn call_register_energy(&mut self, caller: &Address) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.register_energy();
+            })
+    }
+
+    pub fn call_set_project_energy_weighted(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        energy_weighted: bool,
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.set_project_energy_weighted(managed_buffer!(project_id), energy_weighted);
+            })
+    }
+
+    pub fn call_set_project_distribution_mode(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        distribution_mode: DistributionMode,
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.set_project_distribution_mode(managed_buffer!(project_id), distribution_mode);
+            })
+    }
+
+    pub fn call_set_project_lkmex_factor(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        lkmex_factor_bps: u64,
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.set_project_lkmex_factor(managed_buffer!(project_id), lkmex_factor_bps);
+            })
+    }
+
+    pub fn call_set_checkpoint_sqrt_sums(
+        &mut self,
+        week: Week,
+        total_delegation_sqrt_sum: u64,
+        total_lkmex_sqrt_sum: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_checkpoint_sqrt_sums(
+                    week,
+                    managed_biguint!(total_delegation_sqrt_sum),
+                    managed_biguint!(total_lkmex_sqrt_sum),
+                    MultiValueEncoded::new(),
+                );
+            },
+        )
+    }
+
+    pub fn call_set_signed_checkpoint_sqrt_sums(
+        &mut self,
+        caller: &Address,
+        week: Week,
+        total_delegation_sqrt_sum: u64,
+        total_lkmex_sqrt_sum: u64,
+        oracle_signatures: &[(&Address, &[u8; ED25519_SIGNATURE_BYTE_LEN])],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut encoded_signatures = MultiValueEncoded::new();
+                for (oracle, signature) in oracle_signatures {
+                    encoded_signatures
+                        .push((managed_address!(*oracle), (*signature).into()).into());
+                }
+
+                sc.set_checkpoint_sqrt_sums(
+                    week,
+                    managed_biguint!(total_delegation_sqrt_sum),
+                    managed_biguint!(total_lkmex_sqrt_sum),
+                    encoded_signatures,
+                );
+            })
+    }
+
+    pub fn call_set_checkpoint_total_energy(&mut self, week: Week, total_energy: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_checkpoint_total_energy(week, managed_biguint!(total_energy));
+            },
+        )
+    }
+
+    pub fn call_deposit_rewards(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        token_id: &[u8],
+        amount: u64,
+    ) -> TxResult {
+        self.b_mock.execute_esdt_transfer(
+            caller,
+            &self.mb_wrapper,
+            token_id,
+            0,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.deposit_rewards(managed_buffer!(project_id));
+            },
+        )
+    }
+
+    pub fn call_deposit_egld_rewards(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        amount: u64,
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(amount), |sc| {
+                sc.deposit_rewards(managed_buffer!(project_id));
+            })
+    }
+
+    pub fn call_add_project_partner_reward(
+        &mut self,
+        project_id: &[u8],
+        token: &[u8],
+        delegation_supply: u64,
+        lkmex_supply: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.add_project_partner_reward(
+                    managed_buffer!(project_id),
+                    managed_token_id!(token),
+                    managed_biguint!(delegation_supply),
+                    managed_biguint!(lkmex_supply),
+                );
+            },
+        )
+    }
+
+    pub fn call_deposit_partner_rewards(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        token: &[u8],
+        amount: u64,
+    ) -> TxResult {
+        self.b_mock.execute_esdt_transfer(
+            caller,
+            &self.mb_wrapper,
+            token,
+            0,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.deposit_partner_rewards(managed_buffer!(project_id));
+            },
+        )
+    }
+
+    pub fn call_set_owner_deposit_allowed(&mut self, project_id: &[u8], allowed: bool) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_owner_deposit_allowed(managed_buffer!(project_id), allowed);
+            },
+        )
+    }
+
+    pub fn call_set_deposit_deadline_epochs(&mut self, deposit_deadline_epochs: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_deposit_deadline_epochs(deposit_deadline_epochs);
+            },
+        )
+    }
+
+    pub fn call_deactivate_unfunded_project(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.deactivate_unfunded_project(managed_buffer!(project_id));
+            })
+    }
+
+    pub fn call_deposit_rewards_multi(
+        &mut self,
+        caller: &Address,
+        project_ids: &[&[u8]],
+        payments: &[(&[u8], u64)],
+    ) -> TxResult {
+        let esdt_transfers: Vec<TxInputESDT> = payments
+            .iter()
+            .map(|(token_id, amount)| TxInputESDT {
+                token_identifier: token_id.to_vec(),
+                nonce: 0,
+                value: rust_biguint!(*amount),
+            })
+            .collect();
+
+        self.b_mock
+            .execute_esdt_multi_transfer(caller, &self.mb_wrapper, &esdt_transfers, |sc| {
+                let mut encoded_ids = MultiValueEncoded::new();
+                for id in project_ids {
+                    encoded_ids.push(managed_buffer!(id));
+                }
+
+                sc.deposit_rewards_multi(encoded_ids);
+            })
+    }
+
+    pub fn call_set_project_claimant_whitelist(
+        &mut self,
+        project_id: &[u8],
+        claimants: &[&Address],
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut encoded_claimants = MultiValueEncoded::new();
+                for claimant in claimants {
+                    encoded_claimants.push(managed_address!(*claimant));
+                }
+
+                sc.add_project_claimants(managed_buffer!(project_id), encoded_claimants);
+                sc.set_project_claimant_whitelist_enabled(managed_buffer!(project_id), true);
+            },
+        )
+    }
+
+    pub fn call_set_project_max_reward_per_user_per_week(
+        &mut self,
+        project_id: &[u8],
+        max_reward: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_project_max_reward_per_user_per_week(
+                    managed_buffer!(project_id),
+                    managed_biguint!(max_reward),
+                );
+            },
+        )
+    }
+
+    pub fn call_refund_project_capped_rewards(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.refund_project_capped_rewards(managed_buffer!(project_id));
+            })
+    }
+
+    pub fn call_set_project_streak_bonus(
+        &mut self,
+        project_id: &[u8],
+        bonus_bps_per_week: u64,
+        max_bonus_bps: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_project_streak_bonus(
+                    managed_buffer!(project_id),
+                    bonus_bps_per_week,
+                    max_bonus_bps,
+                );
+            },
+        )
+    }
+
+    pub fn call_set_project_min_reward_per_user(
+        &mut self,
+        project_id: &[u8],
+        min_reward: u64,
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_project_min_reward_per_user(
+                    managed_buffer!(project_id),
+                    managed_biguint!(min_reward),
+                );
+            },
+        )
+    }
+
+    pub fn call_add_signer(&mut self, signer: &[u8; 32]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.add_signer(managed_address!(&Address::from(signer)));
+            },
+        )
+    }
+
+    pub fn call_remove_signer(&mut self, signer: &[u8; 32]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.remove_signer(managed_address!(&Address::from(signer)));
+            },
+        )
+    }
+
+    pub fn call_add_admins(&mut self, admins: &[&Address]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut args = MultiValueEncoded::new();
+                for admin in admins {
+                    args.push(managed_address!(*admin));
+                }
+
+                sc.add_admins(args);
+            },
+        )
+    }
+
+    pub fn call_remove_admins(&mut self, admins: &[&Address]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut args = MultiValueEncoded::new();
+                for admin in admins {
+                    args.push(managed_address!(*admin));
+                }
+
+                sc.remove_admins(args);
+            },
+        )
+    }
+
+    pub fn call_propose_new_owner(&mut self, caller: &Address, new_owner: &Address) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.propose_new_owner(managed_address!(new_owner));
+            })
+    }
+
+    pub fn call_claim_ownership(&mut self, caller: &Address) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.claim_ownership();
+            })
+    }
+
+    pub fn call_cancel_ownership_transfer(&mut self, caller: &Address) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.cancel_ownership_transfer();
+            })
+    }
+
+    pub fn call_pause_all(&mut self, caller: &Address) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.pause_all();
+            })
+    }
+
+    pub fn call_unpause_all(&mut self, caller: &Address) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.unpause_all();
+            })
+    }
+
+    pub fn call_grant_role(&mut self, address: &Address, role: Role) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.grant_role(managed_address!(address), role);
+            },
+        )
+    }
+
+    pub fn call_revoke_role(&mut self, address: &Address, role: Role) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.revoke_role(managed_address!(address), role);
+            },
+        )
+    }
+
+    pub fn call_set_signer_grace_period_epochs(&mut self, grace_period_epochs: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_signer_grace_period_epochs(grace_period_epochs);
+            },
+        )
+    }
+
+    pub fn call_set_sig_check_disabled(&mut self, disabled: bool) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_sig_check_disabled(disabled);
+            },
+        )
+    }
+
+    pub fn call_set_claim_signature_threshold(&mut self, threshold: usize) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_claim_signature_threshold(threshold);
+            },
+        )
+    }
+
+    pub fn call_claim_rewards_multi_sig(
+        &mut self,
+        caller: &Address,
+        week: Week,
+        user_delegation_supply: u64,
+        user_lkmex_staked: u64,
+        signatures: &[(&[u8; 32], &[u8; ED25519_SIGNATURE_BYTE_LEN])],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut encoded_signatures = MultiValueEncoded::new();
+                for (signer, signature) in signatures {
+                    encoded_signatures.push(
+                        (
+                            managed_address!(&Address::from(*signer)),
+                            (*signature).into(),
+                        )
+                            .into(),
+                    );
+                }
+
+                let _ = sc.claim_rewards_multi_sig(
+                    week,
+                    managed_biguint!(user_delegation_supply),
+                    managed_biguint!(user_lkmex_staked),
+                    SIGNATURE_VALID_UNTIL_EPOCH,
+                    encoded_signatures,
+                );
+            })
+    }
+
+    pub fn call_set_bonus_week(&mut self, week: Week, multiplier_bps: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_bonus_week(week, multiplier_bps);
+            },
+        )
+    }
+
+    pub fn call_deposit_project_bonus_pool(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        token_id: &[u8],
+        amount: u64,
+    ) -> TxResult {
+        self.b_mock.execute_esdt_transfer(
+            caller,
+            &self.mb_wrapper,
+            token_id,
+            0,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.deposit_project_bonus_pool(managed_buffer!(project_id));
+            },
+        )
+    }
+
+    pub fn get_claim_nonce(&mut self, user: &Address) -> u64 {
+        let mut nonce = 0;
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                nonce = sc.claim_nonce(&managed_address!(user)).get();
+            })
+            .assert_ok();
+
+        nonce
+    }
+
+    pub fn get_project_bonus_pool(&mut self, project_id: &[u8]) -> u64 {
+        let mut bonus_pool = 0;
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                bonus_pool = sc
+                    .project_bonus_pool(&managed_buffer!(project_id))
+                    .get()
+                    .to_u64()
+                    .unwrap();
+            })
+            .assert_ok();
+
+        bonus_pool
+    }
+
+    pub fn call_reclaim_project_rounding_remainders(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.reclaim_project_rounding_remainders(managed_buffer!(project_id));
+            })
+    }
+
+    pub fn get_project_apr(
+        &mut self,
+        project_id: &[u8],
+        week: Week,
+        reward_token_price: u64,
+        delegation_token_price: u64,
+        lkmex_token_price: u64,
+    ) -> (u64, u64) {
+        let mut aprs = (0, 0);
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                let (delegation_apr, lkmex_apr) = sc
+                    .get_project_apr(
+                        managed_buffer!(project_id),
+                        week,
+                        managed_biguint!(reward_token_price),
+                        managed_biguint!(delegation_token_price),
+                        managed_biguint!(lkmex_token_price),
+                    )
+                    .into_tuple();
+                aprs = (
+                    delegation_apr.to_u64().unwrap(),
+                    lkmex_apr.to_u64().unwrap(),
+                );
+            })
+            .assert_ok();
+
+        aprs
+    }
+
+    pub fn get_project_rounding_remainders(&mut self, project_id: &[u8]) -> u64 {
+        let mut rounding_remainders = 0;
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                rounding_remainders = sc
+                    .project_rounding_remainders(&managed_buffer!(project_id))
+                    .get()
+                    .to_u64()
+                    .unwrap();
+            })
+            .assert_ok();
+
+        rounding_remainders
+    }
+
+    pub fn get_project_capped_rewards(&mut self, project_id: &[u8]) -> u64 {
+        let mut capped_rewards = 0;
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                capped_rewards = sc
+                    .project_capped_rewards(&managed_buffer!(project_id))
+                    .get()
+                    .to_u64()
+                    .unwrap();
+            })
+            .assert_ok();
+
+        capped_rewards
+    }
+
+    pub fn call_deposit_locked_rewards(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        token_id: &[u8],
+        token_nonce: u64,
+        amount: u64,
+    ) -> TxResult {
+        self.b_mock.execute_esdt_transfer(
+            caller,
+            &self.mb_wrapper,
+            token_id,
+            token_nonce,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.deposit_rewards(managed_buffer!(project_id));
+            },
+        )
+    }
+
+    pub fn call_set_project_reward_token_nonce(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        token_nonce: u64,
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.set_project_reward_token_nonce(managed_buffer!(project_id), token_nonce);
+            })
+    }
+
+    pub fn call_set_project_claim_fee(&mut self, project_id: &[u8], fee_bps: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_project_claim_fee(managed_buffer!(project_id), fee_bps);
+            },
+        )
+    }
+
+    pub fn get_project_collected_fees(&mut self, project_id: &[u8]) -> u64 {
+        let mut fees = 0;
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                fees = sc
+                    .project_collected_fees(&managed_buffer!(project_id))
+                    .get()
+                    .to_u64()
+                    .unwrap();
+            })
+            .assert_ok();
+
+        fees
+    }
+
+    pub fn call_cancel_project(&mut self, project_id: &[u8]) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.cancel_project(managed_buffer!(project_id));
+            },
+        )
+    }
+
+    pub fn call_extend_project(&mut self, project_id: &[u8], new_end_week: Week) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.extend_project(managed_buffer!(project_id), new_end_week);
+            },
+        )
+    }
+
+    pub fn call_deposit_project_extension(
+        &mut self,
+        caller: &Address,
+        project_id: &[u8],
+        token_id: &[u8],
+        amount: u64,
+    ) -> TxResult {
+        self.b_mock.execute_esdt_transfer(
+            caller,
+            &self.mb_wrapper,
+            token_id,
+            0,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.deposit_project_extension(managed_buffer!(project_id));
+            },
+        )
+    }
+
+    pub fn call_claim_rewards(
+        &mut self,
+        caller: &Address,
+        week: Week,
+        user_delegation_supply: u64,
+        user_lkmex_staked: u64,
+        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+    ) -> TxResult {
+        self.call_claim_rewards_with_expiry(
+            caller,
+            week,
+            user_delegation_supply,
+            user_lkmex_staked,
+            SIGNATURE_VALID_UNTIL_EPOCH,
+            signature,
+        )
+    }
+
+    pub fn call_claim_rewards_with_expiry(
+        &mut self,
+        caller: &Address,
+        week: Week,
+        user_delegation_supply: u64,
+        user_lkmex_staked: u64,
+        valid_until_epoch: u64,
+        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut args = MultiValueEncoded::new();
+                args.push(
+                    (
+                        week,
+                        managed_biguint!(user_delegation_supply),
+                        managed_biguint!(user_lkmex_staked),
+                        valid_until_epoch,
+                        signature.into(),
+                    )
+                        .into(),
+                );
+
+                let _ = sc.claim_rewards(managed_address!(caller), args);
+            })
+    }
+
+    pub fn call_set_min_payout_amount(&mut self, token_id: &[u8], amount: u64) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_min_payout_amount(managed_token_id!(token_id), managed_biguint!(amount));
+            },
+        )
+    }
+
+    pub fn call_claim_dust(&mut self, caller: &Address) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let _ = sc.claim_dust();
+            })
+    }
+
+    pub fn call_claim_rewards_for_user(
+        &mut self,
+        caller: &Address,
+        user: &Address,
+        week: Week,
+        user_delegation_supply: u64,
+        user_lkmex_staked: u64,
+        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut args = MultiValueEncoded::new();
+                args.push(
+                    (
+                        week,
+                        managed_biguint!(user_delegation_supply),
+                        managed_biguint!(user_lkmex_staked),
+                        SIGNATURE_VALID_UNTIL_EPOCH,
+                        signature.into(),
+                    )
+                        .into(),
+                );
+
+                let _ = sc.claim_rewards(managed_address!(user), args);
+            })
+    }
+
+    pub fn call_add_sc_address_to_whitelist(&mut self, address: &Address) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.add_sc_address_to_whitelist(managed_address!(address));
+            },
+        )
+    }
+
+    pub fn call_claim_rewards_for_projects(
+        &mut self,
+        caller: &Address,
+        week: Week,
+        user_delegation_supply: u64,
+        user_lkmex_staked: u64,
+        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+        project_ids: &[&[u8]],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut encoded_ids = MultiValueEncoded::new();
+                for id in project_ids {
+                    encoded_ids.push(managed_buffer!(id));
+                }
+
+                let _ = sc.claim_rewards_for_projects(
+                    week,
+                    managed_biguint!(user_delegation_supply),
+                    managed_biguint!(user_lkmex_staked),
+                    SIGNATURE_VALID_UNTIL_EPOCH,
+                    signature.into(),
+                    encoded_ids,
+                );
+            })
+    }
+
+    /// Returns whether more projects remain to be claimed, and their number
+    pub fn call_claim_rewards_batch(
+        &mut self,
+        caller: &Address,
+        week: Week,
+        user_delegation_supply: u64,
+        user_lkmex_staked: u64,
+        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+    ) -> (bool, usize) {
+        let mut result = (false, 0);
+
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let (_, more_remaining, nr_remaining_projects) = sc
+                    .claim_rewards_batch(
+                        week,
+                        managed_biguint!(user_delegation_supply),
+                        managed_biguint!(user_lkmex_staked),
+                        SIGNATURE_VALID_UNTIL_EPOCH,
+                        signature.into(),
+                    )
+                    .into_tuple();
+                result = (more_remaining, nr_remaining_projects);
+            })
+            .assert_ok();
+
+        result
+    }
+
+    pub fn call_set_max_projects_per_claim(&mut self, max_projects_per_claim: usize) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_max_projects_per_claim(max_projects_per_claim);
+            },
+        )
+    }
+
+    pub fn call_claim_rewards_multiple(
+        &mut self,
+        caller: &Address,
+        args: &[(Week, u64, u64, &[u8; ED25519_SIGNATURE_BYTE_LEN])],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut encoded_args = MultiValueEncoded::new();
+                for arg in args {
+                    let (week, user_delegation_supply, user_lkmex_staked, signature) = *arg;
+
+                    encoded_args.push(
+                        (
+                            week,
+                            managed_biguint!(user_delegation_supply),
+                            managed_biguint!(user_lkmex_staked),
+                            SIGNATURE_VALID_UNTIL_EPOCH,
+                            signature.into(),
+                        )
+                            .into(),
+                    );
+                }
+
+                let _ = sc.claim_rewards(managed_address!(caller), encoded_args);
+            })
+    }
+
+    pub fn call_set_reward_destination(
+        &mut self,
+        user: &Address,
+        destination: &Address,
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(user, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.set_reward_destination(managed_address!(destination));
+            })
+    }
+
+    pub fn call_add_compound_farm(
+        &mut self,
+        farm_address: &Address,
+        farming_token: &[u8],
+    ) -> TxResult {
+        self.b_mock.execute_tx(
+            &self.owner_addr,
+            &self.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.add_compound_farm(
+                    managed_address!(farm_address),
+                    managed_token_id!(farming_token),
+                );
+            },
+        )
+    }
+
+    pub fn call_claim_and_compound(
+        &mut self,
+        caller: &Address,
+        farm_address: &Address,
+        week: Week,
+        user_delegation_supply: u64,
+        user_lkmex_staked: u64,
+        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut args = MultiValueEncoded::new();
+                args.push(
+                    (
+                        week,
+                        managed_biguint!(user_delegation_supply),
+                        managed_biguint!(user_lkmex_staked),
+                        SIGNATURE_VALID_UNTIL_EPOCH,
+                        signature.into(),
+                    )
+                        .into(),
+                );
+
+                let _ = sc.claim_and_compound(managed_address!(farm_address), args);
+            })
+    }
+
+    pub fn call_set_claim_delegate(&mut self, user: &Address, delegate: &Address) -> TxResult {
+        self.b_mock
+            .execute_tx(user, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                sc.set_claim_delegate(managed_address!(delegate));
+            })
+    }
+
+    pub fn call_claim_for_user(
+        &mut self,
+        caller: &Address,
+        user: &Address,
+        week: Week,
+        user_delegation_supply: u64,
+        user_lkmex_staked: u64,
+        signature: &[u8; ED25519_SIGNATURE_BYTE_LEN],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut args = MultiValueEncoded::new();
+                args.push(
+                    (
+                        week,
+                        managed_biguint!(user_delegation_supply),
+                        managed_biguint!(user_lkmex_staked),
+                        SIGNATURE_VALID_UNTIL_EPOCH,
+                        signature.into(),
+                    )
+                        .into(),
+                );
+
+                let _ = sc.claim_for_user(managed_address!(user), args);
+            })
+    }
+
+    pub fn call_claim_pending(
+        &mut self,
+        caller: &Address,
+        max_weeks: usize,
+        args: &[(Week, u64, u64, &[u8; ED25519_SIGNATURE_BYTE_LEN])],
+    ) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.mb_wrapper, &rust_biguint!(0), |sc| {
+                let mut encoded_args = MultiValueEncoded::new();
+                for arg in args {
+                    let (week, user_delegation_supply, user_lkmex_staked, signature) = *arg;
+
+                    encoded_args.push(
+                        (
+                            week,
+                            managed_biguint!(user_delegation_supply),
+                            managed_biguint!(user_lkmex_staked),
+                            SIGNATURE_VALID_UNTIL_EPOCH,
+                            signature.into(),
+                        )
+                            .into(),
+                    );
+                }
+
+                let _ = sc.claim_pending(max_weeks, encoded_args);
+            })
+    }
+
+    pub fn get_claim_cursor(&mut self, user_addr: &Address) -> Week {
+        let mut cursor = 0;
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                cursor = sc.claim_cursor(&managed_address!(user_addr)).get();
+            })
+            .assert_ok();
+
+        cursor
+    }
+
+    pub fn get_claim_deadline(&mut self, week: Week) -> (Week, u64) {
+        let mut deadline = (0, 0);
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                deadline = sc.get_claim_deadline(week).into_tuple();
+            })
+            .assert_ok();
+
+        deadline
+    }
+
+    pub fn get_claimed_weeks(&mut self, user_addr: &Address) -> Vec<Week> {
+        let mut weeks = Vec::new();
+
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                let result = sc.get_claimed_weeks(managed_address!(user_addr));
+
+                for week in &result.to_vec() {
+                    weeks.push(week);
+                }
+            })
+            .assert_ok();
+
+        weeks
+    }
+
+    pub fn get_user_claimable_weeks(&mut self, user_addr: &Address) -> Vec<Week> {
+        let mut weeks = Vec::new();
+
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                let result = sc.get_user_claimable_weeks(managed_address!(user_addr));
+
+                for week in &result.to_vec() {
+                    weeks.push(week);
+                }
+            })
+            .assert_ok();
+
+        weeks
+    }
+
+    pub fn get_user_dust(&mut self, user_addr: &Address) -> Vec<(Vec<u8>, u64, u64)> {
+        let mut dust = Vec::new();
+
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                for payment in &sc.get_user_dust(managed_address!(user_addr)) {
+                    dust.push((
+                        payment
+                            .token_identifier
+                            .to_boxed_bytes()
+                            .as_slice()
+                            .to_vec(),
+                        payment.token_nonce,
+                        payment.amount.to_u64().unwrap(),
+                    ));
+                }
+            })
+            .assert_ok();
+
+        dust
+    }
+
+    pub fn get_checkpoint_status(&mut self, week: Week) -> CheckpointStatus {
+        let mut status = CheckpointStatus::Finalized;
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                status = sc.get_checkpoint_status(week);
+            })
+            .assert_ok();
+
+        status
+    }
+
+    pub fn get_rewards_checkpoints(
+        &mut self,
+        from_week: Week,
+        limit: usize,
+    ) -> Vec<(Week, u64, u64)> {
+        let mut checkpoints = Vec::new();
+
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                let result = sc.get_rewards_checkpoints(from_week, limit);
+                for checkpoint in result {
+                    let (week, total_delegation_supply, total_lkmex_staked) =
+                        checkpoint.into_tuple();
+                    checkpoints.push((
+                        week,
+                        total_delegation_supply.to_u64().unwrap(),
+                        total_lkmex_staked.to_u64().unwrap(),
+                    ));
+                }
+            })
+            .assert_ok();
+
+        checkpoints
+    }
+
+    pub fn get_pretty_rewards(
+        &mut self,
+        week: Week,
+        user_delegation_amount: u64,
+        user_lkmex_staked_amount: u64,
+    ) -> Vec<(Vec<u8>, Vec<u8>, u64)> {
         let mut rewards = Vec::new();
 
         self.b_mock
@@ -434,4 +2683,78 @@ where
 
         rewards
     }
+
+    pub fn get_rewards_for_week_multi_user(
+        &mut self,
+        week: Week,
+        users: &[(&Address, u64, u64)],
+    ) -> Vec<(Address, Vec<u8>, Vec<u8>, u64)> {
+        let mut rewards = Vec::new();
+
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                let mut args = MultiValueEncoded::new();
+                for (user, user_delegation_amount, user_lkmex_staked_amount) in users {
+                    args.push(
+                        (
+                            managed_address!(*user),
+                            managed_biguint!(*user_delegation_amount),
+                            managed_biguint!(*user_lkmex_staked_amount),
+                        )
+                            .into(),
+                    );
+                }
+
+                for rew in sc.get_rewards_for_week_multi_user(week, args) {
+                    let (user, proj_id, token, amount) = rew.into_tuple();
+                    rewards.push((
+                        user.to_address(),
+                        proj_id.to_boxed_bytes().as_slice().to_vec(),
+                        token.to_boxed_bytes().as_slice().to_vec(),
+                        amount.to_u64().unwrap(),
+                    ));
+                }
+            })
+            .assert_ok();
+
+        rewards
+    }
+
+    pub fn get_rewards_for_weeks(
+        &mut self,
+        user: &Address,
+        weeks: &[(Week, u64, u64)],
+    ) -> Vec<(Vec<u8>, u64)> {
+        let mut rewards = Vec::new();
+
+        self.b_mock
+            .execute_query(&self.mb_wrapper, |sc| {
+                let mut args = MultiValueEncoded::new();
+                for (week, user_delegation_amount, user_lkmex_staked_amount) in weeks {
+                    args.push(
+                        (
+                            *week,
+                            managed_biguint!(*user_delegation_amount),
+                            managed_biguint!(*user_lkmex_staked_amount),
+                        )
+                            .into(),
+                    );
+                }
+
+                for payment in sc
+                    .get_rewards_for_weeks(managed_address!(user), args)
+                    .iter()
+                {
+                    let raw_token = payment
+                        .token_identifier
+                        .to_boxed_bytes()
+                        .as_slice()
+                        .to_vec();
+                    rewards.push((raw_token, payment.amount.to_u64().unwrap()));
+                }
+            })
+            .assert_ok();
+
+        rewards
+    }
 }
@@ -1,8 +1,32 @@
 pub mod metabonding_setup;
 
-use elrond_wasm_debug::rust_biguint;
-use metabonding::claim::ClaimModule;
-use metabonding_setup::*;
+use elrond_wasm::types::{Address, BigUint, MultiValueEncoded};
+use elrond_wasm_debug::{
+    managed_address, managed_biguint, managed_buffer, managed_token_id, rust_biguint,
+};
+use metabonding::{
+    access_control::AccessControlModule,
+    admin::{AdminModule, Role},
+    audit_log::{AdminActionKind, AuditLogModule},
+    claim::ClaimModule,
+    claimed_weeks::ClaimedWeeksModule,
+    common_storage::CommonStorageModule,
+    energy_factory::EnergyFactoryModule,
+    global_pause::GlobalPauseModule,
+    oracle::OracleModule,
+    ownership::OwnershipModule,
+    project::{DistributionMode, ProjectModule, EGLD_REWARD_TOKEN_ID},
+    reward_token_whitelist::RewardTokenWhitelistModule,
+    rewards::{CheckpointStatus, RewardsModule},
+    signed_message::{
+        CHECKPOINT_SQRT_SUMS_TAG, CHECKPOINT_TAG, KEEPER_CHECKPOINT_TAG, SIGNED_MESSAGE_MAX_LEN,
+    },
+    stake_verification::StakeVerificationModule,
+    status::StatusModule,
+    timelock::{TimelockAction, TimelockModule},
+    validation::ValidationModule,
+};
+use metabonding_setup::{farm_mock::FarmMock, *};
 
 #[test]
 fn init_test() {
@@ -54,442 +78,6136 @@ fn add_projects_test() {
 }
 
 #[test]
-fn deposit_rewards_test() {
+fn get_projects_test() {
     let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
     mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup
+        .call_pause_project_claims(SECOND_PROJ_ID)
+        .assert_ok();
 
-    // try deposit rewards not project owner
-    let rand_user = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
-    mb_setup.b_mock.set_esdt_balance(
-        &rand_user,
-        FIRST_PROJ_TOKEN,
-        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS),
-    );
     mb_setup
-        .call_deposit_rewards(
-            &rand_user,
-            FIRST_PROJ_ID,
-            FIRST_PROJ_TOKEN,
-            TOTAL_FIRST_PROJ_TOKENS,
-        )
-        .assert_user_error("Only project owner may deposit the rewards");
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(sc.get_projects(0, 10).len(), 2);
+            assert_eq!(sc.get_projects(2, 10).len(), 0);
 
-    mb_setup.deposit_rewards_default_projects();
+            let page = sc.get_projects(1, 1).into_iter().collect::<Vec<_>>();
+            assert_eq!(page.len(), 1);
+            let (id, token, start_week, end_week, deposited, paused) = page[0].clone().into_tuple();
+            assert_eq!(id, managed_buffer!(SECOND_PROJ_ID));
+            assert_eq!(token, managed_token_id!(SECOND_PROJ_TOKEN));
+            assert_eq!(start_week, 2);
+            assert_eq!(end_week, 6);
+            assert!(deposited);
+            assert!(paused);
+        })
+        .assert_ok();
 
-    // try deposit rewards again
-    let first_proj_owner = mb_setup.first_project_owner.clone();
-    mb_setup.b_mock.set_esdt_balance(
-        &first_proj_owner,
-        FIRST_PROJ_TOKEN,
-        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS),
-    );
     mb_setup
-        .call_deposit_rewards(
-            &first_proj_owner,
-            FIRST_PROJ_ID,
-            FIRST_PROJ_TOKEN,
-            TOTAL_FIRST_PROJ_TOKENS,
-        )
-        .assert_user_error("Rewards already deposited");
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let _ = sc.get_projects(0, 51);
+        })
+        .assert_user_error("Page size too large");
+
+    // settings changes, pauses and project updates are logged as well
+    let treasury_addr = mb_setup.second_user_addr.clone();
+    mb_setup
+        .call_set_treasury_address(&treasury_addr)
+        .assert_ok();
+    mb_setup.call_pause_all(&owner_addr).assert_ok();
+    mb_setup
+        .call_pause_project_claims(FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(sc.get_audit_log_length(), 6);
+
+            let page: Vec<_> = sc.get_audit_log(4, 3).into_iter().collect();
+            match &page[0].kind {
+                AdminActionKind::TreasuryAddressChanged(address) => {
+                    assert_eq!(address, &managed_address!(&treasury_addr))
+                }
+                _ => panic!("Expected a treasury address entry"),
+            }
+            assert!(matches!(page[1].kind, AdminActionKind::GloballyPaused));
+            match &page[2].kind {
+                AdminActionKind::ProjectClaimsPaused(project_id) => {
+                    assert_eq!(project_id, &managed_buffer!(FIRST_PROJ_ID))
+                }
+                _ => panic!("Expected a project claims paused entry"),
+            }
+        })
+        .assert_ok();
 }
 
 #[test]
-fn add_rewards_checkpoints_test() {
+fn add_project_with_details_test() {
     let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
-    mb_setup.add_default_projects();
-    mb_setup.deposit_rewards_default_projects();
-    mb_setup.add_default_checkpoints();
+    let proj_owner = mb_setup.first_project_owner.clone();
+    let website_hash = [7u8; 32];
 
-    // try add checkpoint for week 1 again
+    // current week = 1
     mb_setup
-        .call_add_rewards_checkpoint(1, 500_000, 0)
-        .assert_user_error("Invalid checkpoint week");
-
-    // try add checkpoint week 4 (next week should be week 3)
+        .call_add_project_with_details(
+            FIRST_PROJ_ID,
+            &proj_owner,
+            b"First Project",
+            &website_hash,
+            FIRST_PROJ_TOKEN,
+            600,
+            400,
+            1,
+            3,
+        )
+        .assert_user_error("Start week must be in the future");
     mb_setup
-        .call_add_rewards_checkpoint(4, 500_000, 0)
-        .assert_user_error("Invalid checkpoint week");
+        .call_add_project_with_details(
+            FIRST_PROJ_ID,
+            &proj_owner,
+            b"",
+            &website_hash,
+            FIRST_PROJ_TOKEN,
+            600,
+            400,
+            2,
+            4,
+        )
+        .assert_user_error("Invalid project name");
+    mb_setup
+        .call_add_project_with_details(
+            FIRST_PROJ_ID,
+            &proj_owner,
+            b"First Project",
+            &website_hash,
+            FIRST_PROJ_TOKEN,
+            600,
+            400,
+            2,
+            4,
+        )
+        .assert_ok();
+
+    assert_eq!(
+        mb_setup.get_project_details(FIRST_PROJ_ID),
+        (
+            b"First Project".to_vec(),
+            website_hash.to_vec(),
+            600,
+            400,
+            2,
+            4
+        )
+    );
+    assert_eq!(
+        mb_setup.get_project_by_id(FIRST_PROJ_ID),
+        (FIRST_PROJ_TOKEN.to_vec(), 600, 400, 2, 4)
+    );
 }
 
 #[test]
-fn claim_rewards_test() {
+fn propose_project_test() {
     let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
-    mb_setup.add_default_projects();
-    mb_setup.deposit_rewards_default_projects();
-    mb_setup.add_default_checkpoints();
-
-    let first_user_addr = mb_setup.first_user_addr.clone();
-    let second_user_addr = mb_setup.second_user_addr.clone();
-    let sig_first_user_week_1 = hex_literal::hex!("d47c0d67b2d25de8b4a3f43d91a2b5ccb522afac47321ae80bf89c90a4445b26adefa693ab685fa20891f736d74eb2dedc11c4b1a8d6e642fa28df270d6ebe08");
-    let sig_second_user_week_1 = hex_literal::hex!("301e68ce4c473d891f033bc53cc4fd62974cb1c2b80c3fc531d4289cdde4b8f09a650686f2233fd83cb1620b73b8649d3bdd94ab4af5cd479139d04b565a920e");
-    let sig_first_user_week_2 = hex_literal::hex!("b4aadf08eea4cc7c636922511943edbab2ff6ef2558528e0e7b03c7448367989fe860ac091be4d942304f04c86b1eaa0501f36e02819a3c628b4c53f3d3ac801");
-    let sig_second_user_week_2 = hex_literal::hex!("19042097100e5dcd0c11c47d5f8b85d11c744625deeea370b5127797c8b30467c0eb04c153c3d644394a06fba3eef4794a9cdd7cbe61c722984da2f6c7a1f90b");
-
-    // get claimable weeks
-    let claimable_weeks = mb_setup.get_user_claimable_weeks(&first_user_addr);
-    assert_eq!(claimable_weeks, &[1usize, 2usize]);
+    let proposer = mb_setup.b_mock.create_user_account(&rust_biguint!(2_000));
+    let owner_addr = mb_setup.owner_addr.clone();
+    mb_setup.call_set_egld_listing_fee(1_000).assert_ok();
 
-    // get rewards week 1
-    let rewards_week_1_first_user = mb_setup.get_pretty_rewards(1, 25_000, 0);
+    mb_setup
+        .call_propose_project(&proposer, 500, FIRST_PROJ_ID, FIRST_PROJ_TOKEN, 1_000, 1, 3)
+        .assert_user_error("Invalid listing fee payment");
+    mb_setup
+        .call_propose_project(
+            &proposer,
+            1_000,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            0,
+            3,
+        )
+        .assert_user_error("Start week must be in the future");
+    mb_setup
+        .call_propose_project(
+            &proposer,
+            1_000,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            1,
+            3,
+        )
+        .assert_ok();
+    mb_setup
+        .call_propose_project(
+            &proposer,
+            1_000,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            1,
+            3,
+        )
+        .assert_user_error("ID already in use");
     assert_eq!(
-        rewards_week_1_first_user,
-        &[(
-            b"FirstProj".to_vec(),
-            b"PROJ-123456".to_vec(),
-            83_333_333u64
-        )]
+        mb_setup.get_pending_project_ids(),
+        vec![FIRST_PROJ_ID.to_vec()]
     );
+    assert!(mb_setup.get_all_project_ids().is_empty());
 
-    let rewards_week_1_second_user = mb_setup.get_pretty_rewards(1, 50_000, 0);
+    // reject - fee is refunded
+    mb_setup.call_reject_project(FIRST_PROJ_ID).assert_ok();
+    mb_setup
+        .b_mock
+        .check_egld_balance(&proposer, &rust_biguint!(2_000));
+    assert!(mb_setup.get_pending_project_ids().is_empty());
+    mb_setup
+        .call_approve_project(FIRST_PROJ_ID)
+        .assert_user_error("Unknown project proposal");
+
+    // propose again and approve - fee is held in escrow until the rewards are deposited
+    mb_setup
+        .call_propose_project(
+            &proposer,
+            1_000,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            1,
+            3,
+        )
+        .assert_ok();
+    mb_setup.call_approve_project(FIRST_PROJ_ID).assert_ok();
+    mb_setup
+        .b_mock
+        .check_egld_balance(&proposer, &rust_biguint!(1_000));
+    mb_setup
+        .b_mock
+        .check_egld_balance(&owner_addr, &rust_biguint!(0));
+
+    assert!(mb_setup.get_pending_project_ids().is_empty());
+    assert_eq!(mb_setup.get_all_project_ids(), vec![FIRST_PROJ_ID.to_vec()]);
     assert_eq!(
-        rewards_week_1_second_user,
-        &[(
-            b"FirstProj".to_vec(),
-            b"PROJ-123456".to_vec(),
-            166_666_666u64
-        )]
+        mb_setup.get_project_by_id(FIRST_PROJ_ID),
+        (FIRST_PROJ_TOKEN.to_vec(), 1_000, 0, 1, 3)
     );
-
-    // try claim while paused
     mb_setup
-        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
-        .assert_user_error("May not claim rewards while paused");
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(
+                sc.project_owner(&managed_buffer!(FIRST_PROJ_ID)).get(),
+                managed_address!(&proposer)
+            );
+        })
+        .assert_ok();
 
-    // unpause
-    mb_setup.call_unpause().assert_ok();
+    mb_setup
+        .b_mock
+        .set_esdt_balance(&proposer, FIRST_PROJ_TOKEN, &rust_biguint!(1_000));
+    mb_setup
+        .call_deposit_rewards(&proposer, FIRST_PROJ_ID, FIRST_PROJ_TOKEN, 1_000)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_egld_balance(&proposer, &rust_biguint!(2_000));
 
-    // try claim wrong user delegation supply
+    // a stale proposal, whose start week has elapsed, can only be rejected
     mb_setup
-        .call_claim_rewards(&first_user_addr, 1, 30_000, 0, &sig_first_user_week_1)
-        .assert_user_error("Invalid signature");
+        .call_propose_project(
+            &proposer,
+            1_000,
+            SECOND_PROJ_ID,
+            SECOND_PROJ_TOKEN,
+            1_000,
+            1,
+            3,
+        )
+        .assert_ok();
+    mb_setup.b_mock.set_block_epoch(12);
+    mb_setup
+        .call_approve_project(SECOND_PROJ_ID)
+        .assert_user_error("Start week must be in the future");
+    mb_setup.call_reject_project(SECOND_PROJ_ID).assert_ok();
+    mb_setup
+        .b_mock
+        .check_egld_balance(&proposer, &rust_biguint!(2_000));
+}
 
-    // try claim wrong week
+#[test]
+fn forfeit_listing_fee_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let proposer = mb_setup.b_mock.create_user_account(&rust_biguint!(1_000));
+    let treasury_addr = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    mb_setup.call_set_egld_listing_fee(1_000).assert_ok();
     mb_setup
-        .call_claim_rewards(&first_user_addr, 5, 25_000, 0, &sig_first_user_week_1)
-        .assert_user_error("No checkpoint for week yet");
+        .call_set_treasury_address(&treasury_addr)
+        .assert_ok();
 
-    // claim first user week 1 ok
     mb_setup
-        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .call_propose_project(
+            &proposer,
+            1_000,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            1,
+            3,
+        )
         .assert_ok();
-    mb_setup.b_mock.check_esdt_balance(
-        &first_user_addr,
-        FIRST_PROJ_TOKEN,
-        &rust_biguint!(83_333_333),
-    );
+    mb_setup.call_approve_project(FIRST_PROJ_ID).assert_ok();
+    mb_setup
+        .call_forfeit_listing_fee(FIRST_PROJ_ID)
+        .assert_user_error("Listing fee cannot be forfeited");
 
-    // try claim week 1 again
+    // week 1 - project started without rewards
+    mb_setup.b_mock.set_block_epoch(12);
+    mb_setup.call_forfeit_listing_fee(FIRST_PROJ_ID).assert_ok();
     mb_setup
-        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
-        .assert_user_error("Already claimed rewards for this week");
+        .b_mock
+        .check_egld_balance(&treasury_addr, &rust_biguint!(1_000));
+    mb_setup
+        .call_forfeit_listing_fee(FIRST_PROJ_ID)
+        .assert_user_error("No listing fee in escrow");
+}
+
+#[test]
+fn project_category_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
 
-    // claim second user week 1 ok
     mb_setup
-        .call_claim_rewards(&second_user_addr, 1, 50_000, 0, &sig_second_user_week_1)
+        .call_set_project_category(FIRST_PROJ_ID, b"defi")
         .assert_ok();
-    mb_setup.b_mock.check_esdt_balance(
-        &second_user_addr,
-        FIRST_PROJ_TOKEN,
-        &rust_biguint!(166_666_666),
+    mb_setup
+        .call_set_project_category(SECOND_PROJ_ID, b"defi")
+        .assert_ok();
+    assert_eq!(
+        mb_setup.get_projects_by_category(b"defi"),
+        vec![FIRST_PROJ_ID.to_vec(), SECOND_PROJ_ID.to_vec()]
     );
 
-    // get rewards week 2
-    let rewards_week_2_first_user = mb_setup.get_pretty_rewards(2, 25_000, 0);
+    // move second project to another category
+    mb_setup
+        .call_set_project_category(SECOND_PROJ_ID, b"gaming")
+        .assert_ok();
     assert_eq!(
-        rewards_week_2_first_user,
-        &[
-            (
-                b"FirstProj".to_vec(),
-                b"PROJ-123456".to_vec(),
-                41_666_666u64
-            ),
-            (
-                b"SecondProj".to_vec(),
-                b"COOL-123456".to_vec(),
-                50_000_000u64
-            )
-        ]
+        mb_setup.get_projects_by_category(b"defi"),
+        vec![FIRST_PROJ_ID.to_vec()]
     );
-    let rewards_week_2_second_user = mb_setup.get_pretty_rewards(2, 50_000, 0);
     assert_eq!(
-        rewards_week_2_second_user,
-        &[
-            (
-                b"FirstProj".to_vec(),
-                b"PROJ-123456".to_vec(),
-                83_333_333u64
-            ),
-            (
-                b"SecondProj".to_vec(),
-                b"COOL-123456".to_vec(),
-                100_000_000u64
-            )
-        ]
+        mb_setup.get_projects_by_category(b"gaming"),
+        vec![SECOND_PROJ_ID.to_vec()]
+    );
+
+    // removed projects are removed from their category
+    mb_setup.call_remove_project(FIRST_PROJ_ID).assert_ok();
+    assert!(mb_setup.get_projects_by_category(b"defi").is_empty());
+}
+
+#[test]
+fn active_projects_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    assert!(mb_setup.get_active_projects(0).is_empty());
+    assert_eq!(
+        mb_setup.get_active_projects(1),
+        vec![FIRST_PROJ_ID.to_vec()]
+    );
+    assert_eq!(
+        mb_setup.get_active_projects(3),
+        vec![FIRST_PROJ_ID.to_vec(), SECOND_PROJ_ID.to_vec()]
     );
+    assert_eq!(
+        mb_setup.get_active_projects(6),
+        vec![SECOND_PROJ_ID.to_vec()]
+    );
+    assert!(mb_setup.get_active_projects(7).is_empty());
+}
+
+#[test]
+fn max_active_projects_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.call_set_max_active_projects(2).assert_ok();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    let second_proj_owner = mb_setup.second_project_owner.clone();
+    mb_setup
+        .call_add_project(
+            FIRST_PROJ_ID,
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+            1,
+            3,
+            0,
+        )
+        .assert_ok();
+    mb_setup
+        .call_add_project(
+            SECOND_PROJ_ID,
+            &second_proj_owner,
+            SECOND_PROJ_TOKEN,
+            TOTAL_SECOND_PROJ_TOKENS,
+            2,
+            5,
+            0,
+        )
+        .assert_ok();
+
+    // weeks 2 and 3 already have two active projects
+    mb_setup
+        .call_add_project(
+            b"ThirdProj",
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            1,
+            2,
+            0,
+        )
+        .assert_user_error("Too many active projects");
+
+    // week 0 and week 4 only have one active project
+    mb_setup
+        .call_add_project(
+            b"ThirdProj",
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            0,
+            1,
+            0,
+        )
+        .assert_ok();
+    mb_setup
+        .call_add_project(
+            b"FourthProj",
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            4,
+            3,
+            0,
+        )
+        .assert_ok();
+}
+
+#[test]
+fn add_named_project_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+
+    let (tx_result, first_id) =
+        mb_setup.call_add_named_project(b"Alpha", &first_proj_owner, FIRST_PROJ_TOKEN, 1_000, 1, 3);
+    tx_result.assert_ok();
+    assert_eq!(first_id, 1u64.to_be_bytes().to_vec());
+    assert_eq!(mb_setup.get_project_id_by_name(b"Alpha"), first_id);
+
+    let (tx_result, _) =
+        mb_setup.call_add_named_project(b"Alpha", &first_proj_owner, FIRST_PROJ_TOKEN, 1_000, 1, 3);
+    tx_result.assert_user_error("Name already in use");
+
+    // generated IDs skip the pending proposals, which can't be taken manually either
+    let proposed_id = 2u64.to_be_bytes();
+    mb_setup
+        .call_propose_project(
+            &first_proj_owner,
+            0,
+            &proposed_id,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            1,
+            3,
+        )
+        .assert_ok();
+    mb_setup
+        .call_add_project(
+            &proposed_id,
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            1,
+            3,
+            0,
+        )
+        .assert_user_error("ID already in use");
+
+    let (tx_result, second_id) =
+        mb_setup.call_add_named_project(b"Beta", &first_proj_owner, FIRST_PROJ_TOKEN, 1_000, 1, 3);
+    tx_result.assert_ok();
+    assert_eq!(second_id, 3u64.to_be_bytes().to_vec());
+    assert_eq!(
+        mb_setup.get_project_by_id(&second_id),
+        (FIRST_PROJ_TOKEN.to_vec(), 1_000, 0, 1, 3)
+    );
+    mb_setup.call_approve_project(&proposed_id).assert_ok();
+
+    // the name is kept in the project's metadata, and renaming the project moves the lookup
+    let website_hash = [1u8; 32];
+    mb_setup
+        .call_update_project_metadata(&first_proj_owner, &second_id, b"Alpha", &website_hash)
+        .assert_user_error("Name already in use");
+    mb_setup
+        .call_update_project_metadata(&first_proj_owner, &second_id, b"Gamma", &website_hash)
+        .assert_ok();
+    assert!(mb_setup.get_project_id_by_name(b"Beta").is_empty());
+    assert_eq!(mb_setup.get_project_id_by_name(b"Gamma"), second_id);
+
+    // the name can be reused after the project is removed
+    mb_setup.call_remove_project(&first_id).assert_ok();
+    assert!(mb_setup.get_project_id_by_name(b"Alpha").is_empty());
+    let (tx_result, third_id) =
+        mb_setup.call_add_named_project(b"Alpha", &first_proj_owner, FIRST_PROJ_TOKEN, 1_000, 1, 3);
+    tx_result.assert_ok();
+    assert_eq!(third_id, 4u64.to_be_bytes().to_vec());
+}
+
+#[test]
+fn transfer_project_ownership_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    let new_owner = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    let first_user_addr = mb_setup.first_user_addr.clone();
+
+    mb_setup
+        .call_transfer_project_ownership(&first_user_addr, FIRST_PROJ_ID, &new_owner)
+        .assert_user_error("Only project manager or project owner may call this function");
+    mb_setup
+        .call_transfer_project_ownership(&first_proj_owner, FIRST_PROJ_ID, &new_owner)
+        .assert_ok();
+
+    // ownership only changes after accepting
+    mb_setup
+        .call_accept_project_ownership(&first_user_addr, FIRST_PROJ_ID)
+        .assert_user_error("Only the pending project owner may accept ownership");
+    mb_setup
+        .call_pause_project_claims_as(&new_owner, FIRST_PROJ_ID)
+        .assert_user_error("Only project manager or project owner may call this function");
+
+    mb_setup
+        .call_accept_project_ownership(&new_owner, FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .call_pause_project_claims_as(&new_owner, FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .call_pause_project_claims_as(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_user_error("Only project manager or project owner may call this function");
+    mb_setup
+        .call_accept_project_ownership(&new_owner, FIRST_PROJ_ID)
+        .assert_user_error("Only the pending project owner may accept ownership");
+}
+
+#[test]
+fn renew_project_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let proj_owner = mb_setup.first_project_owner.clone();
+    let website_hash = [7u8; 32];
+    mb_setup
+        .call_add_project_with_details(
+            FIRST_PROJ_ID,
+            &proj_owner,
+            b"First Project",
+            &website_hash,
+            FIRST_PROJ_TOKEN,
+            600,
+            400,
+            2,
+            4,
+        )
+        .assert_ok();
+
+    let (tx_result, _) = mb_setup.call_renew_project(FIRST_PROJ_ID, 6, 8, 1_000);
+    tx_result.assert_user_error("Project not ended");
+
+    // week 5
+    mb_setup.b_mock.set_block_epoch(40);
+    let (tx_result, _) = mb_setup.call_renew_project(FIRST_PROJ_ID, 5, 8, 1_000);
+    tx_result.assert_user_error("Start week must be in the future");
+
+    let (tx_result, new_project_id) = mb_setup.call_renew_project(FIRST_PROJ_ID, 6, 8, 1_000);
+    tx_result.assert_ok();
+    assert_eq!(new_project_id, 1u64.to_be_bytes().to_vec());
+    assert_eq!(
+        mb_setup.get_project_details(&new_project_id),
+        (
+            b"First Project".to_vec(),
+            website_hash.to_vec(),
+            1_000,
+            0,
+            6,
+            8
+        )
+    );
+    assert_eq!(
+        mb_setup.get_project_by_id(&new_project_id),
+        (FIRST_PROJ_TOKEN.to_vec(), 1_000, 0, 6, 8)
+    );
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(
+                sc.project_owner(&managed_buffer!(&new_project_id)).get(),
+                managed_address!(&proj_owner)
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn remove_project_refund_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(
+                sc.claimed_project_week_rewards(&managed_buffer!(FIRST_PROJ_ID), 1)
+                    .get(),
+                managed_biguint!(83_333_333)
+            );
+        })
+        .assert_ok();
+
+    mb_setup.call_remove_project(FIRST_PROJ_ID).assert_ok();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_proj_owner,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS - 83_333_333),
+    );
+
+    // nothing of the removed project is carried over to a project reusing its ID
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let project_id = managed_buffer!(FIRST_PROJ_ID);
+            for week in 1..=3 {
+                assert!(sc
+                    .claimed_project_week_rewards(&project_id, week)
+                    .is_empty());
+                assert!(sc
+                    .delegation_rounding_remainder(&project_id, week)
+                    .is_empty());
+                assert!(sc.lkmex_rounding_remainder(&project_id, week).is_empty());
+            }
+            assert!(sc.last_swept_week(&project_id).is_empty());
+            assert!(sc.project_cancelled_from_week(&project_id).is_empty());
+            assert!(sc.project_claim_fee_bps(&project_id).is_empty());
+            assert!(sc.project_start_offset_epochs(&project_id).is_empty());
+            assert!(sc.project_end_offset_epochs(&project_id).is_empty());
+        })
+        .assert_ok();
+
+    // the ID can be reused, and the new project's rewards deposited
+    mb_setup
+        .call_add_project(
+            FIRST_PROJ_ID,
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            4,
+            1,
+            0,
+        )
+        .assert_ok();
+    mb_setup
+        .call_deposit_rewards(&first_proj_owner, FIRST_PROJ_ID, FIRST_PROJ_TOKEN, 1_000)
+        .assert_ok();
+}
+
+#[test]
+fn projects_by_reward_token_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let ids = sc
+                .get_projects_by_reward_token(managed_token_id!(SECOND_PROJ_TOKEN))
+                .into_iter()
+                .collect::<Vec<_>>();
+            assert_eq!(ids, vec![managed_buffer!(SECOND_PROJ_ID)]);
+        })
+        .assert_ok();
+
+    mb_setup.call_remove_project(SECOND_PROJ_ID).assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(
+                sc.get_projects_by_reward_token(managed_token_id!(SECOND_PROJ_TOKEN))
+                    .len(),
+                0
+            );
+            assert_eq!(
+                sc.get_projects_by_reward_token(managed_token_id!(FIRST_PROJ_TOKEN))
+                    .len(),
+                1
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn project_owner_management_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    let second_proj_owner = mb_setup.second_project_owner.clone();
+
+    mb_setup
+        .call_pause_project_claims_as(&second_proj_owner, FIRST_PROJ_ID)
+        .assert_user_error("Only project manager or project owner may call this function");
+    mb_setup
+        .call_pause_project_claims_as(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .call_resume_project_claims(FIRST_PROJ_ID)
+        .assert_ok();
+
+    let website_hash = [3u8; 32];
+    mb_setup
+        .call_update_project_metadata(
+            &second_proj_owner,
+            FIRST_PROJ_ID,
+            b"First Project",
+            &website_hash,
+        )
+        .assert_user_error("Only project manager or project owner may call this function");
+    mb_setup
+        .call_update_project_metadata(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            b"First Project",
+            &website_hash,
+        )
+        .assert_ok();
+
+    let (name, hash, _, _, _, _) = mb_setup.get_project_details(FIRST_PROJ_ID);
+    assert_eq!(name, b"First Project");
+    assert_eq!(hash, website_hash);
+
+    // structural changes are still admin-only
+    mb_setup
+        .b_mock
+        .execute_tx(
+            &first_proj_owner,
+            &mb_setup.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.remove_project(managed_buffer!(FIRST_PROJ_ID));
+            },
+        )
+        .assert_user_error("Caller does not have the required role");
+}
+
+#[test]
+fn update_project_reward_supply_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    // first project already started
+    mb_setup
+        .call_update_project_reward_supply(FIRST_PROJ_ID, 500, 500)
+        .assert_user_error("Project already started");
+    mb_setup
+        .call_update_project_reward_supply(SECOND_PROJ_ID, 0, 0)
+        .assert_user_error("Reward supply cannot be 0");
+    mb_setup
+        .call_update_project_reward_supply(SECOND_PROJ_ID, 1_500_000_000, 500_000_000)
+        .assert_ok();
+    assert_eq!(
+        mb_setup.get_project_by_id(SECOND_PROJ_ID),
+        (SECOND_PROJ_TOKEN.to_vec(), 1_500_000_000, 500_000_000, 2, 6)
+    );
+
+    let second_proj_owner = mb_setup.second_project_owner.clone();
+    mb_setup
+        .call_update_project_reward_supply(SECOND_PROJ_ID, 1_000_000_000, 0)
+        .assert_ok();
+
+    // the amount above the new supply is refunded
+    mb_setup
+        .call_deposit_rewards(
+            &second_proj_owner,
+            SECOND_PROJ_ID,
+            SECOND_PROJ_TOKEN,
+            TOTAL_SECOND_PROJ_TOKENS,
+        )
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_proj_owner,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_SECOND_PROJ_TOKENS - 1_000_000_000),
+    );
+
+    mb_setup
+        .call_update_project_reward_supply(SECOND_PROJ_ID, 2_000_000_000, 0)
+        .assert_user_error("Rewards already deposited");
+}
+
+#[test]
+fn extend_project_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    mb_setup
+        .call_extend_project(FIRST_PROJ_ID, 5)
+        .assert_user_error("Rewards not deposited");
+
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup
+        .call_extend_project(FIRST_PROJ_ID, 3)
+        .assert_user_error("Invalid end week");
+    mb_setup.call_extend_project(FIRST_PROJ_ID, 5).assert_ok();
+
+    // weekly rewards are 333_333_333, so 2 extra weeks need 666_666_666 tokens
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup.b_mock.set_esdt_balance(
+        &first_proj_owner,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS),
+    );
+    mb_setup
+        .call_deposit_project_extension(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+        )
+        .assert_user_error("Invalid amount");
+
+    // extension is not active until the deposit
+    assert_eq!(
+        mb_setup.get_project_by_id(FIRST_PROJ_ID),
+        (FIRST_PROJ_TOKEN.to_vec(), TOTAL_FIRST_PROJ_TOKENS, 0, 1, 3)
+    );
+
+    mb_setup
+        .call_deposit_project_extension(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            666_666_666,
+        )
+        .assert_ok();
+    assert_eq!(
+        mb_setup.get_project_by_id(FIRST_PROJ_ID),
+        (FIRST_PROJ_TOKEN.to_vec(), 1_666_666_666, 0, 1, 5)
+    );
+
+    mb_setup.add_default_checkpoints();
+    assert_eq!(
+        mb_setup.get_pretty_rewards(1, 25_000, 0)[0],
+        (
+            FIRST_PROJ_ID.to_vec(),
+            FIRST_PROJ_TOKEN.to_vec(),
+            83_333_333u64
+        )
+    );
+}
+
+#[test]
+fn cancel_project_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+
+    mb_setup
+        .call_cancel_project(SECOND_PROJ_ID)
+        .assert_user_error("Project not started");
+
+    // current week = 3
+    mb_setup.add_default_checkpoints();
+    mb_setup.set_current_epoch(26);
+    mb_setup
+        .call_cancel_project(FIRST_PROJ_ID)
+        .assert_user_error("No weeks left to cancel");
+
+    // weeks 2 and 3 are kept, 400_000_000 per week, the other 3 weeks are refunded
+    mb_setup.call_cancel_project(SECOND_PROJ_ID).assert_ok();
+    assert_eq!(
+        mb_setup.get_project_by_id(SECOND_PROJ_ID),
+        (SECOND_PROJ_TOKEN.to_vec(), 800_000_000, 0, 2, 3)
+    );
+
+    let second_proj_owner = mb_setup.second_project_owner.clone();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_proj_owner,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(1_200_000_000),
+    );
+
+    // rewards for elapsed weeks are unchanged
+    assert_eq!(
+        mb_setup.get_pretty_rewards(2, 25_000, 0)[1],
+        (
+            SECOND_PROJ_ID.to_vec(),
+            SECOND_PROJ_TOKEN.to_vec(),
+            50_000_000u64
+        )
+    );
+}
+
+#[test]
+fn cancel_partially_funded_project_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    // current week = 3
+    mb_setup.add_default_checkpoints();
+    mb_setup.set_current_epoch(26);
+    let second_proj_owner = mb_setup.second_project_owner.clone();
+    mb_setup
+        .call_deposit_rewards(
+            &second_proj_owner,
+            SECOND_PROJ_ID,
+            SECOND_PROJ_TOKEN,
+            500_000_000,
+        )
+        .assert_ok();
+
+    // nothing is refunded, as the deposits don't cover the 2 remaining weeks
+    mb_setup.call_cancel_project(SECOND_PROJ_ID).assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_proj_owner,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_SECOND_PROJ_TOKENS - 500_000_000),
+    );
+
+    // only the missing amount is taken
+    mb_setup
+        .call_deposit_rewards(
+            &second_proj_owner,
+            SECOND_PROJ_ID,
+            SECOND_PROJ_TOKEN,
+            500_000_000,
+        )
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_proj_owner,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_SECOND_PROJ_TOKENS - 800_000_000),
+    );
+    assert_eq!(
+        mb_setup.get_pretty_rewards(2, 25_000, 0)[0],
+        (
+            SECOND_PROJ_ID.to_vec(),
+            SECOND_PROJ_TOKEN.to_vec(),
+            50_000_000u64
+        )
+    );
+
+    // 333 per week, the rounding dust of the supply is never deposited, so it's not refunded either
+    let dust_proj_id = b"DustProj";
+    let dust_token = b"DUST-123456";
+    mb_setup
+        .b_mock
+        .set_esdt_balance(&second_proj_owner, dust_token, &rust_biguint!(1_000));
+    mb_setup
+        .call_add_project(dust_proj_id, &second_proj_owner, dust_token, 1_000, 4, 3, 0)
+        .assert_ok();
+    mb_setup
+        .call_deposit_rewards(&second_proj_owner, dust_proj_id, dust_token, 500)
+        .assert_ok();
+
+    // current week = 4, the deposits already cover the remaining week, so the project is funded
+    mb_setup.set_current_epoch(33);
+    mb_setup.call_cancel_project(dust_proj_id).assert_ok();
+    assert_eq!(
+        mb_setup.get_project_by_id(dust_proj_id),
+        (dust_token.to_vec(), 333, 0, 4, 4)
+    );
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&second_proj_owner, dust_token, &rust_biguint!(667));
+    mb_setup.b_mock.check_esdt_balance(
+        mb_setup.mb_wrapper.address_ref(),
+        dust_token,
+        &rust_biguint!(333),
+    );
+    mb_setup
+        .call_deposit_rewards(&second_proj_owner, dust_proj_id, dust_token, 1)
+        .assert_user_error("Rewards already deposited");
+}
+
+#[test]
+fn project_claim_fee_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    mb_setup
+        .call_set_project_claim_fee(FIRST_PROJ_ID, 100)
+        .assert_user_error("Treasury address not set");
+
+    let treasury_addr = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    mb_setup
+        .call_set_treasury_address(&treasury_addr)
+        .assert_ok();
+    mb_setup
+        .call_set_project_claim_fee(FIRST_PROJ_ID, 10_001)
+        .assert_user_error("Invalid fee");
+
+    // 1% fee
+    mb_setup
+        .call_set_project_claim_fee(FIRST_PROJ_ID, 100)
+        .assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333 - 833_333),
+    );
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&treasury_addr, FIRST_PROJ_TOKEN, &rust_biguint!(833_333));
+    assert_eq!(mb_setup.get_project_collected_fees(FIRST_PROJ_ID), 833_333);
+}
+
+#[test]
+fn project_claimant_whitelist_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    mb_setup
+        .call_set_project_claimant_whitelist(FIRST_PROJ_ID, &[&second_user_addr])
+        .assert_ok();
+
+    // the query caller is not whitelisted
+    assert!(mb_setup.get_pretty_rewards(1, 25_000, 0).is_empty());
+
+    // only the whitelisted user has rewards
+    assert_eq!(
+        mb_setup.get_rewards_for_week_multi_user(
+            1,
+            &[
+                (&first_user_addr, 25_000, 0),
+                (&second_user_addr, 50_000, 0)
+            ]
+        ),
+        &[(
+            second_user_addr.clone(),
+            FIRST_PROJ_ID.to_vec(),
+            FIRST_PROJ_TOKEN.to_vec(),
+            166_666_666u64
+        )]
+    );
+
+    // first user is not whitelisted, so the week is claimed without rewards
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+
+    let sig_second_user_week_1 = hex_literal::hex!("6056836289c61837308d014ab372a90f7665238bcb85dd1d6c9cad1feff7be9516d5348b7e27f8c74036a4f75d576bccca630611545179399202e268f1388103");
+    mb_setup
+        .call_claim_rewards(&second_user_addr, 1, 50_000, 0, &sig_second_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(166_666_666),
+    );
+
+    // the whitelist does not carry over to a project reusing the ID
+    mb_setup.call_remove_project(FIRST_PROJ_ID).assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let project_id = managed_buffer!(FIRST_PROJ_ID);
+            assert!(!sc.project_claimant_whitelist_enabled(&project_id).get());
+            assert!(sc.project_claimant_whitelist(&project_id).is_empty());
+        })
+        .assert_ok();
+}
+
+#[test]
+fn max_reward_per_user_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup
+        .call_set_project_max_reward_per_user_per_week(FIRST_PROJ_ID, 50_000_000)
+        .assert_ok();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    mb_setup
+        .call_set_project_max_reward_per_user_per_week(FIRST_PROJ_ID, 0)
+        .assert_user_error("Project already started");
+    assert_eq!(
+        mb_setup.get_pretty_rewards(1, 25_000, 0),
+        &[(
+            FIRST_PROJ_ID.to_vec(),
+            FIRST_PROJ_TOKEN.to_vec(),
+            50_000_000u64
+        )]
+    );
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_second_user_week_1 = hex_literal::hex!("6056836289c61837308d014ab372a90f7665238bcb85dd1d6c9cad1feff7be9516d5348b7e27f8c74036a4f75d576bccca630611545179399202e268f1388103");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup
+        .call_claim_rewards(&second_user_addr, 1, 50_000, 0, &sig_second_user_week_1)
+        .assert_ok();
+
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &second_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+    assert_eq!(
+        mb_setup.get_project_capped_rewards(FIRST_PROJ_ID),
+        (83_333_333 - 50_000_000) + (166_666_666 - 50_000_000)
+    );
+
+    // only the owners may refund
+    mb_setup
+        .call_refund_project_capped_rewards(&first_user_addr, FIRST_PROJ_ID)
+        .assert_user_error("Only project manager or project owner may call this function");
+
+    // week 1 can still be claimed
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup
+        .call_refund_project_capped_rewards(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_user_error("No expired weeks to refund");
+
+    // week 1 expired
+    mb_setup.set_current_epoch(47);
+    mb_setup
+        .call_refund_project_capped_rewards(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_proj_owner,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(150_000_000),
+    );
+    assert_eq!(mb_setup.get_project_capped_rewards(FIRST_PROJ_ID), 0);
+    mb_setup
+        .call_refund_project_capped_rewards(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_user_error("No expired weeks to refund");
+
+    // week 2 expired, without capped rewards
+    mb_setup.set_current_epoch(54);
+    mb_setup
+        .call_refund_project_capped_rewards(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_user_error("No capped rewards");
+}
+
+#[test]
+fn deposit_rewards_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    // try deposit rewards not project owner
+    let rand_user = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    mb_setup.b_mock.set_esdt_balance(
+        &rand_user,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS),
+    );
+    mb_setup
+        .call_deposit_rewards(
+            &rand_user,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+        )
+        .assert_user_error("Only project owner may deposit the rewards");
+
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert!(sc.funded_projects().is_empty());
+        })
+        .assert_ok();
+
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(sc.funded_projects().len(), 2);
+            assert!(sc
+                .funded_projects()
+                .contains(&managed_buffer!(FIRST_PROJ_ID)));
+        })
+        .assert_ok();
+
+    // try deposit rewards again
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup.b_mock.set_esdt_balance(
+        &first_proj_owner,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS),
+    );
+    mb_setup
+        .call_deposit_rewards(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+        )
+        .assert_user_error("Rewards already deposited");
+}
+
+#[test]
+fn owner_deposit_override_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    let owner_addr = mb_setup.owner_addr.clone();
+    mb_setup.b_mock.set_esdt_balance(
+        &owner_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS),
+    );
+    mb_setup
+        .call_deposit_rewards(
+            &owner_addr,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+        )
+        .assert_user_error("Only project owner may deposit the rewards");
+
+    mb_setup
+        .call_set_owner_deposit_allowed(FIRST_PROJ_ID, true)
+        .assert_ok();
+    mb_setup
+        .call_deposit_rewards(
+            &owner_addr,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+        )
+        .assert_ok();
+}
+
+#[test]
+fn locked_token_rewards_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let locked_token = b"LKMEX-123456";
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup.b_mock.set_nft_balance(
+        &first_proj_owner,
+        locked_token,
+        5,
+        &rust_biguint!(1_200),
+        &(),
+    );
+    mb_setup.b_mock.set_nft_balance(
+        &first_proj_owner,
+        locked_token,
+        6,
+        &rust_biguint!(1_200),
+        &(),
+    );
+    mb_setup
+        .call_add_project(
+            FIRST_PROJ_ID,
+            &first_proj_owner,
+            locked_token,
+            1_200,
+            1,
+            3,
+            0,
+        )
+        .assert_ok();
+
+    // all deposits must have the same nonce
+    mb_setup
+        .call_deposit_locked_rewards(&first_proj_owner, FIRST_PROJ_ID, locked_token, 5, 600)
+        .assert_ok();
+    mb_setup
+        .call_deposit_locked_rewards(&first_proj_owner, FIRST_PROJ_ID, locked_token, 6, 600)
+        .assert_user_error("Invalid payment nonce");
+    mb_setup
+        .call_deposit_locked_rewards(&first_proj_owner, FIRST_PROJ_ID, locked_token, 5, 600)
+        .assert_ok();
+
+    // the deposited rewards are only owed in the deposited nonce
+    assert_eq!(mb_setup.get_token_liabilities(locked_token, 5), 1_200);
+    assert_eq!(mb_setup.get_token_liabilities(locked_token, 6), 0);
+    let sc_addr = mb_setup.mb_wrapper.address_ref().clone();
+    mb_setup
+        .b_mock
+        .set_nft_balance(&sc_addr, locked_token, 5, &rust_biguint!(1_300), &());
+    mb_setup
+        .call_emergency_withdraw(locked_token, 5, 101)
+        .assert_user_error("Amount exceeds the withdrawable balance");
+    mb_setup
+        .call_emergency_withdraw(locked_token, 5, 100)
+        .assert_ok();
+    let owner_addr = mb_setup.owner_addr.clone();
+    mb_setup.b_mock.check_nft_balance(
+        &owner_addr,
+        locked_token,
+        5,
+        &rust_biguint!(100),
+        Option::<&()>::None,
+    );
+
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_nft_balance(
+        &first_user_addr,
+        locked_token,
+        5,
+        &rust_biguint!(100),
+        Option::<&()>::None,
+    );
+}
+
+#[test]
+fn egld_rewards_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup.b_mock.set_egld_balance(
+        &first_proj_owner,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS + 1),
+    );
+    mb_setup
+        .call_add_project(
+            FIRST_PROJ_ID,
+            &first_proj_owner,
+            EGLD_REWARD_TOKEN_ID,
+            TOTAL_FIRST_PROJ_TOKENS,
+            1,
+            3,
+            0,
+        )
+        .assert_ok();
+
+    mb_setup
+        .call_deposit_rewards(&first_proj_owner, FIRST_PROJ_ID, FIRST_PROJ_TOKEN, 1)
+        .assert_user_error("Invalid payment token");
+
+    // the amount above the distributable supply is refunded in EGLD
+    mb_setup
+        .call_deposit_egld_rewards(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            TOTAL_FIRST_PROJ_TOKENS + 1,
+        )
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_egld_balance(&first_proj_owner, &rust_biguint!(2));
+    assert_eq!(
+        mb_setup.get_token_liabilities_egld(),
+        TOTAL_FIRST_PROJ_TOKENS - 1
+    );
+
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_egld_balance(&first_user_addr, &rust_biguint!(83_333_333));
+}
+
+#[test]
+fn reward_token_whitelist_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup
+        .call_set_reward_token_whitelist_enabled(true)
+        .assert_ok();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup
+        .call_add_project(
+            FIRST_PROJ_ID,
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+            1,
+            3,
+            0,
+        )
+        .assert_user_error("Reward token not whitelisted");
+
+    // unknown, NFT or paused tokens are not whitelisted
+    mb_setup
+        .call_reward_token_properties_callback(FIRST_PROJ_TOKEN, None)
+        .assert_ok();
+    mb_setup
+        .call_reward_token_properties_callback(
+            FIRST_PROJ_TOKEN,
+            Some(&[b"Proj", b"NonFungibleESDT", b"IsPaused-false"]),
+        )
+        .assert_ok();
+    mb_setup
+        .call_reward_token_properties_callback(
+            FIRST_PROJ_TOKEN,
+            Some(&[b"Proj", b"FungibleESDT", b"IsPaused-true"]),
+        )
+        .assert_ok();
+    mb_setup
+        .call_add_project(
+            FIRST_PROJ_ID,
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+            1,
+            3,
+            0,
+        )
+        .assert_user_error("Reward token not whitelisted");
+
+    mb_setup
+        .call_reward_token_properties_callback(
+            FIRST_PROJ_TOKEN,
+            Some(&[b"Proj", b"FungibleESDT", b"IsPaused-false"]),
+        )
+        .assert_ok();
+    mb_setup
+        .call_add_project(
+            FIRST_PROJ_ID,
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+            1,
+            3,
+            0,
+        )
+        .assert_ok();
+
+    // meta ESDTs are whitelisted as well
+    mb_setup
+        .call_reward_token_properties_callback(
+            SECOND_PROJ_TOKEN,
+            Some(&[b"Proj", b"MetaESDT", b"IsPaused-false"]),
+        )
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert!(sc
+                .reward_token_whitelist()
+                .contains(&managed_token_id!(SECOND_PROJ_TOKEN)));
+        })
+        .assert_ok();
+}
+
+#[test]
+fn project_rewards_lock_epochs_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    let second_proj_owner = mb_setup.second_project_owner.clone();
+    mb_setup
+        .call_set_project_rewards_lock_epochs(&first_proj_owner, FIRST_PROJ_ID, 1_440)
+        .assert_user_error("Energy factory address not set");
+    mb_setup
+        .call_set_energy_factory_address(&first_proj_owner)
+        .assert_user_error("Invalid energy factory address");
+
+    let energy_factory_addr = mb_setup.mb_wrapper.address_ref().clone();
+    mb_setup
+        .call_set_energy_factory_address(&energy_factory_addr)
+        .assert_ok();
+    mb_setup
+        .call_set_project_rewards_lock_epochs(&second_proj_owner, FIRST_PROJ_ID, 1_440)
+        .assert_user_error("Only project manager or project owner may call this function");
+    mb_setup
+        .call_set_project_rewards_lock_epochs(&first_proj_owner, FIRST_PROJ_ID, 0)
+        .assert_ok();
+
+    // the lock options and base token are read from the energy factory, so the lock period is set directly
+    let owner_addr = mb_setup.owner_addr.clone();
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.project_rewards_lock_epochs(&managed_buffer!(FIRST_PROJ_ID))
+                .set(1_440u64);
+        })
+        .assert_ok();
+    assert_eq!(
+        mb_setup.get_project_rewards_lock_epochs(FIRST_PROJ_ID),
+        1_440
+    );
+
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup
+        .call_set_project_rewards_lock_epochs(&first_proj_owner, FIRST_PROJ_ID, 0)
+        .assert_user_error("Project already started");
+
+    // the locked rewards are kept for the user, instead of calling the energy factory during the claim
+    mb_setup.call_unpause().assert_ok();
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let project_id = managed_buffer!(FIRST_PROJ_ID);
+            assert_eq!(
+                sc.pending_locked_rewards(&managed_address!(&first_user_addr), &project_id)
+                    .get(),
+                managed_biguint!(83_333_333)
+            );
+            assert_eq!(
+                sc.total_pending_locked_rewards(&project_id).get(),
+                managed_biguint!(83_333_333)
+            );
+        })
+        .assert_ok();
+
+    mb_setup
+        .call_remove_project(FIRST_PROJ_ID)
+        .assert_user_error("Project has pending locked rewards");
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    mb_setup
+        .call_claim_locked_rewards(&second_user_addr, FIRST_PROJ_ID)
+        .assert_user_error("No locked rewards to claim");
+}
+
+#[test]
+fn partner_rewards_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    let partner_token = b"PART-123456";
+    let second_proj_owner = mb_setup.second_project_owner.clone();
+    mb_setup.b_mock.set_esdt_balance(
+        &second_proj_owner,
+        partner_token,
+        &rust_biguint!(200_000_001),
+    );
+
+    mb_setup
+        .call_add_project_partner_reward(SECOND_PROJ_ID, SECOND_PROJ_TOKEN, 150_000_000, 50_000_000)
+        .assert_user_error("Invalid partner reward token");
+    mb_setup
+        .call_add_project_partner_reward(SECOND_PROJ_ID, partner_token, 0, 0)
+        .assert_user_error("Invalid supply");
+    mb_setup
+        .call_add_project_partner_reward(SECOND_PROJ_ID, partner_token, 150_000_000, 50_000_000)
+        .assert_ok();
+    mb_setup
+        .call_add_project_partner_reward(SECOND_PROJ_ID, partner_token, 150_000_000, 50_000_000)
+        .assert_user_error("Partner reward already added");
+
+    // the project is listed under the partner token as well
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let ids = sc
+                .get_projects_by_reward_token(managed_token_id!(partner_token))
+                .to_vec();
+            assert_eq!(ids.len(), 1);
+            assert_eq!(*ids.get(0), managed_buffer!(SECOND_PROJ_ID));
+        })
+        .assert_ok();
+
+    // the amount above the partner token's supply is refunded
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup
+        .call_deposit_partner_rewards(
+            &second_proj_owner,
+            SECOND_PROJ_ID,
+            partner_token,
+            200_000_001,
+        )
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&second_proj_owner, partner_token, &rust_biguint!(1));
+    assert_eq!(
+        mb_setup.get_token_liabilities(partner_token, 0),
+        200_000_000
+    );
+
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+    mb_setup
+        .call_add_project_partner_reward(SECOND_PROJ_ID, b"OTHER-123456", 100, 0)
+        .assert_user_error("Project already started");
+    mb_setup
+        .call_deposit_partner_rewards(&second_proj_owner, SECOND_PROJ_ID, partner_token, 1)
+        .assert_user_error("Project already started");
+    mb_setup
+        .call_cancel_project(SECOND_PROJ_ID)
+        .assert_user_error("Project has partner rewards");
+    mb_setup
+        .call_extend_project(SECOND_PROJ_ID, 8)
+        .assert_user_error("Project has partner rewards");
+
+    // the partner token's weekly rewards are computed from its own supplies,
+    // i.e. 30_000_000 delegation rewards per week, of which the user gets 25_000 / 200_000
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_2 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, partner_token, &rust_biguint!(3_750_000));
+    assert_eq!(
+        mb_setup.get_token_liabilities(partner_token, 0),
+        196_250_000
+    );
+
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let remaining = sc
+                .get_remaining_rewards(managed_buffer!(SECOND_PROJ_ID))
+                .to_vec();
+            assert_eq!(remaining.len(), 2);
+            assert_eq!(
+                remaining.get(1).token_identifier,
+                managed_token_id!(partner_token)
+            );
+            assert_eq!(remaining.get(1).amount, managed_biguint!(196_250_000));
+        })
+        .assert_ok();
+
+    // the partner token is no longer indexed once the project is removed
+    mb_setup.call_remove_project(SECOND_PROJ_ID).assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_proj_owner,
+        partner_token,
+        &rust_biguint!(196_250_001),
+    );
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert!(sc
+                .get_projects_by_reward_token(managed_token_id!(partner_token))
+                .is_empty());
+        })
+        .assert_ok();
+}
+
+#[test]
+fn fixed_reward_token_nonce_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let locked_token = b"LKMEX-123456";
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    let second_proj_owner = mb_setup.second_project_owner.clone();
+    mb_setup.b_mock.set_nft_balance(
+        &first_proj_owner,
+        locked_token,
+        5,
+        &rust_biguint!(1_200),
+        &(),
+    );
+    mb_setup.b_mock.set_nft_balance(
+        &first_proj_owner,
+        locked_token,
+        6,
+        &rust_biguint!(1_200),
+        &(),
+    );
+    mb_setup
+        .call_add_project(
+            FIRST_PROJ_ID,
+            &first_proj_owner,
+            locked_token,
+            1_200,
+            1,
+            3,
+            0,
+        )
+        .assert_ok();
+
+    mb_setup
+        .call_set_project_reward_token_nonce(&second_proj_owner, FIRST_PROJ_ID, 6)
+        .assert_user_error("Only project manager or project owner may call this function");
+    mb_setup
+        .call_set_project_reward_token_nonce(&first_proj_owner, FIRST_PROJ_ID, 6)
+        .assert_ok();
+
+    // the first deposit must also match the set nonce
+    mb_setup
+        .call_deposit_locked_rewards(&first_proj_owner, FIRST_PROJ_ID, locked_token, 5, 600)
+        .assert_user_error("Invalid payment nonce");
+    mb_setup
+        .call_deposit_locked_rewards(&first_proj_owner, FIRST_PROJ_ID, locked_token, 6, 600)
+        .assert_ok();
+
+    mb_setup
+        .call_set_project_reward_token_nonce(&first_proj_owner, FIRST_PROJ_ID, 5)
+        .assert_user_error("Rewards already deposited");
+}
+
+#[test]
+fn partial_deposit_rewards_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.add_default_checkpoints();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup
+        .call_deposit_rewards(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS / 2,
+        )
+        .assert_ok();
+
+    // no rewards until the full supply is deposited
+    assert!(mb_setup.get_pretty_rewards(1, 25_000, 0).is_empty());
+
+    // overshoot is refunded
+    mb_setup.b_mock.set_esdt_balance(
+        &first_proj_owner,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS),
+    );
+    mb_setup
+        .call_deposit_rewards(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+        )
+        .assert_ok();
+    // the distributable supply is 333_333_333 * 3
+    mb_setup.b_mock.check_esdt_balance(
+        &first_proj_owner,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS / 2 + 1),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        mb_setup.mb_wrapper.address_ref(),
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS - 1),
+    );
+
+    assert_eq!(
+        mb_setup.get_pretty_rewards(1, 25_000, 0),
+        &[(
+            FIRST_PROJ_ID.to_vec(),
+            FIRST_PROJ_TOKEN.to_vec(),
+            83_333_333u64
+        )]
+    );
+    mb_setup
+        .call_deposit_rewards(&first_proj_owner, FIRST_PROJ_ID, FIRST_PROJ_TOKEN, 1)
+        .assert_user_error("Rewards already deposited");
+}
+
+#[test]
+fn deposit_rewards_multi_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup
+        .call_add_project(b"ProjA", &first_proj_owner, FIRST_PROJ_TOKEN, 900, 1, 3, 0)
+        .assert_ok();
+    mb_setup
+        .call_add_project(
+            b"ProjB",
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            1_800,
+            1,
+            3,
+            0,
+        )
+        .assert_ok();
+
+    mb_setup
+        .call_deposit_rewards_multi(
+            &first_proj_owner,
+            &[b"ProjA", b"ProjB"],
+            &[(FIRST_PROJ_TOKEN, 1_800)],
+        )
+        .assert_user_error("Invalid number of payments");
+    mb_setup
+        .call_deposit_rewards_multi(
+            &first_proj_owner,
+            &[b"ProjA", b"ProjB"],
+            &[(FIRST_PROJ_TOKEN, 1_800), (FIRST_PROJ_TOKEN, 1_800)],
+        )
+        .assert_user_error("No matching payment for project");
+
+    // payments are matched by amount, regardless of order
+    mb_setup
+        .call_deposit_rewards_multi(
+            &first_proj_owner,
+            &[b"ProjA", b"ProjB"],
+            &[(FIRST_PROJ_TOKEN, 1_800), (FIRST_PROJ_TOKEN, 900)],
+        )
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_proj_owner,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS - 2_700),
+    );
+    mb_setup
+        .call_deposit_rewards(&first_proj_owner, b"ProjA", FIRST_PROJ_TOKEN, 900)
+        .assert_user_error("Rewards already deposited");
+}
+
+#[test]
+fn deposit_deadline_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    // deadlines: first project - epoch 9, second project - epoch 16
+    mb_setup.call_set_deposit_deadline_epochs(3).assert_ok();
+
+    let rand_user = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    let second_proj_owner = mb_setup.second_project_owner.clone();
+    mb_setup
+        .call_deactivate_unfunded_project(&rand_user, FIRST_PROJ_ID)
+        .assert_user_error("Deposit deadline not passed");
+    mb_setup
+        .call_deposit_rewards(
+            &second_proj_owner,
+            SECOND_PROJ_ID,
+            SECOND_PROJ_TOKEN,
+            TOTAL_SECOND_PROJ_TOKENS / 2,
+        )
+        .assert_ok();
+
+    mb_setup.b_mock.set_block_epoch(10);
+    mb_setup
+        .call_deposit_rewards(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+        )
+        .assert_user_error("Deposit deadline passed");
+    mb_setup
+        .call_deactivate_unfunded_project(&rand_user, FIRST_PROJ_ID)
+        .assert_ok();
+    assert_eq!(
+        mb_setup.get_all_project_ids(),
+        vec![SECOND_PROJ_ID.to_vec()]
+    );
+
+    // partial deposit is refunded
+    mb_setup.b_mock.set_block_epoch(17);
+    mb_setup
+        .call_deactivate_unfunded_project(&rand_user, SECOND_PROJ_ID)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_proj_owner,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_SECOND_PROJ_TOKENS),
+    );
+    assert!(mb_setup.get_all_project_ids().is_empty());
+}
+
+#[test]
+fn rescue_token_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+
+    let rand_token = b"OOPS-123456";
+    let sc_addr = mb_setup.mb_wrapper.address_ref().clone();
+    mb_setup
+        .b_mock
+        .set_esdt_balance(&sc_addr, rand_token, &rust_biguint!(500));
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    mb_setup
+        .call_rescue_token(FIRST_PROJ_TOKEN, 1_000, &first_user_addr)
+        .assert_user_error("Token is used by the SC");
+    mb_setup
+        .call_rescue_token(rand_token, 1_000, &first_user_addr)
+        .assert_user_error("Insufficient balance");
+    mb_setup
+        .call_rescue_token(rand_token, 300, &first_user_addr)
+        .assert_ok();
+
+    // tokens already recorded for a depositor can't be rescued again
+    mb_setup
+        .call_rescue_token(rand_token, 300, &first_user_addr)
+        .assert_user_error("Insufficient balance");
+    mb_setup
+        .call_rescue_token(rand_token, 200, &first_user_addr)
+        .assert_ok();
+
+    // only the depositor takes the tokens back
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    mb_setup
+        .call_reclaim_failed_deposit(&second_user_addr)
+        .assert_user_error("No failed deposits");
+    mb_setup
+        .call_reclaim_failed_deposit(&first_user_addr)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, rand_token, &rust_biguint!(500));
+    mb_setup
+        .call_reclaim_failed_deposit(&first_user_addr)
+        .assert_user_error("No failed deposits");
+}
+
+#[test]
+fn emergency_withdraw_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+
+    // the deposited rewards are owed to the users
+    assert_eq!(
+        mb_setup.get_token_liabilities(FIRST_PROJ_TOKEN, 0),
+        TOTAL_FIRST_PROJ_TOKENS
+    );
+    mb_setup
+        .call_emergency_withdraw(FIRST_PROJ_TOKEN, 0, 1)
+        .assert_user_error("Amount exceeds the withdrawable balance");
+
+    // only the excess may be withdrawn
+    let sc_addr = mb_setup.mb_wrapper.address_ref().clone();
+    mb_setup.b_mock.set_esdt_balance(
+        &sc_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS + 500),
+    );
+    mb_setup
+        .call_emergency_withdraw(FIRST_PROJ_TOKEN, 0, 501)
+        .assert_user_error("Amount exceeds the withdrawable balance");
+    mb_setup
+        .call_emergency_withdraw(FIRST_PROJ_TOKEN, 0, 500)
+        .assert_ok();
+
+    let owner_addr = mb_setup.owner_addr.clone();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&owner_addr, FIRST_PROJ_TOKEN, &rust_biguint!(500));
+    mb_setup.b_mock.check_esdt_balance(
+        &sc_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS),
+    );
+}
+
+#[test]
+fn add_rewards_checkpoints_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+
+    // try add checkpoint for week 1 again
+    mb_setup
+        .call_add_rewards_checkpoint(1, 500_000, 0)
+        .assert_user_error("Invalid checkpoint week");
+
+    // try add checkpoint week 4 (current week is 3)
+    mb_setup
+        .call_add_rewards_checkpoint(4, 500_000, 0)
+        .assert_user_error("Invalid checkpoint week");
+}
+
+#[test]
+fn week_active_projects_snapshot_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.add_default_checkpoints();
+
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let week_1_ids = sc.get_week_project_ids(1);
+            assert_eq!(week_1_ids.len(), 1);
+            assert_eq!(week_1_ids.get(0), managed_buffer!(FIRST_PROJ_ID));
+            assert_eq!(sc.get_week_project_ids(2).len(), 2);
+        })
+        .assert_ok();
+
+    // a project starting in an already checkpointed week is added to the week's snapshot
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup
+        .call_add_project(
+            b"ThirdProj",
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            2,
+            4,
+            0,
+        )
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(sc.get_week_project_ids(1).len(), 1);
+            let week_2_ids = sc.get_week_project_ids(2);
+            assert_eq!(week_2_ids.len(), 3);
+            assert_eq!(week_2_ids.get(2), managed_buffer!(b"ThirdProj"));
+        })
+        .assert_ok();
+}
+
+#[test]
+fn add_rewards_checkpoints_batch_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+
+    // current week = 3
+    mb_setup.set_current_epoch(26);
+
+    // try add out of order
+    mb_setup
+        .call_add_rewards_checkpoints(&[(2, 200_000, 0), (1, 100_000, 0)])
+        .assert_user_error("Invalid checkpoint week");
+
+    // try add future week
+    mb_setup
+        .call_add_rewards_checkpoints(&[
+            (1, 100_000, 0),
+            (2, 200_000, 0),
+            (3, 300_000, 0),
+            (4, 400_000, 0),
+        ])
+        .assert_user_error("Invalid checkpoint week");
+
+    mb_setup
+        .call_add_rewards_checkpoints(&[(1, 100_000, 0), (2, 200_000, 0), (3, 300_000, 0)])
+        .assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let claimable_weeks = mb_setup.get_user_claimable_weeks(&first_user_addr);
+    assert_eq!(claimable_weeks, &[1usize, 2usize, 3usize]);
+}
+
+#[test]
+fn update_rewards_checkpoint_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.set_current_epoch(20);
+    mb_setup.call_unpause().assert_ok();
+
+    // typo in week 1 supply
+    mb_setup
+        .call_add_rewards_checkpoint(1, 10_000, 0)
+        .assert_ok();
+    assert!(mb_setup.get_pretty_rewards(1, 25_000, 0).is_empty());
+
+    mb_setup
+        .call_update_rewards_checkpoint(2, 200_000, 0)
+        .assert_user_error("No checkpoint for week");
+
+    mb_setup
+        .call_update_rewards_checkpoint(1, 100_000, 0)
+        .assert_ok();
+    assert_eq!(
+        mb_setup.get_pretty_rewards(1, 25_000, 0),
+        &[(
+            b"FirstProj".to_vec(),
+            b"PROJ-123456".to_vec(),
+            83_333_333u64
+        )]
+    );
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+
+    mb_setup
+        .call_update_rewards_checkpoint(1, 200_000, 0)
+        .assert_user_error("Rewards were already claimed for this week");
+}
+
+#[test]
+fn oracle_signed_checkpoint_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.set_current_epoch(20);
+
+    // seeds are sha256("oracle1"), sha256("oracle2") and sha256("oracle3")
+    let first_oracle = Address::from(hex_literal::hex!(
+        "f6ce6020352f241644dada1f285afb51df3d81cacd3f1e63befbe928fd8dfeba"
+    ));
+    let second_oracle = Address::from(hex_literal::hex!(
+        "8e3dc9d1f1bde655c09aae66b127b6d3c051e44a6ddfec9bb5f71e30fd04efed"
+    ));
+    let third_oracle = Address::from(hex_literal::hex!(
+        "f92ca26904967b96f25b73f4734c53aacb945af2efb851d7e036092ab7d61370"
+    ));
+    let first_oracle_sig_week_1 = hex_literal::hex!("5c102a7d93987ac60117f5b9685caf70375bcc59faf622c3e28a96bd0365dedc7b937ac1bf696c033aa473672fa160efcdf161f9d77973808295df763e69070f");
+    let second_oracle_sig_week_1 = hex_literal::hex!("5bf70ab42c6e6a5b14701d24c3eee32dca304115414ba99b433c11e2149047f49bf11ae75d35911c93fc7c0f37b5f6655a749273e54d8c3f1eec645e3fff700e");
+    let third_oracle_sig_week_1_wrong_supply = hex_literal::hex!("bb830455225b67fa2e14371f2a6f9920473984000a8ab80eab38ba569a8aac40570e80af35accf15c6bdfaf456a93f1142adc3eb9394bd2902a90e05b366850c");
+
+    mb_setup
+        .call_set_oracle_quorum(2)
+        .assert_user_error("Quorum higher than number of oracles");
+    mb_setup
+        .call_add_oracles(&[&first_oracle, &second_oracle, &third_oracle])
+        .assert_ok();
+    mb_setup.call_set_oracle_quorum(2).assert_ok();
+
+    // unsigned checkpoints are no longer accepted, not even from the owner
+    mb_setup
+        .call_add_rewards_checkpoint(1, 100_000, 0)
+        .assert_user_error("Not enough oracle signatures");
+    mb_setup
+        .call_add_rewards_checkpoints(&[(1, 100_000, 0)])
+        .assert_user_error("Checkpoints must be signed by oracles");
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    mb_setup
+        .call_add_signed_rewards_checkpoint(
+            &first_user_addr,
+            1,
+            100_000,
+            0,
+            &[(&first_oracle, &first_oracle_sig_week_1)],
+        )
+        .assert_user_error("Not enough oracle signatures");
+    mb_setup
+        .call_add_signed_rewards_checkpoint(
+            &first_user_addr,
+            1,
+            100_000,
+            0,
+            &[
+                (&first_oracle, &first_oracle_sig_week_1),
+                (&first_oracle, &first_oracle_sig_week_1),
+            ],
+        )
+        .assert_user_error("Duplicate oracle signature");
+    mb_setup
+        .call_add_signed_rewards_checkpoint(
+            &first_user_addr,
+            1,
+            100_000,
+            0,
+            &[
+                (&first_oracle, &first_oracle_sig_week_1),
+                (&third_oracle, &third_oracle_sig_week_1_wrong_supply),
+            ],
+        )
+        .assert_user_error("Invalid oracle signature");
+    mb_setup
+        .call_add_signed_rewards_checkpoint(
+            &first_user_addr,
+            1,
+            100_000,
+            0,
+            &[
+                (&first_user_addr, &first_oracle_sig_week_1),
+                (&second_oracle, &second_oracle_sig_week_1),
+            ],
+        )
+        .assert_user_error("Unknown oracle");
+
+    // anyone may submit a checkpoint signed by enough oracles
+    mb_setup
+        .call_add_signed_rewards_checkpoint(
+            &first_user_addr,
+            1,
+            100_000,
+            0,
+            &[
+                (&first_oracle, &first_oracle_sig_week_1),
+                (&second_oracle, &second_oracle_sig_week_1),
+            ],
+        )
+        .assert_ok();
+    assert_eq!(
+        mb_setup.get_pretty_rewards(1, 25_000, 0),
+        &[(
+            b"FirstProj".to_vec(),
+            b"PROJ-123456".to_vec(),
+            83_333_333u64
+        )]
+    );
+
+    // the square root sums must be signed by the oracles as well, under their own tag
+    let first_oracle_sqrt_sums_sig_week_1 = hex_literal::hex!("aee42f9376b43d37d450fa7d46a1aec2c8af88e4d092886f2d60278e00dc090aca3f7487f3affd17221c2fa1578651650bdf57c2ff7ebf07c01e8c7040bc0206");
+    let second_oracle_sqrt_sums_sig_week_1 = hex_literal::hex!("26b2bd6a042bc7357a8c4e0efe69b70c959d14b45b3a834d98c64893a99dc860aa03b510933c537cb2312966cb9feaa3bcadc58f73e92ec06b99d00993765105");
+    mb_setup
+        .call_set_checkpoint_sqrt_sums(1, 500, 0)
+        .assert_user_error("Not enough oracle signatures");
+    mb_setup
+        .call_set_signed_checkpoint_sqrt_sums(
+            &first_user_addr,
+            1,
+            100_000,
+            0,
+            &[
+                (&first_oracle, &first_oracle_sig_week_1),
+                (&second_oracle, &second_oracle_sig_week_1),
+            ],
+        )
+        .assert_user_error("Invalid oracle signature");
+    mb_setup
+        .call_set_signed_checkpoint_sqrt_sums(
+            &first_user_addr,
+            1,
+            500,
+            0,
+            &[
+                (&first_oracle, &first_oracle_sqrt_sums_sig_week_1),
+                (&second_oracle, &second_oracle_sqrt_sums_sig_week_1),
+            ],
+        )
+        .assert_ok();
+
+    // corrections are signed separately, with the week's number of past updates as the nonce
+    let first_oracle_update_sig_week_1 = hex_literal::hex!("50ba0a868b3205d10531049f35158e093c1037b04dfe541214dd380271f966cb2140d6f6abee4f31136935ced83851a16d85d8ee7b274598255d72253783cc01");
+    let second_oracle_update_sig_week_1 = hex_literal::hex!("c23766729b06ca294a086b4924a0369427f035388a16ed1c6ea58ed4f8ccef3dc0445675296faae7ed244d35b78abc7a0fc085c9344e6f4c10aabf8ef8a54806");
+    mb_setup
+        .call_update_signed_rewards_checkpoint(
+            &first_user_addr,
+            1,
+            100_000,
+            0,
+            &[
+                (&first_oracle, &first_oracle_sig_week_1),
+                (&second_oracle, &second_oracle_sig_week_1),
+            ],
+        )
+        .assert_user_error("Invalid oracle signature");
+    mb_setup
+        .call_update_signed_rewards_checkpoint(
+            &first_user_addr,
+            1,
+            150_000,
+            0,
+            &[
+                (&first_oracle, &first_oracle_update_sig_week_1),
+                (&second_oracle, &second_oracle_update_sig_week_1),
+            ],
+        )
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(sc.checkpoint_update_nonce(1).get(), 1);
+        })
+        .assert_ok();
+
+    // the correction can't be replayed once applied
+    mb_setup
+        .call_update_signed_rewards_checkpoint(
+            &first_user_addr,
+            1,
+            150_000,
+            0,
+            &[
+                (&first_oracle, &first_oracle_update_sig_week_1),
+                (&second_oracle, &second_oracle_update_sig_week_1),
+            ],
+        )
+        .assert_user_error("Invalid oracle signature");
+}
+
+#[test]
+fn auto_checkpoint_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.set_current_epoch(20);
+
+    mb_setup
+        .call_request_rewards_checkpoint()
+        .assert_user_error("Checkpoint sources not set");
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    mb_setup
+        .call_set_checkpoint_sources(&first_user_addr, &second_user_addr)
+        .assert_user_error("Invalid SC address");
+
+    // failed async call does not add the checkpoint
+    mb_setup
+        .call_auto_checkpoint_callback(1, 0, None)
+        .assert_ok();
+    assert_eq!(mb_setup.get_last_checkpoint_week(), 0);
+
+    mb_setup
+        .call_auto_checkpoint_callback(1, 0, Some(100_000))
+        .assert_ok();
+    assert_eq!(mb_setup.get_last_checkpoint_week(), 1);
+    assert_eq!(
+        mb_setup.get_pretty_rewards(1, 25_000, 0),
+        &[(
+            b"FirstProj".to_vec(),
+            b"PROJ-123456".to_vec(),
+            83_333_333u64
+        )]
+    );
+
+    // week 1 checkpoint was already added, so a late callback is ignored
+    mb_setup
+        .call_auto_checkpoint_callback(1, 0, Some(200_000))
+        .assert_ok();
+    assert_eq!(mb_setup.get_last_checkpoint_week(), 1);
+    assert_eq!(
+        mb_setup.get_pretty_rewards(1, 25_000, 0),
+        &[(
+            b"FirstProj".to_vec(),
+            b"PROJ-123456".to_vec(),
+            83_333_333u64
+        )]
+    );
+}
+
+#[test]
+fn energy_weighted_project_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let proj_owner = mb_setup.first_project_owner.clone();
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    mb_setup
+        .b_mock
+        .set_esdt_balance(&proj_owner, b"ENRG-123456", &rust_biguint!(1_000));
+    mb_setup
+        .call_add_project(
+            b"EnergyProj",
+            &proj_owner,
+            b"ENRG-123456",
+            1_000,
+            2,
+            1,
+            10_000,
+        )
+        .assert_ok();
+    mb_setup
+        .call_deposit_rewards(&proj_owner, b"EnergyProj", b"ENRG-123456", 1_000)
+        .assert_ok();
+
+    mb_setup
+        .call_set_project_energy_weighted(&first_user_addr, b"EnergyProj", true)
+        .assert_user_error("Only project owner may change the distribution");
+    mb_setup
+        .call_set_project_energy_weighted(&proj_owner, b"EnergyProj", true)
+        .assert_ok();
+
+    mb_setup.set_current_epoch(20);
+    mb_setup
+        .call_set_project_energy_weighted(&proj_owner, b"EnergyProj", false)
+        .assert_user_error("Project already started");
+
+    mb_setup
+        .call_add_rewards_checkpoint(1, 0, 1_000)
+        .assert_ok();
+    mb_setup
+        .call_add_rewards_checkpoint(2, 0, 1_000)
+        .assert_ok();
+
+    // no energy in checkpoint, so LKMEX staked is used
+    assert_eq!(
+        mb_setup.get_pretty_rewards(2, 0, 500),
+        &[(b"EnergyProj".to_vec(), b"ENRG-123456".to_vec(), 500u64)]
+    );
+
+    mb_setup
+        .call_set_checkpoint_total_energy(3, 4_000)
+        .assert_user_error("No checkpoint for week");
+    let sig_first_user_energy_week_2 = hex_literal::hex!("d0324bd96db2d0075cd8a69d4fb43d0c484c94591694e5c6cc916f9f1f497cf671531f0cb3c2321b525b33f801690fed3fba91721d4526f52c7fbd8af5b4980d");
+    mb_setup
+        .call_register_signed_energy(&first_user_addr, 2, 500, &sig_first_user_energy_week_2)
+        .assert_user_error("No total energy for week");
+    mb_setup
+        .call_set_checkpoint_total_energy(2, 4_000)
+        .assert_ok();
+
+    // the signed LKMEX amount is no longer used as energy
+    assert!(mb_setup.get_pretty_rewards(2, 0, 500).is_empty());
+
+    mb_setup
+        .call_register_signed_energy(&first_user_addr, 2, 400, &sig_first_user_energy_week_2)
+        .assert_user_error("Invalid signature");
+    mb_setup
+        .call_register_signed_energy(&first_user_addr, 2, 500, &sig_first_user_energy_week_2)
+        .assert_ok();
+    mb_setup
+        .call_register_signed_energy(&first_user_addr, 2, 500, &sig_first_user_energy_week_2)
+        .assert_user_error("Energy already registered for week");
+
+    mb_setup.call_unpause().assert_ok();
+    let sig_first_user_week_2 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, b"ENRG-123456", &rust_biguint!(125));
+}
+
+#[test]
+fn quadratic_distribution_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup
+        .call_set_project_distribution_mode(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            DistributionMode::Quadratic,
+        )
+        .assert_ok();
+    mb_setup.add_default_checkpoints();
+
+    // no square root sums in checkpoint, so the amounts are used as they are
+    assert_eq!(
+        mb_setup.get_pretty_rewards(1, 25_000, 0),
+        &[(
+            FIRST_PROJ_ID.to_vec(),
+            FIRST_PROJ_TOKEN.to_vec(),
+            83_333_333u64
+        )]
+    );
+
+    // the sums must be between sqrt(total) and total, here sqrt(100_000) = 316
+    mb_setup
+        .call_set_checkpoint_sqrt_sums(1, 315, 0)
+        .assert_user_error("Invalid square root sum");
+    mb_setup
+        .call_set_checkpoint_sqrt_sums(1, 100_001, 0)
+        .assert_user_error("Invalid square root sum");
+    mb_setup
+        .call_set_checkpoint_sqrt_sums(1, 500, 1)
+        .assert_user_error("Invalid square root sum");
+
+    // 333_333_333 * sqrt(25_000) / 500
+    mb_setup
+        .call_set_checkpoint_sqrt_sums(1, 500, 0)
+        .assert_ok();
+    assert_eq!(
+        mb_setup.get_pretty_rewards(1, 25_000, 0),
+        &[(
+            FIRST_PROJ_ID.to_vec(),
+            FIRST_PROJ_TOKEN.to_vec(),
+            105_333_333u64
+        )]
+    );
+
+    mb_setup
+        .call_set_project_distribution_mode(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            DistributionMode::Linear,
+        )
+        .assert_user_error("Project already started");
+}
+
+#[test]
+fn combined_distribution_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup
+        .b_mock
+        .set_esdt_balance(&proj_owner, b"CMB-123456", &rust_biguint!(1_000));
+    mb_setup
+        .call_add_project(b"CombProj", &proj_owner, b"CMB-123456", 1_000, 2, 1, 5_000)
+        .assert_ok();
+    mb_setup
+        .call_deposit_rewards(&proj_owner, b"CombProj", b"CMB-123456", 1_000)
+        .assert_ok();
+
+    mb_setup
+        .call_set_project_distribution_mode(&proj_owner, b"CombProj", DistributionMode::Combined)
+        .assert_ok();
+    mb_setup
+        .call_set_project_lkmex_factor(&proj_owner, b"CombProj", 10_000)
+        .assert_ok();
+
+    mb_setup.set_current_epoch(20);
+    mb_setup
+        .call_set_project_lkmex_factor(&proj_owner, b"CombProj", 5_000)
+        .assert_user_error("Project already started");
+
+    mb_setup
+        .call_add_rewards_checkpoint(2, 1_000, 2_000)
+        .assert_ok();
+
+    // a weight of 1_000 out of 3_000 for the whole supply, instead of the whole delegation pool
+    assert_eq!(
+        mb_setup.get_pretty_rewards(2, 1_000, 0),
+        &[(b"CombProj".to_vec(), b"CMB-123456".to_vec(), 332u64)]
+    );
+    assert_eq!(
+        mb_setup.get_pretty_rewards(2, 0, 1_000),
+        &[(b"CombProj".to_vec(), b"CMB-123456".to_vec(), 332u64)]
+    );
+}
+
+#[test]
+fn get_rewards_checkpoints_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.set_current_epoch(30);
+    mb_setup
+        .call_add_rewards_checkpoints(&[(1, 100_000, 10), (2, 200_000, 20), (3, 300_000, 30)])
+        .assert_ok();
+
+    assert_eq!(
+        mb_setup.get_rewards_checkpoints(0, 2),
+        &[(1, 100_000, 10), (2, 200_000, 20)]
+    );
+    assert_eq!(
+        mb_setup.get_rewards_checkpoints(2, 5),
+        &[(2, 200_000, 20), (3, 300_000, 30)]
+    );
+    assert!(mb_setup.get_rewards_checkpoints(4, 5).is_empty());
+    assert!(mb_setup.get_rewards_checkpoints(1, 0).is_empty());
+}
+
+#[test]
+fn project_apr_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.add_default_checkpoints();
+
+    // 333_333_333 * 52 * 10_000 / (100_000 * 1_000)
+    assert_eq!(
+        mb_setup.get_project_apr(FIRST_PROJ_ID, 1, 1, 1_000, 1_000),
+        (1_733_333, 0)
+    );
+
+    // SecondProj starts in week 2: 400_000_000 * 3 * 52 * 10_000 / (200_000 * 1_000)
+    assert_eq!(
+        mb_setup.get_project_apr(SECOND_PROJ_ID, 2, 3, 1_000, 1_000),
+        (3_120_000, 0)
+    );
+}
+
+#[test]
+fn claim_streak_bonus_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    mb_setup
+        .call_set_project_streak_bonus(FIRST_PROJ_ID, 2_000, 1_000)
+        .assert_user_error("Invalid bonus");
+    // 10% bonus for each consecutive week, up to 10%
+    mb_setup
+        .call_set_project_streak_bonus(FIRST_PROJ_ID, 1_000, 1_000)
+        .assert_ok();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup.b_mock.set_esdt_balance(
+        &first_proj_owner,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(100_000_000),
+    );
+    mb_setup
+        .call_deposit_project_bonus_pool(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            100_000_000,
+        )
+        .assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+    let sig_second_user_week_2 = hex_literal::hex!("00601643e9d0a4f0da6b347393522e85ee2f183bb0f7168e1130a5081a22def4263f3b18637ac8f2840547c58be8f3d88c9b6e2a2e1933ab37f0acfce1663d0c");
+
+    // first week of the streak - no bonus
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333),
+    );
+
+    // second consecutive week - 10% bonus
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333 + 41_666_666 + 4_166_666),
+    );
+    assert_eq!(
+        mb_setup.get_project_bonus_pool(FIRST_PROJ_ID),
+        100_000_000 - 4_166_666
+    );
+
+    // second user missed week 1, so their streak starts in week 2
+    mb_setup
+        .call_claim_rewards(&second_user_addr, 2, 50_000, 0, &sig_second_user_week_2)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333),
+    );
+}
+
+#[test]
+fn min_reward_per_user_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    mb_setup
+        .call_set_project_min_reward_per_user(FIRST_PROJ_ID, 100_000_000)
+        .assert_ok();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup.b_mock.set_esdt_balance(
+        &first_proj_owner,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(20_000_000),
+    );
+    mb_setup
+        .call_deposit_project_bonus_pool(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            20_000_000,
+        )
+        .assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+    let sig_second_user_week_1 = hex_literal::hex!("6056836289c61837308d014ab372a90f7665238bcb85dd1d6c9cad1feff7be9516d5348b7e27f8c74036a4f75d576bccca630611545179399202e268f1388103");
+
+    // raised to the minimum
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(100_000_000),
+    );
+    assert_eq!(mb_setup.get_project_bonus_pool(FIRST_PROJ_ID), 3_333_333);
+
+    // already above the minimum
+    mb_setup
+        .call_claim_rewards(&second_user_addr, 1, 50_000, 0, &sig_second_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(166_666_666),
+    );
+
+    // only the rest of the bonus pool is added
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(100_000_000 + 41_666_666 + 3_333_333),
+    );
+    assert_eq!(mb_setup.get_project_bonus_pool(FIRST_PROJ_ID), 0);
+}
+
+#[test]
+fn signer_rotation_test() {
+    // associated private key: 9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60
+    let new_signer =
+        hex_literal::hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    mb_setup.call_set_signer_grace_period_epochs(7).assert_ok();
+    mb_setup.call_add_signer(&new_signer).assert_ok();
+    mb_setup
+        .call_add_signer(&new_signer)
+        .assert_user_error("Signer already added");
+    mb_setup
+        .call_remove_signer(&[0u8; 32])
+        .assert_user_error("Unknown signer");
+    mb_setup.call_remove_signer(&SIGNER_ADDRESS).assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+    let new_sig_first_user_week_2 = hex_literal::hex!("b052bf6e58095d7008b6e048a35974b2dd4084730a345db3a92208f8b136e4086bb90cb7b63b15e75da1b93b7cda443963eee014535c0152cacec9f0c7f84c08");
+    let new_sig_second_user_week_1 = hex_literal::hex!("af2c3c11773fc028da8ab2e30bf2fb96eb3ba687147e5a0089c92e91aa381a5dcdbcb842e391b232b744c52b9edf518fb088bfe5043718078a1747967bcaba0d");
+
+    // both keys are valid during the grace period
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup
+        .call_claim_rewards(&second_user_addr, 1, 50_000, 0, &new_sig_second_user_week_1)
+        .assert_ok();
+
+    // only the new key is valid after the grace period
+    mb_setup.b_mock.set_block_epoch(28);
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_user_error("Invalid signature");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &new_sig_first_user_week_2)
+        .assert_ok();
+}
+
+#[test]
+fn multi_sig_claim_test() {
+    // associated private key: 9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60
+    let second_signer =
+        hex_literal::hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    mb_setup
+        .call_set_claim_signature_threshold(2)
+        .assert_user_error("Threshold higher than number of signers");
+    mb_setup.call_add_signer(&second_signer).assert_ok();
+    mb_setup.call_set_claim_signature_threshold(2).assert_ok();
+    mb_setup
+        .call_remove_signer(&second_signer)
+        .assert_user_error("Not enough signers left for threshold");
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let first_signer_sig = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let second_signer_sig = hex_literal::hex!("c93c3640a0964c9c91e3bf159b6b607e76983a3acd86ce121f010656ec46a911fc4c590a99ad5f9cde63149348994c824c0d1f0d6ffdf9dc50dc5c763e0e2a0a");
+
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &first_signer_sig)
+        .assert_user_error("Multiple signatures required");
+    mb_setup
+        .call_claim_rewards_multi_sig(
+            &first_user_addr,
+            1,
+            25_000,
+            0,
+            &[(&SIGNER_ADDRESS, &first_signer_sig)],
+        )
+        .assert_user_error("Not enough signatures");
+    mb_setup
+        .call_claim_rewards_multi_sig(
+            &first_user_addr,
+            1,
+            25_000,
+            0,
+            &[
+                (&SIGNER_ADDRESS, &first_signer_sig),
+                (&SIGNER_ADDRESS, &first_signer_sig),
+            ],
+        )
+        .assert_user_error("Duplicate signer signature");
+    mb_setup
+        .call_claim_rewards_multi_sig(
+            &first_user_addr,
+            1,
+            25_000,
+            0,
+            &[
+                (&SIGNER_ADDRESS, &first_signer_sig),
+                (&second_signer, &first_signer_sig),
+            ],
+        )
+        .assert_user_error("Invalid signature");
+    mb_setup
+        .call_claim_rewards_multi_sig(
+            &first_user_addr,
+            1,
+            25_000,
+            0,
+            &[
+                (&SIGNER_ADDRESS, &first_signer_sig),
+                (&second_signer, &second_signer_sig),
+            ],
+        )
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333),
+    );
+}
+
+#[cfg(not(feature = "devnet"))]
+#[test]
+fn sig_check_disabled_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup
+        .call_set_sig_check_disabled(true)
+        .assert_user_error("Signature checks can only be disabled on devnet builds");
+}
+
+#[cfg(feature = "devnet")]
+#[test]
+fn sig_check_disabled_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+    mb_setup.call_set_sig_check_disabled(true).assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 50_000, 0, &[0u8; 64])
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(166_666_666),
+    );
+}
+
+#[test]
+fn bonus_week_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    mb_setup
+        .call_set_bonus_week(1, 9_999)
+        .assert_user_error("Invalid multiplier");
+    mb_setup.call_set_bonus_week(1, 15_000).assert_ok();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup.b_mock.set_esdt_balance(
+        &first_proj_owner,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+    mb_setup
+        .call_deposit_project_bonus_pool(
+            &first_proj_owner,
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            50_000_000,
+        )
+        .assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_second_user_week_1 = hex_literal::hex!("6056836289c61837308d014ab372a90f7665238bcb85dd1d6c9cad1feff7be9516d5348b7e27f8c74036a4f75d576bccca630611545179399202e268f1388103");
+
+    // boosted by half
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333 + 41_666_666),
+    );
+    assert_eq!(mb_setup.get_project_bonus_pool(FIRST_PROJ_ID), 8_333_334);
+
+    mb_setup
+        .call_set_bonus_week(1, 20_000)
+        .assert_user_error("Rewards were already claimed for this week");
+
+    // only the rest of the bonus pool is added
+    mb_setup
+        .call_claim_rewards(&second_user_addr, 1, 50_000, 0, &sig_second_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(166_666_666 + 8_333_334),
+    );
+    assert_eq!(mb_setup.get_project_bonus_pool(FIRST_PROJ_ID), 0);
+}
+
+#[test]
+fn rounding_remainders_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+
+    let proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup
+        .b_mock
+        .set_esdt_balance(&proj_owner, b"RND-123456", &rust_biguint!(11));
+    mb_setup
+        .call_add_project(b"RoundProj", &proj_owner, b"RND-123456", 11, 1, 1, 0)
+        .assert_ok();
+    mb_setup
+        .call_deposit_rewards(&proj_owner, b"RoundProj", b"RND-123456", 11)
+        .assert_ok();
+
+    mb_setup
+        .call_reclaim_project_rounding_remainders(&proj_owner, b"RoundProj")
+        .assert_user_error("Project not ended");
+
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_second_user_week_1 = hex_literal::hex!("6056836289c61837308d014ab372a90f7665238bcb85dd1d6c9cad1feff7be9516d5348b7e27f8c74036a4f75d576bccca630611545179399202e268f1388103");
+
+    // 11 * 1/4 = 2.75
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, b"RND-123456", &rust_biguint!(2));
+    assert_eq!(mb_setup.get_project_rounding_remainders(b"RoundProj"), 0);
+
+    // 11 * 1/2 = 5.5, so the remainders add up to a whole token
+    mb_setup
+        .call_claim_rewards(&second_user_addr, 1, 50_000, 0, &sig_second_user_week_1)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&second_user_addr, b"RND-123456", &rust_biguint!(5));
+    assert_eq!(mb_setup.get_project_rounding_remainders(b"RoundProj"), 1);
+
+    mb_setup
+        .call_reclaim_project_rounding_remainders(&first_user_addr, b"RoundProj")
+        .assert_user_error("Only project manager or project owner may call this function");
+    mb_setup
+        .call_reclaim_project_rounding_remainders(&proj_owner, b"RoundProj")
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&proj_owner, b"RND-123456", &rust_biguint!(1));
+    mb_setup
+        .call_reclaim_project_rounding_remainders(&proj_owner, b"RoundProj")
+        .assert_user_error("No rounding remainders");
+}
+
+#[test]
+fn min_payout_dust_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    mb_setup
+        .call_set_min_payout_amount(FIRST_PROJ_TOKEN, 100_000_000)
+        .assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+    let sig_second_user_week_1 = hex_literal::hex!("6056836289c61837308d014ab372a90f7665238bcb85dd1d6c9cad1feff7be9516d5348b7e27f8c74036a4f75d576bccca630611545179399202e268f1388103");
+
+    // below the minimum - kept as dust
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+    assert_eq!(
+        mb_setup.get_user_dust(&first_user_addr),
+        &[(FIRST_PROJ_TOKEN.to_vec(), 0, 83_333_333)]
+    );
+
+    // the dust is counted once, in place of the paid out funds
+    assert_eq!(
+        mb_setup.get_token_liabilities(FIRST_PROJ_TOKEN, 0),
+        TOTAL_FIRST_PROJ_TOKENS
+    );
+
+    // accumulated dust crosses the minimum, so it's paid out along with the new rewards
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333 + 41_666_666),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+    mb_setup
+        .call_claim_dust(&first_user_addr)
+        .assert_user_error("No dust to claim");
+    assert!(mb_setup.get_user_dust(&first_user_addr).is_empty());
+
+    // dust can be claimed at any time, regardless of the minimum
+    mb_setup
+        .call_set_min_payout_amount(FIRST_PROJ_TOKEN, 1_000_000_000)
+        .assert_ok();
+    mb_setup
+        .call_claim_rewards(&second_user_addr, 1, 50_000, 0, &sig_second_user_week_1)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&second_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+
+    mb_setup.call_claim_dust(&second_user_addr).assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(166_666_666),
+    );
+}
+
+#[test]
+fn checkpoint_finalization_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.call_unpause().assert_ok();
+    mb_setup.call_set_checkpoint_dispute_epochs(3).assert_ok();
+    mb_setup.add_default_checkpoints();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+
+    assert!(mb_setup.get_checkpoint_status(1) == CheckpointStatus::Pending);
+    assert!(mb_setup
+        .get_user_claimable_weeks(&first_user_addr)
+        .is_empty());
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Checkpoint not finalized yet");
+
+    // still correctable while pending
+    mb_setup
+        .call_update_rewards_checkpoint(1, 100_000, 0)
+        .assert_ok();
+
+    mb_setup.call_finalize_checkpoint(1).assert_ok();
+    mb_setup
+        .call_finalize_checkpoint(1)
+        .assert_user_error("Checkpoint not pending");
+    assert!(mb_setup.get_checkpoint_status(1) == CheckpointStatus::Finalized);
+    assert_eq!(mb_setup.get_user_claimable_weeks(&first_user_addr), &[1]);
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+
+    // dispute window for week 2 ends by itself
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_user_error("Checkpoint not finalized yet");
+    mb_setup.set_current_epoch(23);
+    assert!(mb_setup.get_checkpoint_status(2) == CheckpointStatus::Finalized);
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+}
+
+#[test]
+fn user_claimable_weeks_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    assert_eq!(mb_setup.get_user_claimable_weeks(&first_user_addr), &[1, 2]);
+
+    // claimed weeks are left out, for that user only
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    assert_eq!(mb_setup.get_user_claimable_weeks(&first_user_addr), &[2]);
+    assert_eq!(
+        mb_setup.get_user_claimable_weeks(&second_user_addr),
+        &[1, 2]
+    );
+
+    // the claim window follows the current week, even without newer checkpoints
+    mb_setup.set_current_epoch(47);
+    assert_eq!(mb_setup.get_user_claimable_weeks(&first_user_addr), &[2]);
+    assert_eq!(mb_setup.get_user_claimable_weeks(&second_user_addr), &[2]);
+    mb_setup.set_current_epoch(54);
+    assert!(mb_setup
+        .get_user_claimable_weeks(&first_user_addr)
+        .is_empty());
+
+    // a longer expiration makes the week claimable again
+    mb_setup.call_set_claim_expiration_weeks(5).assert_ok();
+    assert_eq!(mb_setup.get_user_claimable_weeks(&first_user_addr), &[2]);
+    assert_eq!(mb_setup.get_user_claimable_weeks(&second_user_addr), &[2]);
+}
+
+#[test]
+fn skipped_week_checkpoint_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.call_unpause().assert_ok();
+
+    // current week = 3, week 2 is skipped
+    mb_setup.set_current_epoch(26);
+    mb_setup
+        .call_add_rewards_checkpoints(&[(1, 100_000, 0), (3, 300_000, 0)])
+        .assert_ok();
+    assert_eq!(mb_setup.get_last_checkpoint_week(), 3);
+    mb_setup
+        .call_add_rewards_checkpoint(2, 200_000, 0)
+        .assert_user_error("Invalid checkpoint week");
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    assert_eq!(mb_setup.get_user_claimable_weeks(&first_user_addr), &[1, 3]);
+    assert!(mb_setup.get_pretty_rewards(2, 25_000, 0).is_empty());
+    assert_eq!(
+        mb_setup.get_rewards_checkpoints(1, 2),
+        &[(1, 100_000, 0), (3, 300_000, 0)]
+    );
+
+    let sig_first_user_week_2 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_user_error("No checkpoint for week");
+}
+
+#[test]
+fn prune_checkpoints_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+
+    mb_setup
+        .call_prune_checkpoints(1)
+        .assert_user_error("Week not expired");
+
+    // current week = 6, week 1 expired, week 2 can still be claimed
+    mb_setup.set_current_epoch(40);
+    mb_setup
+        .call_prune_checkpoints(2)
+        .assert_user_error("Week not expired");
+    mb_setup.call_prune_checkpoints(1).assert_ok();
+    mb_setup
+        .call_prune_checkpoints(1)
+        .assert_user_error("Nothing to prune");
+
+    assert_eq!(mb_setup.get_last_checkpoint_week(), 2);
+    assert_eq!(mb_setup.get_rewards_checkpoints(1, 5), &[(2, 200_000, 0)]);
+    assert!(mb_setup.get_pretty_rewards(1, 25_000, 0).is_empty());
+}
+
+#[test]
+fn keeper_checkpoint_bounty_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.set_current_epoch(20);
+
+    let owner_addr = mb_setup.owner_addr.clone();
+    let keeper_addr = mb_setup.first_user_addr.clone();
+    let other_user_addr = mb_setup.second_user_addr.clone();
+    mb_setup
+        .b_mock
+        .set_egld_balance(&owner_addr, &rust_biguint!(15));
+    mb_setup.call_set_egld_keeper_bounty(10).assert_ok();
+    mb_setup.call_fund_egld_keeper_bounty(15).assert_ok();
+    mb_setup.call_add_keepers(&[&keeper_addr]).assert_ok();
+
+    // signer's signatures over the checkpoint message for (week, total_delegation_supply, total_lkmex_staked)
+    let sig_week_1 = hex_literal::hex!("d6fb6111c960f345dbbe9bf421310538db42e095c4e60e890c792d0cbc43c459a0451244e0a4cd6809aac8fcf61007aa76b4a85c5c6137820743edef3407280e");
+    let sig_week_2 = hex_literal::hex!("dfcc66893d5190d9f5de4d2c490fc749e063b3f62c321b5c04c8543c4ea4f96f69b32cc3715742aa72dfc013708e787d3e9fc8f1b98cbc10d057fb297a343c0d");
+
+    mb_setup
+        .call_keeper_add_rewards_checkpoint(&other_user_addr, 1, 100_000, 0, &sig_week_1)
+        .assert_user_error("Only keepers may submit checkpoints");
+    mb_setup
+        .call_keeper_add_rewards_checkpoint(&keeper_addr, 1, 200_000, 0, &sig_week_1)
+        .assert_user_error("Invalid signature");
+
+    mb_setup
+        .call_keeper_add_rewards_checkpoint(&keeper_addr, 1, 100_000, 0, &sig_week_1)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_egld_balance(&keeper_addr, &rust_biguint!(10));
+
+    // not enough left in the pool, checkpoint is still added
+    mb_setup
+        .call_keeper_add_rewards_checkpoint(&keeper_addr, 2, 200_000, 0, &sig_week_2)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_egld_balance(&keeper_addr, &rust_biguint!(10));
+    assert_eq!(mb_setup.get_last_checkpoint_week(), 2);
+}
+
+#[test]
+fn claim_rewards_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_second_user_week_1 = hex_literal::hex!("6056836289c61837308d014ab372a90f7665238bcb85dd1d6c9cad1feff7be9516d5348b7e27f8c74036a4f75d576bccca630611545179399202e268f1388103");
+    let sig_first_user_week_2 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+    let sig_second_user_week_2 = hex_literal::hex!("3f11e58df1a4059adefe9e7f586431d6d2d68e4431d564e3ef0693bc465073d694f093a08d640f38f406618539602342e09c57386a062e7fe45b236351fa3f0c");
+
+    // get claimable weeks
+    let claimable_weeks = mb_setup.get_user_claimable_weeks(&first_user_addr);
+    assert_eq!(claimable_weeks, &[1usize, 2usize]);
+
+    // get rewards week 1
+    let rewards_week_1_first_user = mb_setup.get_pretty_rewards(1, 25_000, 0);
+    assert_eq!(
+        rewards_week_1_first_user,
+        &[(
+            b"FirstProj".to_vec(),
+            b"PROJ-123456".to_vec(),
+            83_333_333u64
+        )]
+    );
+
+    let rewards_week_1_second_user = mb_setup.get_pretty_rewards(1, 50_000, 0);
+    assert_eq!(
+        rewards_week_1_second_user,
+        &[(
+            b"FirstProj".to_vec(),
+            b"PROJ-123456".to_vec(),
+            166_666_666u64
+        )]
+    );
+
+    // try claim while paused
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("May not claim rewards while paused");
+
+    // unpause
+    mb_setup.call_unpause().assert_ok();
+
+    // try claim wrong user delegation supply
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 30_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Invalid signature");
+
+    // try claim wrong week
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 5, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("No checkpoint for week yet");
+
+    // claim first user week 1 ok
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333),
+    );
+
+    // try claim week 1 again
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Already claimed rewards for this week");
+
+    // claim second user week 1 ok
+    mb_setup
+        .call_claim_rewards(&second_user_addr, 1, 50_000, 0, &sig_second_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(166_666_666),
+    );
+
+    // get rewards week 2
+    let rewards_week_2_first_user = mb_setup.get_pretty_rewards(2, 25_000, 0);
+    assert_eq!(
+        rewards_week_2_first_user,
+        &[
+            (
+                b"FirstProj".to_vec(),
+                b"PROJ-123456".to_vec(),
+                41_666_666u64
+            ),
+            (
+                b"SecondProj".to_vec(),
+                b"COOL-123456".to_vec(),
+                50_000_000u64
+            )
+        ]
+    );
+    let rewards_week_2_second_user = mb_setup.get_pretty_rewards(2, 50_000, 0);
+    assert_eq!(
+        rewards_week_2_second_user,
+        &[
+            (
+                b"FirstProj".to_vec(),
+                b"PROJ-123456".to_vec(),
+                83_333_333u64
+            ),
+            (
+                b"SecondProj".to_vec(),
+                b"COOL-123456".to_vec(),
+                100_000_000u64
+            )
+        ]
+    );
+
+    // claim first user week 2 ok
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333 + 41_666_666),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+
+    // claim second user week 2 ok
+    mb_setup
+        .call_claim_rewards(&second_user_addr, 2, 50_000, 0, &sig_second_user_week_2)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(166_666_666 + 83_333_333),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &second_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(100_000_000),
+    );
+
+    // clear expired projects
+    mb_setup.b_mock.set_block_epoch(100);
+    mb_setup.call_clear_expired_projects().assert_ok();
+
+    mb_setup.b_mock.check_esdt_balance(
+        &mb_setup.first_project_owner.clone(),
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(625_000_002),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &mb_setup.second_project_owner.clone(),
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(1_850_000_000),
+    );
+}
+
+#[test]
+fn clear_expired_project_bounty_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let owner_addr = mb_setup.owner_addr.clone();
+    let caller_addr = mb_setup.first_user_addr.clone();
+    mb_setup
+        .b_mock
+        .set_egld_balance(&owner_addr, &rust_biguint!(15));
+    mb_setup.call_set_egld_cleanup_bounty(10).assert_ok();
+    mb_setup.call_fund_egld_cleanup_bounty(15).assert_ok();
+
+    // keep the second project's week 2 rewards
+    let sig_first_user_week_2 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+    mb_setup
+        .call_pause_project_claims(SECOND_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .call_claim_rewards(&caller_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+
+    mb_setup
+        .call_clear_expired_project(&caller_addr, FIRST_PROJ_ID)
+        .assert_user_error("Project not expired");
+
+    mb_setup.b_mock.set_block_epoch(100);
+    mb_setup
+        .call_clear_expired_project(&caller_addr, b"Unknown")
+        .assert_user_error("Invalid project ID");
+
+    // any address may clear an expired project and receives the bounty
+    mb_setup
+        .call_clear_expired_project(&caller_addr, FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_egld_balance(&caller_addr, &rust_biguint!(10));
+    mb_setup.b_mock.check_esdt_balance(
+        &mb_setup.first_project_owner.clone(),
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS - 41_666_666),
+    );
+    assert_eq!(
+        mb_setup.get_all_project_ids(),
+        vec![SECOND_PROJ_ID.to_vec()]
+    );
+
+    // the kept rewards can't be lost by clearing the project
+    mb_setup
+        .call_clear_expired_project(&caller_addr, SECOND_PROJ_ID)
+        .assert_user_error("Project has paused rewards");
+
+    mb_setup
+        .call_resume_project_claims(SECOND_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .call_claim_paused_project_rewards(&caller_addr, &[SECOND_PROJ_ID])
+        .assert_ok();
+
+    // not enough left in the pool, the project is still cleared
+    mb_setup
+        .call_clear_expired_project(&caller_addr, SECOND_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_egld_balance(&caller_addr, &rust_biguint!(10));
+    assert!(mb_setup.get_all_project_ids().is_empty());
+
+    // the owner takes back what's left in the pool
+    mb_setup
+        .call_withdraw_cleanup_bounty(10)
+        .assert_user_error("Invalid amount");
+    mb_setup.call_withdraw_cleanup_bounty(5).assert_ok();
+    mb_setup
+        .b_mock
+        .check_egld_balance(&owner_addr, &rust_biguint!(5));
+}
+
+#[test]
+fn claim_nonce_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2_nonce_0 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+    let sig_first_user_week_2_nonce_1 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    assert_eq!(mb_setup.get_claim_nonce(&first_user_addr), 1);
+
+    // signatures for older nonces can no longer be used
+    mb_setup
+        .call_claim_rewards(
+            &first_user_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2_nonce_0,
+        )
+        .assert_user_error("Invalid signature");
+    mb_setup
+        .call_claim_rewards(
+            &first_user_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2_nonce_1,
+        )
+        .assert_ok();
+    assert_eq!(mb_setup.get_claim_nonce(&first_user_addr), 2);
+}
+
+#[test]
+fn expiring_signature_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_valid_until_19 = hex_literal::hex!("8f914a63880450308f3f49be718efca78e9db03f573ee172aee78a81946a709e4e49743f37a7c5258d5784fc3c301a2dc816302f4c01eb52dd25404f07b47703");
+    let sig_valid_until_20 = hex_literal::hex!("1112034f0479f1ea9e817ce496da0fea263efa7fc618c52e2568437d2d347b60101682367e057ce434c0ab8b02e82ffe5ae85690015c7a406289314e20735b0f");
+
+    // current epoch is 20
+    mb_setup
+        .call_claim_rewards_with_expiry(&first_user_addr, 1, 25_000, 0, 19, &sig_valid_until_19)
+        .assert_user_error("Signature expired");
+
+    // the expiration epoch is part of the signed data
+    mb_setup
+        .call_claim_rewards_with_expiry(&first_user_addr, 1, 25_000, 0, 20, &sig_valid_until_19)
+        .assert_user_error("Invalid signature");
+    mb_setup
+        .call_claim_rewards_with_expiry(&first_user_addr, 1, 25_000, 0, 20, &sig_valid_until_20)
+        .assert_ok();
+}
+
+#[test]
+fn signed_message_max_len_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let user_addr = mb_setup.first_user_addr.clone();
+
+    // every message, with all amounts at their largest length, fits the verification buffer
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let user = managed_address!(&user_addr);
+            let max_amount = BigUint::from_bytes_be(&[0xff; 32]);
+            let mut message_lens = vec![
+                sc.build_claim_message(u64::MAX, 1, &user, &max_amount, &max_amount, u64::MAX)
+                    .as_buffer()
+                    .len(),
+                sc.build_relayed_claim_message(u64::MAX, 1, &user, &max_amount, &max_amount)
+                    .as_buffer()
+                    .len(),
+                sc.build_user_energy_message(u64::MAX, 1, &user, &max_amount, u64::MAX)
+                    .as_buffer()
+                    .len(),
+                sc.build_checkpoint_update_message(1, u64::MAX, &max_amount, &max_amount)
+                    .as_buffer()
+                    .len(),
+            ];
+            for tag in [
+                CHECKPOINT_TAG,
+                KEEPER_CHECKPOINT_TAG,
+                CHECKPOINT_SQRT_SUMS_TAG,
+            ] {
+                message_lens.push(
+                    sc.build_checkpoint_message(tag, 1, &max_amount, &max_amount)
+                        .as_buffer()
+                        .len(),
+                );
+            }
+
+            assert!(message_lens
+                .iter()
+                .all(|len| *len <= SIGNED_MESSAGE_MAX_LEN));
+            assert_eq!(message_lens.iter().max(), Some(&SIGNED_MESSAGE_MAX_LEN));
+        })
+        .assert_ok();
+}
+
+#[test]
+fn claim_rewards_multiple_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2_nonce_0 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+
+    // claim first two weeks user 1 ok, with both signatures given for the same nonce
+    mb_setup
+        .call_claim_rewards_multiple(
+            &first_user_addr,
+            &[
+                (1, 25_000, 0, &sig_first_user_week_1),
+                (2, 25_000, 0, &sig_first_user_week_2_nonce_0),
+            ],
+        )
+        .assert_ok();
+
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333 + 41_666_666),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+
+    // the nonce is only incremented once for the whole claim
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(sc.claim_nonce(&managed_address!(&first_user_addr)).get(), 1);
+        })
+        .assert_ok();
+
+    // try claim week 1 again
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Already claimed rewards for this week");
+
+    // try claim week 2 again
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Already claimed rewards for this week");
+}
+
+#[test]
+fn project_claim_stats_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2_nonce_0 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+    mb_setup
+        .call_claim_rewards_multiple(
+            &first_user_addr,
+            &[
+                (1, 25_000, 0, &sig_first_user_week_1),
+                (2, 25_000, 0, &sig_first_user_week_2_nonce_0),
+            ],
+        )
+        .assert_ok();
+
+    // the same user claiming twice is counted once as a claimer
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let project_id = managed_buffer!(FIRST_PROJ_ID);
+            assert_eq!(
+                sc.total_claimed_project_rewards(&project_id).get(),
+                managed_biguint!(83_333_333 + 41_666_666)
+            );
+            assert_eq!(sc.project_unique_claimers(&project_id).get(), 1);
+            assert_eq!(sc.project_week_claims(&project_id, 1).get(), 1);
+            assert_eq!(sc.project_week_claims(&project_id, 2).get(), 1);
+            assert_eq!(sc.project_week_claims(&project_id, 3).get(), 0);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn user_stats_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+
+    // no claims yet
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let stats = sc.get_user_stats(managed_address!(&first_user_addr));
+            assert_eq!(stats.nr_weeks_claimed, 0);
+            assert!(stats.total_claimed.is_empty());
+        })
+        .assert_ok();
+
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let stats = sc.get_user_stats(managed_address!(&first_user_addr));
+            assert_eq!(stats.nr_weeks_claimed, 2);
+            assert_eq!(stats.first_claim_week, 1);
+            assert_eq!(stats.last_claim_week, 2);
+            assert_eq!(stats.total_claimed.len(), 2);
+
+            let first_token_total = stats.total_claimed.get(0);
+            assert_eq!(
+                first_token_total.token_identifier,
+                managed_token_id!(FIRST_PROJ_TOKEN)
+            );
+            assert_eq!(
+                first_token_total.amount,
+                managed_biguint!(83_333_333 + 41_666_666)
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn get_rewards_for_weeks_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    // same as the payments of the multiple weeks claim
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let rewards =
+        mb_setup.get_rewards_for_weeks(&first_user_addr, &[(1, 25_000, 0), (2, 25_000, 0)]);
+    assert_eq!(
+        rewards,
+        vec![
+            (FIRST_PROJ_TOKEN.to_vec(), 83_333_333 + 41_666_666),
+            (SECOND_PROJ_TOKEN.to_vec(), 50_000_000)
+        ]
+    );
+
+    // the simulation saves nothing, so the claim still gets the full rewards
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333),
+    );
+
+    // claimed weeks are skipped
+    let rewards =
+        mb_setup.get_rewards_for_weeks(&first_user_addr, &[(1, 25_000, 0), (2, 25_000, 0)]);
+    assert_eq!(rewards[0], (FIRST_PROJ_TOKEN.to_vec(), 41_666_666));
+}
+
+#[test]
+fn claim_rewards_same_token_merged_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+
+    // third project rewards the same token as the first one
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup.b_mock.set_esdt_balance(
+        &first_proj_owner,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS),
+    );
+    mb_setup
+        .call_add_project(
+            b"ThirdProj",
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+            1,
+            3,
+            0,
+        )
+        .assert_ok();
+    mb_setup
+        .call_deposit_rewards(
+            &first_proj_owner,
+            b"ThirdProj",
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+        )
+        .assert_ok();
+
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+
+    mb_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &mb_setup.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut args = MultiValueEncoded::new();
+                args.push(
+                    (
+                        1,
+                        managed_biguint!(25_000),
+                        managed_biguint!(0),
+                        (&sig_first_user_week_1).into(),
+                    )
+                        .into(),
+                );
+
+                let payments = sc.claim_rewards(managed_address!(&first_user_addr), args);
+                assert_eq!(payments.len(), 1);
+
+                let payment = payments.get(0);
+                assert_eq!(
+                    payment.token_identifier,
+                    managed_token_id!(FIRST_PROJ_TOKEN)
+                );
+                assert_eq!(payment.amount, managed_biguint!(83_333_333 * 2));
+            },
+        )
+        .assert_ok();
+
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333 * 2),
+    );
+}
+
+#[test]
+fn claim_rewards_for_projects_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_2 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+    // signatures are only valid for the user's current claim nonce
+    let sig_first_user_week_2_next_nonce = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+
+    // the second project starts in week 2
+    mb_setup
+        .call_claim_rewards_for_projects(
+            &first_user_addr,
+            1,
+            25_000,
+            0,
+            &sig_first_user_week_1,
+            &[SECOND_PROJ_ID],
+        )
+        .assert_user_error("Project not active in the given week");
+
+    // claim only the second project
+    mb_setup
+        .call_claim_rewards_for_projects(
+            &first_user_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2,
+            &[SECOND_PROJ_ID],
+        )
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+
+    // try claim second project again
+    mb_setup
+        .call_claim_rewards_for_projects(
+            &first_user_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2_next_nonce,
+            &[SECOND_PROJ_ID],
+        )
+        .assert_user_error("Already claimed rewards for this project");
+
+    // claim the rest of the week
+    mb_setup
+        .call_claim_rewards(
+            &first_user_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2_next_nonce,
+        )
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(41_666_666),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+
+    mb_setup
+        .call_claim_rewards_for_projects(
+            &first_user_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2,
+            &[FIRST_PROJ_ID],
+        )
+        .assert_user_error("Already claimed rewards for this week");
+}
+
+#[test]
+fn claim_rewards_batch_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_2_nonce_0 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+    let sig_first_user_week_2_nonce_1 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+    let sig_first_user_week_2_nonce_2 = hex_literal::hex!("dba5563fd63f9352f7fc1f1f79d0cd78db42ee5bef2014224276c09e54e931f9b1f07a2f8a60a0a479fba962eef433c48da10ab5baaccf48c50096f0f62f5901");
+
+    mb_setup
+        .call_set_max_projects_per_claim(0)
+        .assert_user_error("Invalid max projects per claim");
+    mb_setup.call_set_max_projects_per_claim(1).assert_ok();
+
+    // only the first project is claimed
+    assert_eq!(
+        mb_setup.call_claim_rewards_batch(
+            &first_user_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2_nonce_0
+        ),
+        (true, 1)
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(41_666_666),
+    );
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, SECOND_PROJ_TOKEN, &rust_biguint!(0));
+    assert_eq!(
+        mb_setup.get_user_claimable_weeks(&first_user_addr),
+        &[1usize, 2usize]
+    );
+
+    // the rest of the week is claimed
+    assert_eq!(
+        mb_setup.call_claim_rewards_batch(
+            &first_user_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2_nonce_1
+        ),
+        (false, 0)
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(41_666_666),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+    assert_eq!(
+        mb_setup.get_user_claimable_weeks(&first_user_addr),
+        &[1usize]
+    );
+
+    mb_setup
+        .call_claim_rewards(
+            &first_user_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2_nonce_2,
+        )
+        .assert_user_error("Already claimed rewards for this week");
+}
+
+#[test]
+fn claim_through_proxy_then_direct_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let proxy_addr = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+
+    mb_setup
+        .call_add_sc_address_to_whitelist(&proxy_addr)
+        .assert_ok();
+
+    // proxy claims for user, rewards are sent to the proxy
+    mb_setup
+        .call_claim_rewards_for_user(
+            &proxy_addr,
+            &first_user_addr,
+            1,
+            25_000,
+            0,
+            &sig_first_user_week_1,
+        )
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&proxy_addr, FIRST_PROJ_TOKEN, &rust_biguint!(83_333_333));
+
+    // user try claim same week directly
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Already claimed rewards for this week");
+}
+
+#[test]
+fn reward_destination_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let hot_wallet_addr = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+
+    mb_setup
+        .call_set_reward_destination(&first_user_addr, &hot_wallet_addr)
+        .assert_ok();
+
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &hot_wallet_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333),
+    );
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+
+    // remove destination by setting own address
+    mb_setup
+        .call_set_reward_destination(&first_user_addr, &first_user_addr)
+        .assert_ok();
+
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(41_666_666),
+    );
+}
+
+#[test]
+fn claim_and_compound_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let farm_token = b"FARM-123456";
+    let owner_addr = mb_setup.owner_addr.clone();
+    let farm_wrapper = mb_setup.b_mock.create_sc_account(
+        &rust_biguint!(0),
+        Some(&owner_addr),
+        farm_mock::contract_obj,
+        "farm_mock.wasm",
+    );
+    let farm_addr = farm_wrapper.address_ref().clone();
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &farm_wrapper, &rust_biguint!(0), |sc| {
+            sc.init(managed_token_id!(farm_token));
+        })
+        .assert_ok();
+    mb_setup.b_mock.set_nft_balance(
+        &farm_addr,
+        farm_token,
+        1,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS),
+        &(),
+    );
+
+    let hot_wallet_addr = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+
+    mb_setup
+        .call_claim_and_compound(
+            &first_user_addr,
+            &farm_addr,
+            1,
+            25_000,
+            0,
+            &sig_first_user_week_1,
+        )
+        .assert_user_error("Farm not whitelisted");
+    mb_setup
+        .call_add_compound_farm(&farm_addr, FIRST_PROJ_TOKEN)
+        .assert_ok();
+
+    // the farm position is sent to, and entered on behalf of, the reward destination
+    mb_setup
+        .call_set_reward_destination(&first_user_addr, &hot_wallet_addr)
+        .assert_ok();
+    mb_setup
+        .call_claim_and_compound(
+            &first_user_addr,
+            &farm_addr,
+            1,
+            25_000,
+            0,
+            &sig_first_user_week_1,
+        )
+        .assert_ok();
+    mb_setup.b_mock.check_nft_balance(
+        &hot_wallet_addr,
+        farm_token,
+        1,
+        &rust_biguint!(83_333_333),
+        Option::<&()>::None,
+    );
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&farm_addr, FIRST_PROJ_TOKEN, &rust_biguint!(83_333_333));
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+    mb_setup
+        .b_mock
+        .execute_query(&farm_wrapper, |sc| {
+            assert_eq!(
+                sc.last_orig_caller().get(),
+                managed_address!(&hot_wallet_addr)
+            );
+        })
+        .assert_ok();
+
+    // only the farming token is compounded, the other rewards are sent as they are
+    mb_setup
+        .call_set_reward_destination(&first_user_addr, &first_user_addr)
+        .assert_ok();
+    mb_setup
+        .call_claim_and_compound(
+            &first_user_addr,
+            &farm_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2,
+        )
+        .assert_ok();
+    mb_setup.b_mock.check_nft_balance(
+        &first_user_addr,
+        farm_token,
+        1,
+        &rust_biguint!(41_666_666),
+        Option::<&()>::None,
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &farm_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333 + 41_666_666),
+    );
+}
+
+#[test]
+fn claim_delegate_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let delegate_addr = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+
+    // try claim before being set as delegate
+    mb_setup
+        .call_claim_for_user(
+            &delegate_addr,
+            &first_user_addr,
+            1,
+            25_000,
+            0,
+            &sig_first_user_week_1,
+        )
+        .assert_user_error("Not the claim delegate for this user");
+
+    mb_setup
+        .call_set_claim_delegate(&first_user_addr, &delegate_addr)
+        .assert_ok();
+
+    // delegate claims, rewards go to the user
+    mb_setup
+        .call_claim_for_user(
+            &delegate_addr,
+            &first_user_addr,
+            1,
+            25_000,
+            0,
+            &sig_first_user_week_1,
+        )
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333),
+    );
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&delegate_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+
+    // user try claim same week
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Already claimed rewards for this week");
+}
+
+#[test]
+fn claim_pending_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2 = hex_literal::hex!("9a37e93f5bec7b252ade5b1618824025cf5933b0bc5fe87b5e5a80084864b7dd493bffd30bd4a75f65d0982597d0cb03d5b96624d5240994fcd64c3db5b76805");
+    let all_args = [
+        (1, 25_000, 0, &sig_first_user_week_1),
+        (2, 25_000, 0, &sig_first_user_week_2),
+    ];
+
+    // only process one week
+    mb_setup
+        .call_claim_pending(&first_user_addr, 1, &all_args)
+        .assert_ok();
+    assert_eq!(mb_setup.get_claim_cursor(&first_user_addr), 2);
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333),
+    );
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, SECOND_PROJ_TOKEN, &rust_biguint!(0));
+
+    // resume with the same arguments
+    mb_setup
+        .call_claim_pending(&first_user_addr, 1, &all_args)
+        .assert_ok();
+    assert_eq!(mb_setup.get_claim_cursor(&first_user_addr), 3);
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333 + 41_666_666),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+
+    // nothing left to claim
+    mb_setup
+        .call_claim_pending(&first_user_addr, 2, &all_args)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333 + 41_666_666),
+    );
+}
+
+#[test]
+fn claim_pending_skipped_week_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    // no checkpoint is added for week 3
+    mb_setup.set_current_epoch(33);
+    mb_setup
+        .call_add_rewards_checkpoint(4, 200_000, 0)
+        .assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_4 = hex_literal::hex!("98c989d61fcdd9c672c888b9d6c270c5ed890635b3461e6f47124caded1d02070630d3816622b1e87c6bda09f60747f8955a3c72e2423ed3ede5046fc97c1802");
+    let invalid_sig = [0u8; 64];
+    let all_args = [
+        (1, 25_000, 0, &sig_first_user_week_1),
+        (3, 25_000, 0, &invalid_sig),
+        (4, 25_000, 0, &sig_first_user_week_4),
+        (5, 25_000, 0, &invalid_sig),
+    ];
+
+    // week 3 is skipped without being validated, and week 5 is left for a later call
+    mb_setup
+        .call_claim_pending(&first_user_addr, 4, &all_args)
+        .assert_ok();
+    assert_eq!(mb_setup.get_claim_cursor(&first_user_addr), 5);
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+}
+
+#[test]
+fn relayed_claim_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    // public key of 4246c6a03ec5423c14986b61cecc08a4822ea380e5f664dd43e51a618765cfd6
+    let user_addr = Address::from(hex_literal::hex!(
+        "64195c4ead809f61d2269421cc32e897b3690a889979a1a23d1a421d97d7f00c"
+    ));
+    mb_setup
+        .b_mock
+        .create_user_account_fixed_address(&user_addr, &rust_biguint!(0));
+    let relayer_addr = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+
+    let sig_user_week_1 = hex_literal::hex!("138561e8f97f26ae890e51c297c3c8295a002f6af14e9ce84342afea17d19bf053bd38f67b2dcfdd0c04cd13b5c41d8c1bc7f6fae5cd6fae1c384cabae8b570b");
+    let user_intent_nonce_0 = hex_literal::hex!("e76a5257815d81faa9ec1a7458bf0e315dce333c5c72c07bef660b46c6e7efdd7fbf32e4b00b77c230079654afde1c687c387a4ea60304a6aa20ecc1cbb99006");
+
+    // try relay with the signer's signature as user signature
+    mb_setup
+        .call_relayed_claim(
+            &relayer_addr,
+            &user_addr,
+            1,
+            25_000,
+            0,
+            &sig_user_week_1,
+            &sig_user_week_1,
+        )
+        .assert_user_error("Invalid user signature");
+
+    mb_setup
+        .call_relayed_claim(
+            &relayer_addr,
+            &user_addr,
+            1,
+            25_000,
+            0,
+            &sig_user_week_1,
+            &user_intent_nonce_0,
+        )
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(83_333_333));
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&relayer_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+
+    // try replay the same intent
+    mb_setup
+        .call_relayed_claim(
+            &relayer_addr,
+            &user_addr,
+            1,
+            25_000,
+            0,
+            &sig_user_week_1,
+            &user_intent_nonce_0,
+        )
+        .assert_user_error("Invalid user signature");
+}
+
+#[test]
+fn week_data_hash_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_checkpoints();
+
+    let data_hash = [7u8; 32];
+    mb_setup
+        .call_set_week_data_hash(3, &data_hash)
+        .assert_user_error("No checkpoint for week");
+    mb_setup.call_set_week_data_hash(1, &data_hash).assert_ok();
+    mb_setup
+        .call_set_week_data_hash(1, &[8u8; 32])
+        .assert_user_error("Week data hash already set");
+
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(sc.week_data_hash(1).get().to_byte_array(), data_hash);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn claim_with_proof_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+
+    // leaves for (week 1, user1, 25_000, 0) and (week 1, user2, 50_000, 0)
+    let first_user_leaf =
+        hex_literal::hex!("ab93bc0b4d32fee93de9ca005c9e1eee5365144ce7bfa0e38ba346a2eb11659c");
+    let second_user_leaf =
+        hex_literal::hex!("8d4cd51f75fcab9f660325715ff28511b9e52fa6ca4ad536c330dabdb9dd92f3");
+    let root =
+        hex_literal::hex!("e0dc4d85a9db854d4c3c0a30054e4ed38635d62da288251d494707d2bc5ebcec");
+
+    // try claim before root is set
+    mb_setup
+        .call_claim_with_proof(&first_user_addr, 1, 25_000, 0, &[second_user_leaf])
+        .assert_user_error("No merkle root for week");
+
+    // only the owner may set the root
+    mb_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &mb_setup.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_week_merkle_root(1, (&root).into());
+            },
+        )
+        .assert_user_error("Endpoint can only be called by owner");
+
+    mb_setup.call_set_week_merkle_root(1, &root).assert_ok();
+
+    // try claim wrong amount
+    mb_setup
+        .call_claim_with_proof(&second_user_addr, 1, 60_000, 0, &[first_user_leaf])
+        .assert_user_error("Invalid merkle proof");
+
+    mb_setup
+        .call_claim_with_proof(&first_user_addr, 1, 25_000, 0, &[second_user_leaf])
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_333),
+    );
+
+    mb_setup
+        .call_claim_with_proof(&second_user_addr, 1, 50_000, 0, &[first_user_leaf])
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &second_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(166_666_666),
+    );
+
+    // try claim again, with signature this time
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Already claimed rewards for this week");
+}
+
+#[test]
+fn claim_events_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_second_user_week_1 = hex_literal::hex!("6056836289c61837308d014ab372a90f7665238bcb85dd1d6c9cad1feff7be9516d5348b7e27f8c74036a4f75d576bccca630611545179399202e268f1388103");
+
+    // paid rewards emit a claim event
+    let tx_result =
+        mb_setup.call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1);
+    tx_result.assert_ok();
+    let event_identifiers = get_event_identifiers(&tx_result);
+    assert!(event_identifiers.contains(&b"claim".to_vec()));
+    assert!(!event_identifiers.contains(&b"rewardsParked".to_vec()));
+    assert!(event_identifiers.contains(&b"claimFinished".to_vec()));
+
+    // rewards kept for the user emit a rewardsParked event instead
+    mb_setup
+        .call_pause_project_claims(FIRST_PROJ_ID)
+        .assert_ok();
+    let tx_result =
+        mb_setup.call_claim_rewards(&second_user_addr, 1, 50_000, 0, &sig_second_user_week_1);
+    tx_result.assert_ok();
+    let event_identifiers = get_event_identifiers(&tx_result);
+    assert!(!event_identifiers.contains(&b"claim".to_vec()));
+    assert!(event_identifiers.contains(&b"rewardsParked".to_vec()));
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&second_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+}
+
+#[test]
+fn pause_project_claims_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_2 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+
+    mb_setup
+        .call_pause_project_claims(SECOND_PROJ_ID)
+        .assert_ok();
+
+    // paused project is not shown
+    let rewards_week_2 = mb_setup.get_pretty_rewards(2, 25_000, 0);
+    assert_eq!(
+        rewards_week_2,
+        &[(
+            b"FirstProj".to_vec(),
+            b"PROJ-123456".to_vec(),
+            41_666_666u64
+        )]
+    );
+
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(41_666_666),
+    );
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, SECOND_PROJ_TOKEN, &rust_biguint!(0));
+
+    // try claim kept rewards while still paused
+    mb_setup
+        .call_claim_paused_project_rewards(&first_user_addr, &[SECOND_PROJ_ID])
+        .assert_user_error("Project claims are paused");
+
+    // the kept rewards can't be lost by removing the project
+    mb_setup
+        .call_remove_project(SECOND_PROJ_ID)
+        .assert_user_error("Project has paused rewards");
+
+    // nor withdrawn, since they're still counted as liabilities
+    mb_setup
+        .call_emergency_withdraw(SECOND_PROJ_TOKEN, 0, 1)
+        .assert_user_error("Amount exceeds the withdrawable balance");
+
+    mb_setup
+        .call_resume_project_claims(SECOND_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .call_claim_paused_project_rewards(&first_user_addr, &[SECOND_PROJ_ID])
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
+
+    mb_setup.call_remove_project(SECOND_PROJ_ID).assert_ok();
+}
+
+#[test]
+fn resume_project_claims_permissions_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+
+    // claims paused by the owner can't be resumed by the project owner
+    mb_setup
+        .call_pause_project_claims(FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .call_pause_project_claims_as(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_user_error("Project claims already paused");
+    mb_setup
+        .call_resume_project_claims_as(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_user_error("Only the pauser or the owner may resume claims");
+    mb_setup
+        .call_resume_project_claims(FIRST_PROJ_ID)
+        .assert_ok();
+
+    // the project owner may resume the claims they paused
+    mb_setup
+        .call_pause_project_claims_as(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(
+                sc.project_claims_paused_by(&managed_buffer!(FIRST_PROJ_ID))
+                    .get(),
+                managed_address!(&first_proj_owner)
+            );
+        })
+        .assert_ok();
+    mb_setup
+        .call_resume_project_claims_as(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_ok();
+
+    // and the owner may resume any pause
+    mb_setup
+        .call_pause_project_claims_as(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .call_resume_project_claims(FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert!(!sc
+                .project_claims_paused(&managed_buffer!(FIRST_PROJ_ID))
+                .get());
+            assert!(sc
+                .project_claims_paused_by(&managed_buffer!(FIRST_PROJ_ID))
+                .is_empty());
+        })
+        .assert_ok();
+}
+
+#[test]
+fn claimed_weeks_bitmap_migration_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let owner_addr = mb_setup.owner_addr.clone();
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    let sig_first_user_week_2 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+
+    // simulate a week claimed before the bitmap was introduced
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.rewards_claimed(&managed_address!(&first_user_addr), 1)
+                .set(true);
+        })
+        .assert_ok();
+
+    // legacy flag is still taken into account
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Already claimed rewards for this week");
+    assert!(mb_setup.get_claimed_weeks(&first_user_addr).is_empty());
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert!(sc.get_rewards_claimed(managed_address!(&first_user_addr), 1));
+            assert!(!sc.get_rewards_claimed(managed_address!(&first_user_addr), 2));
+        })
+        .assert_ok();
+
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            let mut users = MultiValueEncoded::new();
+            users.push(managed_address!(&first_user_addr));
+            sc.migrate_claimed_weeks(2, users);
+
+            assert!(sc
+                .rewards_claimed(&managed_address!(&first_user_addr), 1)
+                .is_empty());
+        })
+        .assert_ok();
+    assert_eq!(mb_setup.get_claimed_weeks(&first_user_addr), &[1usize]);
+
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Already claimed rewards for this week");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+    assert_eq!(
+        mb_setup.get_claimed_weeks(&first_user_addr),
+        &[1usize, 2usize]
+    );
+
+    // weeks 1 to 10, week 1 being the first bit
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let statuses = sc.get_claim_statuses(managed_address!(&first_user_addr), 1, 10);
+            assert_eq!(statuses, managed_buffer!(&[0b0000_0011, 0]));
+        })
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let _ = sc.get_claim_statuses(managed_address!(&first_user_addr), 2, 1);
+        })
+        .assert_user_error("Invalid week range");
+}
+
+#[test]
+fn grace_period_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let owner_addr = mb_setup.owner_addr.clone();
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+
+    // week 1 can be claimed until the end of week 5, i.e. epoch 5 + 6 * 7 - 1
+    assert_eq!(mb_setup.get_claim_deadline(1), (5, 46));
+
+    // set current week = 6
+    mb_setup.b_mock.set_block_epoch(50);
+
+    // get claimable weeks - only able to claim week 2
+    let claimable_weeks = mb_setup.get_user_claimable_weeks(&first_user_addr);
+    assert_eq!(claimable_weeks, &[2usize]);
+
+    // set grace period of 5 weeks,
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.rewards_nr_first_grace_weeks().set(5);
+        })
+        .assert_ok();
+
+    // get claimable weeks - user still can only claim for week 2
+    let claimable_weeks = mb_setup.get_user_claimable_weeks(&first_user_addr);
+    assert_eq!(claimable_weeks, &[2usize]);
+
+    // user try claim week 1
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Claiming too late");
+
+    // set grace weeks to 6
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_rewards_nr_first_grace_weeks(6);
+        })
+        .assert_ok();
+    assert_eq!(mb_setup.get_claim_deadline(1), (6, 53));
+
+    // get claimable weeks - user can now claim for week 1 and 2
+    let claimable_weeks = mb_setup.get_user_claimable_weeks(&first_user_addr);
+    assert_eq!(claimable_weeks, &[1usize, 2usize]);
+
+    // user claim week 1 ok
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+}
+
+#[test]
+fn sweep_expired_rewards_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+
+    // try sweep without treasury
+    mb_setup
+        .call_sweep_expired_rewards(FIRST_PROJ_ID)
+        .assert_user_error("Treasury address not set");
+
+    let treasury_addr = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    mb_setup
+        .call_set_treasury_address(&treasury_addr)
+        .assert_ok();
+
+    // week 1 not expired yet
+    mb_setup
+        .call_sweep_expired_rewards(FIRST_PROJ_ID)
+        .assert_user_error("No expired weeks to sweep");
+
+    // set current week = 6, week 1 is expired
+    mb_setup.set_current_epoch(47);
+    mb_setup
+        .call_sweep_expired_rewards(FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &treasury_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(333_333_333 - 83_333_333),
+    );
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(
+                sc.get_remaining_rewards(managed_buffer!(FIRST_PROJ_ID))
+                    .to_vec()
+                    .get(0)
+                    .amount,
+                managed_biguint!(TOTAL_FIRST_PROJ_TOKENS - 333_333_333)
+            );
+        })
+        .assert_ok();
+
+    // second project starts in week 2
+    mb_setup
+        .call_sweep_expired_rewards(SECOND_PROJ_ID)
+        .assert_user_error("No expired weeks to sweep");
+
+    // the expiration can't be shortened
+    mb_setup
+        .call_set_claim_expiration_weeks(2)
+        .assert_user_error("Expiration weeks can only be increased");
+
+    // set current week = 8, weeks 2 and 3 also expire
+    mb_setup.set_current_epoch(61);
+    mb_setup
+        .call_sweep_expired_rewards(FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &treasury_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(333_333_333 - 83_333_333 + 2 * 333_333_333),
+    );
+
+    // project fully swept
+    mb_setup
+        .call_sweep_expired_rewards(FIRST_PROJ_ID)
+        .assert_user_error("No expired weeks to sweep");
+
+    // only the rounding leftover remains
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(
+                sc.get_remaining_rewards(managed_buffer!(FIRST_PROJ_ID))
+                    .to_vec()
+                    .get(0)
+                    .amount,
+                managed_biguint!(1)
+            );
+        })
+        .assert_ok();
+
+    // a longer expiration doesn't re-open the swept weeks
+    mb_setup.call_set_claim_expiration_weeks(10).assert_ok();
+    let second_user_addr = mb_setup.second_user_addr.clone();
+    let sig_second_user_week_1 = hex_literal::hex!("6056836289c61837308d014ab372a90f7665238bcb85dd1d6c9cad1feff7be9516d5348b7e27f8c74036a4f75d576bccca630611545179399202e268f1388103");
+    mb_setup
+        .call_claim_rewards(&second_user_addr, 1, 50_000, 0, &sig_second_user_week_1)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&second_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+}
+
+#[test]
+fn project_with_split_rewards_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.call_unpause().assert_ok();
+
+    // 25% goes to LKMEX holders, 75% to delegation
+    mb_setup
+        .call_add_project(
+            FIRST_PROJ_ID,
+            &mb_setup.first_project_owner.clone(),
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+            1,
+            3,
+            2_500,
+        )
+        .assert_ok();
+
+    mb_setup
+        .call_deposit_rewards(
+            &mb_setup.first_project_owner.clone(),
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+        )
+        .assert_ok();
+
+    mb_setup.b_mock.set_block_epoch(15);
+
+    mb_setup
+        .call_add_rewards_checkpoint(1, 100_000, 200_000)
+        .assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("b0f83362f3bfe76ccff9d637daaef286ae381a6de811ebff7853731c6a2bd32683e5335e2f4f34f0c6576ed70495613991541b1009fffe229eac77fb6feb9009");
+
+    // (1/4 * 750,000,000 + 1/2 * 250,000,000) / 3 ~= 104,166,666
+    let expected_rewards_amount = 104_166_666u64;
+    let actual_rewards_amount = mb_setup.get_pretty_rewards(1, 25_000, 100_000)[0].2;
+    assert_eq!(expected_rewards_amount, actual_rewards_amount);
+
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 100_000, &sig_first_user_week_1)
+        .assert_ok();
+
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(expected_rewards_amount),
+    );
+}
+
+#[test]
+fn set_project_reward_split_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    mb_setup
+        .call_set_project_reward_split(FIRST_PROJ_ID, 10_001)
+        .assert_user_error("Invalid split");
+
+    // 25% of the 333_333_333 weekly supply goes to LKMEX holders
+    mb_setup
+        .call_set_project_reward_split(FIRST_PROJ_ID, 2_500)
+        .assert_ok();
+    let (_, delegation_supply, lkmex_supply, _, _) = mb_setup.get_project_by_id(FIRST_PROJ_ID);
+    assert_eq!(delegation_supply, 250_000_000 * 3);
+    assert_eq!(lkmex_supply, 83_333_333 * 3);
+
+    // the distributable supply is unchanged, so the regular deposit still works
+    mb_setup
+        .call_deposit_rewards(
+            &mb_setup.first_project_owner.clone(),
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+        )
+        .assert_ok();
+
+    // week 1 - project started
+    mb_setup.b_mock.set_block_epoch(12);
+    mb_setup
+        .call_set_project_reward_split(FIRST_PROJ_ID, 5_000)
+        .assert_user_error("Project already started");
+}
+
+#[test]
+fn add_project_percentage_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+
+    mb_setup
+        .call_add_project(
+            b"ThirdProj",
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            1,
+            2,
+            101,
+        )
+        .assert_user_error("Invalid percentage");
+
+    // the split is still given as a percentage
+    mb_setup
+        .call_add_project(
+            b"ThirdProj",
+            &first_proj_owner,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            1,
+            2,
+            40,
+        )
+        .assert_ok();
+    let (_, delegation_supply, lkmex_supply, _, _) = mb_setup.get_project_by_id(b"ThirdProj");
+    assert_eq!(delegation_supply, 600);
+    assert_eq!(lkmex_supply, 400);
+}
+
+#[test]
+fn project_emission_curve_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    mb_setup
+        .call_set_project_emission_curve(FIRST_PROJ_ID, &[5_000, 5_000])
+        .assert_user_error("Invalid number of weights");
+    mb_setup
+        .call_set_project_emission_curve(FIRST_PROJ_ID, &[5_000, 3_000, 1_000])
+        .assert_user_error("Invalid weights sum");
+
+    // 50% of the supply in the first week, 30% in the second, 20% in the third
+    mb_setup
+        .call_set_project_emission_curve(FIRST_PROJ_ID, &[5_000, 3_000, 2_000])
+        .assert_ok();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    mb_setup
+        .call_set_project_emission_curve(FIRST_PROJ_ID, &[])
+        .assert_user_error("Rewards already deposited");
+    mb_setup
+        .call_extend_project(FIRST_PROJ_ID, 4)
+        .assert_user_error("Project has an emission curve");
+
+    // 500_000_000 * 25_000 / 100_000
+    assert_eq!(
+        mb_setup.get_pretty_rewards(1, 25_000, 0),
+        &[(
+            FIRST_PROJ_ID.to_vec(),
+            FIRST_PROJ_TOKEN.to_vec(),
+            125_000_000u64
+        )]
+    );
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(125_000_000),
+    );
+}
+
+#[test]
+fn project_edge_offsets_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+
+    mb_setup
+        .call_set_project_edge_offsets(FIRST_PROJ_ID, 7, 0)
+        .assert_user_error("Invalid offsets");
+
+    // starts 3 epochs into week 1 and ends 5 epochs before the end of week 3
+    mb_setup
+        .call_set_project_edge_offsets(FIRST_PROJ_ID, 3, 5)
+        .assert_ok();
+
+    // 333_333_333 * 4/7 + 333_333_333 + 333_333_333 * 2/7 are distributed, the rest is refunded
+    mb_setup.deposit_rewards_default_projects();
+    let distributable_supply = 190_476_190 + 333_333_333 + 95_238_095;
+    mb_setup.b_mock.check_esdt_balance(
+        &mb_setup.first_project_owner.clone(),
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS - distributable_supply),
+    );
+
+    mb_setup.add_default_checkpoints();
+    mb_setup
+        .call_extend_project(FIRST_PROJ_ID, 4)
+        .assert_user_error("Project has edge offsets");
+
+    // 190_476_190 * 25_000 / 100_000
+    assert_eq!(
+        mb_setup.get_pretty_rewards(1, 25_000, 0),
+        &[(
+            FIRST_PROJ_ID.to_vec(),
+            FIRST_PROJ_TOKEN.to_vec(),
+            47_619_047u64
+        )]
+    );
+}
+
+#[test]
+fn add_project_with_start_week_in_the_past_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.call_unpause().assert_ok();
+
+    // set current week in the future - week 5
+    mb_setup.b_mock.set_block_epoch(41);
+
+    mb_setup
+        .call_add_project(
+            FIRST_PROJ_ID,
+            &mb_setup.first_project_owner.clone(),
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+            1,
+            7,
+            0,
+        )
+        .assert_ok();
+
+    mb_setup
+        .call_deposit_rewards(
+            &mb_setup.first_project_owner.clone(),
+            FIRST_PROJ_ID,
+            FIRST_PROJ_TOKEN,
+            TOTAL_FIRST_PROJ_TOKENS,
+        )
+        .assert_ok();
+
+    mb_setup
+        .call_add_rewards_checkpoint(1, 100_000, 0)
+        .assert_ok();
+
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(35_714_285),
+    );
+}
+
+#[test]
+fn clear_project_funds_not_deposited_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.call_unpause().assert_ok();
+    mb_setup.add_default_projects();
+
+    // get project IDs before
+    let proj_ids = mb_setup.get_all_project_ids();
+    assert_eq!(
+        proj_ids,
+        vec![FIRST_PROJ_ID.to_vec(), SECOND_PROJ_ID.to_vec(),]
+    );
+
+    // set current week - week 9 - first project expires
+    mb_setup.b_mock.set_block_epoch(68);
+
+    mb_setup.call_clear_expired_projects().assert_ok();
+
+    // get project IDs after
+    let proj_ids = mb_setup.get_all_project_ids();
+    assert_eq!(proj_ids, vec![SECOND_PROJ_ID.to_vec(),]);
+}
+
+#[test]
+fn admin_permissions_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.set_current_epoch(20);
+
+    let admin = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    let not_admin = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+
+    // only the owner may add admins
+    mb_setup
+        .b_mock
+        .execute_tx(&admin, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            let mut args = MultiValueEncoded::new();
+            args.push(managed_address!(&admin));
+            sc.add_admins(args);
+        })
+        .assert_user_error("Endpoint can only be called by owner");
+    mb_setup.call_add_admins(&[&admin]).assert_ok();
+
+    // admins may add checkpoints and manage projects
+    mb_setup
+        .b_mock
+        .execute_tx(&admin, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.add_rewards_checkpoint(
+                1,
+                managed_biguint!(100_000),
+                managed_biguint!(0),
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_tx(&admin, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.remove_project(managed_buffer!(SECOND_PROJ_ID));
+        })
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_tx(&not_admin, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.remove_project(managed_buffer!(FIRST_PROJ_ID));
+        })
+        .assert_user_error("Caller does not have the required role");
+
+    // signers and fees stay owner-only
+    mb_setup
+        .b_mock
+        .execute_tx(&admin, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.add_signer(managed_address!(&admin));
+        })
+        .assert_user_error("Endpoint can only be called by owner");
+    mb_setup
+        .b_mock
+        .execute_tx(&admin, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_project_claim_fee(managed_buffer!(FIRST_PROJ_ID), 100);
+        })
+        .assert_user_error("Endpoint can only be called by owner");
+
+    // removed admins lose their permissions
+    mb_setup.call_remove_admins(&[&admin]).assert_ok();
+    mb_setup
+        .b_mock
+        .execute_tx(&admin, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.remove_project(managed_buffer!(FIRST_PROJ_ID));
+        })
+        .assert_user_error("Caller does not have the required role");
+}
+
+#[test]
+fn ownership_transfer_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let owner_addr = mb_setup.owner_addr.clone();
+    let new_owner_addr = mb_setup.first_user_addr.clone();
+    let other_addr = mb_setup.second_user_addr.clone();
+
+    mb_setup
+        .call_propose_new_owner(&new_owner_addr, &other_addr)
+        .assert_user_error("Endpoint can only be called by owner");
+    mb_setup
+        .call_propose_new_owner(&owner_addr, &owner_addr)
+        .assert_user_error("Invalid new owner");
+    mb_setup
+        .call_propose_new_owner(&owner_addr, &new_owner_addr)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(sc.pending_owner().get(), managed_address!(&new_owner_addr));
+        })
+        .assert_ok();
+
+    mb_setup
+        .call_claim_ownership(&other_addr)
+        .assert_user_error("Only the pending owner may claim ownership");
+
+    // the owner hasn't handed the ownership over to the SC yet
+    mb_setup
+        .call_claim_ownership(&new_owner_addr)
+        .assert_user_error("Ownership not handed over to the SC");
+
+    mb_setup
+        .call_cancel_ownership_transfer(&new_owner_addr)
+        .assert_user_error("Only the proposer may cancel the transfer");
+    mb_setup
+        .call_cancel_ownership_transfer(&owner_addr)
+        .assert_ok();
+    mb_setup
+        .call_claim_ownership(&new_owner_addr)
+        .assert_user_error("Only the pending owner may claim ownership");
+}
+
+#[test]
+fn claim_handed_over_ownership_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    let owner_addr = mb_setup.owner_addr.clone();
+    let new_owner_addr = mb_setup.first_user_addr.clone();
+
+    // a SC holding its own ownership, as after the owner's ChangeOwnerAddress call
+    let handed_over_sc_addr = Address::from(hex_literal::hex!(
+        "0000000000000000050068616e6465645f6f7665725f5f5f5f5f5f5f5f5f5f5f"
+    ));
+    let handed_over_wrapper = mb_setup.b_mock.create_sc_account_fixed_address(
+        &handed_over_sc_addr,
+        &rust_biguint!(0),
+        Some(&handed_over_sc_addr),
+        metabonding::contract_obj,
+        "metabonding wasm path",
+    );
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &handed_over_wrapper, &rust_biguint!(0), |sc| {
+            sc.pending_owner().set(&managed_address!(&new_owner_addr));
+            sc.ownership_proposer().set(&managed_address!(&owner_addr));
+        })
+        .assert_ok();
+
+    // the proposer can no longer call the owner-only endpoints
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &handed_over_wrapper, &rust_biguint!(0), |sc| {
+            sc.propose_new_owner(managed_address!(&new_owner_addr));
+        })
+        .assert_user_error("Endpoint can only be called by owner");
+
+    mb_setup
+        .b_mock
+        .execute_tx(
+            &new_owner_addr,
+            &handed_over_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.claim_ownership();
+            },
+        )
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&handed_over_wrapper, |sc| {
+            assert_eq!(
+                sc.blockchain().get_owner_address(),
+                managed_address!(&new_owner_addr)
+            );
+            assert!(sc.pending_owner().is_empty());
+        })
+        .assert_ok();
+}
+
+#[test]
+fn roles_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.set_current_epoch(20);
+
+    let submitter = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    let pauser = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+
+    mb_setup
+        .call_grant_role(&submitter, Role::CheckpointSubmitter)
+        .assert_ok();
+    mb_setup
+        .call_grant_role(&submitter, Role::CheckpointSubmitter)
+        .assert_user_error("Role already granted");
+    mb_setup.call_grant_role(&pauser, Role::Pauser).assert_ok();
+
+    // a checkpoint submitter may add checkpoints, but not manage projects
+    mb_setup
+        .b_mock
+        .execute_tx(&submitter, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.add_rewards_checkpoint(
+                1,
+                managed_biguint!(100_000),
+                managed_biguint!(0),
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_tx(&submitter, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.remove_project(managed_buffer!(SECOND_PROJ_ID));
+        })
+        .assert_user_error("Caller does not have the required role");
+
+    // a pauser may only pause and unpause
+    mb_setup
+        .b_mock
+        .execute_tx(&pauser, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.pause_all();
+            sc.unpause_all();
+        })
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_tx(&pauser, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.add_rewards_checkpoint(
+                2,
+                managed_biguint!(200_000),
+                managed_biguint!(0),
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_user_error("Only checkpoint submitter or signer may call this function");
+    mb_setup
+        .b_mock
+        .execute_tx(&submitter, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.pause_all();
+        })
+        .assert_user_error("Caller does not have the required role");
+
+    // revoked roles are no longer accepted
+    mb_setup
+        .call_revoke_role(&submitter, Role::CheckpointSubmitter)
+        .assert_ok();
+    mb_setup
+        .call_revoke_role(&submitter, Role::CheckpointSubmitter)
+        .assert_user_error("Role not granted");
+    mb_setup
+        .b_mock
+        .execute_tx(&submitter, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.add_rewards_checkpoint(
+                2,
+                managed_biguint!(200_000),
+                managed_biguint!(0),
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_user_error("Only checkpoint submitter or signer may call this function");
+}
+
+#[test]
+fn global_pause_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.set_current_epoch(20);
+    mb_setup
+        .call_add_rewards_checkpoint(1, 100_000, 0)
+        .assert_ok();
+    mb_setup.call_unpause().assert_ok();
+
+    let pauser = mb_setup.b_mock.create_user_account(&rust_biguint!(0));
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+
+    mb_setup
+        .call_pause_all(&pauser)
+        .assert_user_error("Caller does not have the required role");
+    mb_setup.call_grant_role(&pauser, Role::Pauser).assert_ok();
+    mb_setup.call_pause_all(&pauser).assert_ok();
+
+    // claims, checkpoints and deposits are stopped
+    let sig_first_user_week_1 = hex_literal::hex!("d5166ee2d8b9c3b048db54263dba9bc88ae9307f0b79ca0a581d74b2cf815e1795f1db73d49ddbfda85ee75cdf5dd453368b0d584435efceb9eccf9e6f0f8207");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_user_error("Contract is globally paused");
+    mb_setup
+        .call_add_rewards_checkpoint(2, 200_000, 0)
+        .assert_user_error("Contract is globally paused");
+    mb_setup
+        .call_deposit_rewards(&first_proj_owner, FIRST_PROJ_ID, FIRST_PROJ_TOKEN, 1)
+        .assert_user_error("Contract is globally paused");
+
+    // as are the proposals and the week settings
+    mb_setup
+        .call_propose_project(
+            &first_user_addr,
+            0,
+            b"NewProj",
+            FIRST_PROJ_TOKEN,
+            1_000,
+            3,
+            3,
+        )
+        .assert_user_error("Contract is globally paused");
+    mb_setup
+        .call_set_bonus_week(2, 20_000)
+        .assert_user_error("Contract is globally paused");
+    mb_setup
+        .call_set_week_data_hash(1, &[1u8; 32])
+        .assert_user_error("Contract is globally paused");
+
+    // views keep working
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert!(sc.globally_paused().get());
+            assert_eq!(sc.get_last_checkpoint_week(), 1);
+        })
+        .assert_ok();
+
+    mb_setup.call_unpause_all(&pauser).assert_ok();
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+}
+
+#[test]
+fn timelock_test() {
+    let new_signer =
+        hex_literal::hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.set_current_epoch(20);
+
+    let owner_addr = mb_setup.owner_addr.clone();
+    let first_user_addr = mb_setup.first_user_addr.clone();
+
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_timelock_delay_epochs(7);
+        })
+        .assert_ok();
+
+    // sensitive actions may no longer be called directly
+    mb_setup
+        .call_add_signer(&new_signer)
+        .assert_user_error("Action must go through the timelock");
+    mb_setup
+        .call_grant_role(&first_user_addr, Role::Pauser)
+        .assert_user_error("Action must go through the timelock");
+    mb_setup
+        .call_add_admins(&[&first_user_addr])
+        .assert_user_error("Action must go through the timelock");
+    mb_setup
+        .call_set_oracle_quorum(0)
+        .assert_user_error("Action must go through the timelock");
+    mb_setup
+        .call_set_treasury_address(&first_user_addr)
+        .assert_user_error("Action must go through the timelock");
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_claim_signature_threshold(1);
+        })
+        .assert_user_error("Action must go through the timelock");
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_timelock_delay_epochs(0);
+        })
+        .assert_user_error("Action must go through the timelock");
+
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            let action_id = sc.schedule_action(TimelockAction::AddSigner(managed_address!(
+                &Address::from(&new_signer)
+            )));
+            assert_eq!(action_id, 1);
+        })
+        .assert_ok();
+
+    // anyone may execute the action once the delay has passed
+    mb_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &mb_setup.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.execute_action(1);
+            },
+        )
+        .assert_user_error("Action not executable yet");
+
+    mb_setup.set_current_epoch(27);
+    mb_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &mb_setup.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.execute_action(1);
+            },
+        )
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert!(sc
+                .signers()
+                .contains(&managed_address!(&Address::from(&new_signer))));
+        })
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &mb_setup.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.execute_action(1);
+            },
+        )
+        .assert_user_error("Unknown action");
+
+    // cancelled actions can no longer be executed
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            let action_id = sc.schedule_action(TimelockAction::SetProjectClaimFee(
+                managed_buffer!(FIRST_PROJ_ID),
+                100,
+            ));
+            assert_eq!(action_id, 2);
+        })
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &mb_setup.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.cancel_action(2);
+            },
+        )
+        .assert_user_error("Endpoint can only be called by owner");
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.cancel_action(2);
+        })
+        .assert_ok();
+
+    mb_setup.set_current_epoch(34);
+    mb_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &mb_setup.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.execute_action(2);
+            },
+        )
+        .assert_user_error("Unknown action");
+
+    // roles are granted through the timelock
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            let action_id = sc.schedule_action(TimelockAction::GrantRole(
+                managed_address!(&first_user_addr),
+                Role::Pauser,
+            ));
+            assert_eq!(action_id, 3);
+        })
+        .assert_ok();
+    mb_setup.set_current_epoch(41);
+    mb_setup
+        .b_mock
+        .execute_tx(
+            &first_user_addr,
+            &mb_setup.mb_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.execute_action(3);
+            },
+        )
+        .assert_ok();
+    mb_setup.call_pause_all(&first_user_addr).assert_ok();
+}
+
+#[test]
+fn audit_log_test() {
+    let new_signer =
+        hex_literal::hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.b_mock.set_block_timestamp(1_000);
+    mb_setup.call_add_signer(&new_signer).assert_ok();
+
+    let owner_addr = mb_setup.owner_addr.clone();
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            assert_eq!(sc.get_audit_log_length(), 3);
+
+            let first_page: Vec<_> = sc.get_audit_log(1, 2).into_iter().collect();
+            assert_eq!(first_page.len(), 2);
+            match &first_page[0].kind {
+                AdminActionKind::ProjectAdded(project_id) => {
+                    assert_eq!(project_id, &managed_buffer!(FIRST_PROJ_ID))
+                }
+                _ => panic!("Expected a project added entry"),
+            }
+            match &first_page[1].kind {
+                AdminActionKind::ProjectAdded(project_id) => {
+                    assert_eq!(project_id, &managed_buffer!(SECOND_PROJ_ID))
+                }
+                _ => panic!("Expected a project added entry"),
+            }
+
+            let second_page: Vec<_> = sc.get_audit_log(3, 2).into_iter().collect();
+            assert_eq!(second_page.len(), 1);
+            let action = &second_page[0];
+            assert_eq!(action.caller, managed_address!(&owner_addr));
+            assert_eq!(action.timestamp, 1_000);
+            match &action.kind {
+                AdminActionKind::SignerAdded(signer) => {
+                    assert_eq!(signer, &managed_address!(&Address::from(&new_signer)))
+                }
+                _ => panic!("Expected a signer added entry"),
+            }
+        })
+        .assert_ok();
+
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let _ = sc.get_audit_log(1, 51);
+        })
+        .assert_user_error("Page size too large");
+}
+
+#[test]
+fn claim_circuit_breaker_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
+
+    // signed amount way above the checkpoint's total, i.e. 100 times the weekly rewards
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("ba79b59828638b5f967f1b1745eae68ff2c642f83c16073fcb2baba886be5a1e199aaf221792e59db30aa6fac8b208e2b33dd6eb30a485e5bddde59426c65608");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 1, 10_000_000, 0, &sig_first_user_week_1)
+        .assert_ok();
+
+    // nothing is paid, the project's claims are paused and the rewards are kept for the user
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+    mb_setup
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let project_id = managed_buffer!(FIRST_PROJ_ID);
+            assert!(sc.project_claims_paused(&project_id).get());
+            assert_eq!(
+                sc.leftover_project_funds(&project_id).get(),
+                managed_biguint!(0)
+            );
+            assert_eq!(
+                sc.total_claimed_project_rewards(&project_id).get(),
+                managed_biguint!(TOTAL_FIRST_PROJ_TOKENS)
+            );
+
+            // only what was left of the project's funds is kept
+            let kept_rewards = sc
+                .paused_project_rewards(&managed_address!(&first_user_addr), &project_id)
+                .get();
+            assert_eq!(kept_rewards, managed_biguint!(TOTAL_FIRST_PROJ_TOKENS));
+            assert_eq!(
+                sc.total_paused_project_rewards(&project_id).get(),
+                kept_rewards
+            );
+        })
+        .assert_ok();
+
+    // only the owner may resume the claims after a circuit breaker trip
+    let first_proj_owner = mb_setup.first_project_owner.clone();
+    mb_setup
+        .call_resume_project_claims_as(&first_proj_owner, FIRST_PROJ_ID)
+        .assert_user_error("Only owner may resume claims paused by the circuit breaker");
+    mb_setup
+        .call_resume_project_claims(FIRST_PROJ_ID)
+        .assert_ok();
+}
+
+#[test]
+fn claim_circuit_breaker_shared_token_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.add_default_projects();
+    mb_setup.deposit_rewards_default_projects();
+
+    // another project paying the same token, which is not active in week 1
+    let second_proj_owner = mb_setup.second_project_owner.clone();
+    mb_setup
+        .b_mock
+        .set_esdt_balance(&second_proj_owner, FIRST_PROJ_TOKEN, &rust_biguint!(1_000));
+    mb_setup
+        .call_add_project(
+            b"SharedProj",
+            &second_proj_owner,
+            FIRST_PROJ_TOKEN,
+            1_000,
+            2,
+            1,
+            0,
+        )
+        .assert_ok();
+    mb_setup
+        .call_deposit_rewards(&second_proj_owner, b"SharedProj", FIRST_PROJ_TOKEN, 1_000)
+        .assert_ok();
+
+    mb_setup.add_default_checkpoints();
+    mb_setup.call_unpause().assert_ok();
 
-    // claim first user week 2 ok
+    // signed amount way above the checkpoint's total, which trips the first project's breaker
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    let sig_first_user_week_1 = hex_literal::hex!("ba79b59828638b5f967f1b1745eae68ff2c642f83c16073fcb2baba886be5a1e199aaf221792e59db30aa6fac8b208e2b33dd6eb30a485e5bddde59426c65608");
     mb_setup
-        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .call_claim_rewards(&first_user_addr, 1, 10_000_000, 0, &sig_first_user_week_1)
         .assert_ok();
+
+    // the liabilities are still covered by the SC's balance
+    let mb_balance = TOTAL_FIRST_PROJ_TOKENS + 1_000;
     mb_setup.b_mock.check_esdt_balance(
-        &first_user_addr,
+        mb_setup.mb_wrapper.address_ref(),
         FIRST_PROJ_TOKEN,
-        &rust_biguint!(83_333_333 + 41_666_666),
+        &rust_biguint!(mb_balance),
     );
-    mb_setup.b_mock.check_esdt_balance(
-        &first_user_addr,
-        SECOND_PROJ_TOKEN,
-        &rust_biguint!(50_000_000),
+    assert_eq!(
+        mb_setup.get_token_liabilities(FIRST_PROJ_TOKEN, 0),
+        mb_balance
     );
 
-    // claim second user week 2 ok
+    // once resumed, the kept rewards are paid without touching the other project's deposit
     mb_setup
-        .call_claim_rewards(&second_user_addr, 2, 50_000, 0, &sig_second_user_week_2)
+        .call_resume_project_claims(FIRST_PROJ_ID)
+        .assert_ok();
+    mb_setup
+        .call_claim_paused_project_rewards(&first_user_addr, &[FIRST_PROJ_ID])
         .assert_ok();
     mb_setup.b_mock.check_esdt_balance(
-        &second_user_addr,
+        &first_user_addr,
         FIRST_PROJ_TOKEN,
-        &rust_biguint!(166_666_666 + 83_333_333),
-    );
-    mb_setup.b_mock.check_esdt_balance(
-        &second_user_addr,
-        SECOND_PROJ_TOKEN,
-        &rust_biguint!(100_000_000),
+        &rust_biguint!(TOTAL_FIRST_PROJ_TOKENS),
     );
-
-    // clear expired projects
-    mb_setup.b_mock.set_block_epoch(100);
-    mb_setup.call_clear_expired_projects().assert_ok();
-
     mb_setup.b_mock.check_esdt_balance(
-        &mb_setup.first_project_owner.clone(),
+        mb_setup.mb_wrapper.address_ref(),
         FIRST_PROJ_TOKEN,
-        &rust_biguint!(625_000_002),
-    );
-    mb_setup.b_mock.check_esdt_balance(
-        &mb_setup.second_project_owner.clone(),
-        SECOND_PROJ_TOKEN,
-        &rust_biguint!(1_850_000_000),
+        &rust_biguint!(1_000),
     );
+    assert_eq!(mb_setup.get_token_liabilities(FIRST_PROJ_TOKEN, 0), 1_000);
 }
 
 #[test]
-fn claim_rewards_multiple_test() {
+fn contract_status_test() {
     let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
     mb_setup.add_default_projects();
-    mb_setup.deposit_rewards_default_projects();
     mb_setup.add_default_checkpoints();
-    mb_setup.call_unpause().assert_ok();
-
-    let first_user_addr = mb_setup.first_user_addr.clone();
-    let sig_first_user_week_1 = hex_literal::hex!("d47c0d67b2d25de8b4a3f43d91a2b5ccb522afac47321ae80bf89c90a4445b26adefa693ab685fa20891f736d74eb2dedc11c4b1a8d6e642fa28df270d6ebe08");
-    let sig_first_user_week_2 = hex_literal::hex!("b4aadf08eea4cc7c636922511943edbab2ff6ef2558528e0e7b03c7448367989fe860ac091be4d942304f04c86b1eaa0501f36e02819a3c628b4c53f3d3ac801");
 
-    // claim first two weeks user 1 ok
+    // week 4, with the last checkpoint for week 2
+    mb_setup.set_current_epoch(33);
     mb_setup
-        .call_claim_rewards_multiple(
-            &first_user_addr,
-            &[
-                (1, 25_000, 0, &sig_first_user_week_1),
-                (2, 25_000, 0, &sig_first_user_week_2),
-            ],
-        )
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let status = sc.get_contract_status();
+            assert_eq!(status.current_week, 4);
+            assert_eq!(status.last_checkpoint_week, 2);
+            assert_eq!(status.nr_active_projects, 1);
+            assert!(status.claims_paused);
+            assert!(!status.globally_paused);
+            assert_eq!(status.signers.len(), 1);
+        })
         .assert_ok();
+}
 
-    mb_setup.b_mock.check_esdt_balance(
-        &first_user_addr,
-        FIRST_PROJ_TOKEN,
-        &rust_biguint!(83_333_333 + 41_666_666),
-    );
-    mb_setup.b_mock.check_esdt_balance(
-        &first_user_addr,
-        SECOND_PROJ_TOKEN,
-        &rust_biguint!(50_000_000),
-    );
+#[test]
+fn week_schedule_test() {
+    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
+    mb_setup.set_current_epoch(20);
+    assert_eq!(mb_setup.get_current_week(), 2);
 
-    // try claim week 1 again
     mb_setup
-        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
-        .assert_user_error("Already claimed rewards for this week");
+        .call_set_week_schedule(5, 0)
+        .assert_user_error("Invalid week length");
+    mb_setup
+        .call_set_week_schedule(25, 7)
+        .assert_user_error("Invalid first week start epoch");
 
-    // try claim week 2 again
+    // any schedule is allowed before the first checkpoint
+    mb_setup.call_set_week_schedule(6, 2).assert_ok();
+    assert_eq!(mb_setup.get_current_week(), 7);
     mb_setup
-        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_1)
-        .assert_user_error("Already claimed rewards for this week");
+        .b_mock
+        .execute_query(&mb_setup.mb_wrapper, |sc| {
+            let (first_week_start_epoch, epochs_per_week) = sc.get_week_schedule().into_tuple();
+            assert_eq!(first_week_start_epoch, 6);
+            assert_eq!(epochs_per_week, 2);
+        })
+        .assert_ok();
+
+    // afterwards, the past weeks' epochs may not change, even if the current week stays the same
+    mb_setup.call_set_week_schedule(5, 7).assert_ok();
+    mb_setup
+        .call_add_rewards_checkpoint(1, 100_000, 0)
+        .assert_ok();
+    mb_setup
+        .call_set_week_schedule(6, 7)
+        .assert_user_error("Checkpoints already added");
+    assert_eq!(mb_setup.get_current_week(), 2);
 }
 
 #[test]
-fn grace_period_test() {
+fn claim_rewards_with_stake_query_test() {
     let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
     mb_setup.add_default_projects();
     mb_setup.deposit_rewards_default_projects();
     mb_setup.add_default_checkpoints();
     mb_setup.call_unpause().assert_ok();
 
-    let owner_addr = mb_setup.owner_addr.clone();
     let first_user_addr = mb_setup.first_user_addr.clone();
-    let sig_first_user_week_1 = hex_literal::hex!("d47c0d67b2d25de8b4a3f43d91a2b5ccb522afac47321ae80bf89c90a4445b26adefa693ab685fa20891f736d74eb2dedc11c4b1a8d6e642fa28df270d6ebe08");
-
-    // set current week = 6
-    mb_setup.b_mock.set_block_epoch(50);
+    let provider_addr = mb_setup.mb_wrapper.address_ref().clone();
+    let sig_first_user_week_2 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
 
-    // get claimable weeks - only able to claim week 2
-    let claimable_weeks = mb_setup.get_user_claimable_weeks(&first_user_addr);
-    assert_eq!(claimable_weeks, &[2usize]);
+    mb_setup
+        .call_add_stake_providers(&[&first_user_addr])
+        .assert_user_error("Invalid SC address");
+    mb_setup
+        .call_claim_rewards_with_stake_query(
+            &first_user_addr,
+            &provider_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2,
+        )
+        .assert_user_error("Provider not whitelisted");
+    mb_setup
+        .call_add_stake_providers(&[&provider_addr])
+        .assert_ok();
 
-    // set grace period of 5 weeks,
+    // a lost query blocks new ones until it times out
+    let owner_addr = mb_setup.owner_addr.clone();
     mb_setup
         .b_mock
         .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
-            sc.rewards_nr_first_grace_weeks().set(5);
+            sc.pending_stake_query_epoch(&managed_address!(&first_user_addr))
+                .set(20);
         })
         .assert_ok();
+    mb_setup
+        .call_claim_rewards_with_stake_query(
+            &first_user_addr,
+            &provider_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2,
+        )
+        .assert_user_error("Stake query already in progress");
+    mb_setup.set_current_epoch(21);
+    mb_setup
+        .call_claim_rewards_with_stake_query(
+            &first_user_addr,
+            &provider_addr,
+            3,
+            25_000,
+            0,
+            &sig_first_user_week_2,
+        )
+        .assert_user_error("No checkpoint for week yet");
 
-    // get claimable weeks - user still can only claim for week 2
-    let claimable_weeks = mb_setup.get_user_claimable_weeks(&first_user_addr);
-    assert_eq!(claimable_weeks, &[2usize]);
-
-    // user try claim week 1
+    // failed queries send nothing
     mb_setup
-        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
-        .assert_user_error("Claiming too late");
+        .call_stake_query_callback(&first_user_addr, 1, 25_000, None)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
 
-    // set grace weeks to 6
+    // neither are queries answered while the SC is paused
     mb_setup
         .b_mock
         .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
-            sc.rewards_nr_first_grace_weeks().set(6);
+            sc.globally_paused().set(true);
         })
         .assert_ok();
-
-    // get claimable weeks - user can now claim for week 1 and 2
-    let claimable_weeks = mb_setup.get_user_claimable_weeks(&first_user_addr);
-    assert_eq!(claimable_weeks, &[1usize, 2usize]);
-
-    // user claim week 1 ok
     mb_setup
-        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .call_stake_query_callback(&first_user_addr, 1, 25_000, Some(25_000))
         .assert_ok();
-}
-
-#[test]
-fn project_with_split_rewards_test() {
-    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
-    mb_setup.call_unpause().assert_ok();
-
-    // 25% goes to LKMEX holders, 75% to delegation
     mb_setup
-        .call_add_project(
-            FIRST_PROJ_ID,
-            &mb_setup.first_project_owner.clone(),
-            FIRST_PROJ_TOKEN,
-            TOTAL_FIRST_PROJ_TOKENS,
-            1,
-            3,
-            25,
-        )
+        .b_mock
+        .check_esdt_balance(&first_user_addr, FIRST_PROJ_TOKEN, &rust_biguint!(0));
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.globally_paused().clear();
+        })
         .assert_ok();
 
+    // a lower active stake than the signed amount is used instead
     mb_setup
-        .call_deposit_rewards(
-            &mb_setup.first_project_owner.clone(),
-            FIRST_PROJ_ID,
-            FIRST_PROJ_TOKEN,
-            TOTAL_FIRST_PROJ_TOKENS,
-        )
+        .call_stake_query_callback(&first_user_addr, 1, 25_000, Some(12_500))
         .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(41_666_666),
+    );
 
-    mb_setup.b_mock.set_block_epoch(15);
-
+    // a higher active stake is capped at the signed amount
     mb_setup
-        .call_add_rewards_checkpoint(1, 100_000, 200_000)
+        .call_stake_query_callback(&first_user_addr, 2, 25_000, Some(1_000_000))
         .assert_ok();
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        FIRST_PROJ_TOKEN,
+        &rust_biguint!(83_333_332),
+    );
+    mb_setup.b_mock.check_esdt_balance(
+        &first_user_addr,
+        SECOND_PROJ_TOKEN,
+        &rust_biguint!(50_000_000),
+    );
 
-    let first_user_addr = mb_setup.first_user_addr.clone();
-    let sig_first_user_week_1 = hex_literal::hex!("7d086ee457a3ce00013156e1d4b30fe9911264833983ed212ce3d3b9c7bc61c15e671014bca50142a1f066012380f0c30f3db9f15bb3bbd641e03e88fd663f06");
-
-    // (1/4 * 750,000,000 + 1/2 * 250,000,000) / 3 ~= 104,166,666
-    let expected_rewards_amount = 104_166_666u64;
-    let actual_rewards_amount = mb_setup.get_pretty_rewards(1, 25_000, 100_000)[0].2;
-    assert_eq!(expected_rewards_amount, actual_rewards_amount);
-
+    // the week can't be claimed again
     mb_setup
-        .call_claim_rewards(&first_user_addr, 1, 25_000, 100_000, &sig_first_user_week_1)
+        .call_stake_query_callback(&first_user_addr, 2, 25_000, Some(25_000))
         .assert_ok();
-
     mb_setup.b_mock.check_esdt_balance(
         &first_user_addr,
         FIRST_PROJ_TOKEN,
-        &rust_biguint!(expected_rewards_amount),
+        &rust_biguint!(83_333_332),
     );
+    mb_setup
+        .call_claim_rewards_with_stake_query(
+            &first_user_addr,
+            &provider_addr,
+            2,
+            25_000,
+            0,
+            &sig_first_user_week_2,
+        )
+        .assert_user_error("Already claimed rewards for this week");
 }
 
 #[test]
-fn add_project_with_start_week_in_the_past_test() {
+fn energy_lookup_test() {
     let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
-    mb_setup.call_unpause().assert_ok();
-
-    // set current week in the future - week 5
-    mb_setup.b_mock.set_block_epoch(41);
-
+    let proj_owner = mb_setup.first_project_owner.clone();
+    let first_user_addr = mb_setup.first_user_addr.clone();
+    mb_setup
+        .b_mock
+        .set_esdt_balance(&proj_owner, b"ENRG-123456", &rust_biguint!(1_000));
     mb_setup
         .call_add_project(
-            FIRST_PROJ_ID,
-            &mb_setup.first_project_owner.clone(),
-            FIRST_PROJ_TOKEN,
-            TOTAL_FIRST_PROJ_TOKENS,
+            b"EnergyProj",
+            &proj_owner,
+            b"ENRG-123456",
+            1_000,
             1,
-            7,
-            0,
+            2,
+            10_000,
         )
         .assert_ok();
-
     mb_setup
-        .call_deposit_rewards(
-            &mb_setup.first_project_owner.clone(),
-            FIRST_PROJ_ID,
-            FIRST_PROJ_TOKEN,
-            TOTAL_FIRST_PROJ_TOKENS,
-        )
+        .call_deposit_rewards(&proj_owner, b"EnergyProj", b"ENRG-123456", 1_000)
         .assert_ok();
-
     mb_setup
-        .call_add_rewards_checkpoint(1, 100_000, 0)
+        .call_set_project_energy_weighted(&proj_owner, b"EnergyProj", true)
         .assert_ok();
 
-    let first_user_addr = mb_setup.first_user_addr.clone();
-    let sig_first_user_week_1 = hex_literal::hex!("d47c0d67b2d25de8b4a3f43d91a2b5ccb522afac47321ae80bf89c90a4445b26adefa693ab685fa20891f736d74eb2dedc11c4b1a8d6e642fa28df270d6ebe08");
+    mb_setup
+        .call_register_energy(&first_user_addr)
+        .assert_user_error("Energy lookup not enabled");
+    mb_setup
+        .call_set_energy_lookup_enabled(true)
+        .assert_user_error("Energy factory address not set");
 
+    let energy_factory_addr = mb_setup.mb_wrapper.address_ref().clone();
     mb_setup
-        .call_claim_rewards(&first_user_addr, 1, 25_000, 0, &sig_first_user_week_1)
+        .call_set_energy_factory_address(&energy_factory_addr)
         .assert_ok();
+    mb_setup.call_set_energy_lookup_enabled(true).assert_ok();
 
-    mb_setup.b_mock.check_esdt_balance(
-        &first_user_addr,
-        FIRST_PROJ_TOKEN,
-        &rust_biguint!(35_714_285),
-    );
-}
-
-#[test]
-fn clear_project_funds_not_deposited_test() {
-    let mut mb_setup = MetabondingSetup::new(metabonding::contract_obj);
-    mb_setup.call_unpause().assert_ok();
-    mb_setup.add_default_projects();
-
-    // get project IDs before
-    let proj_ids = mb_setup.get_all_project_ids();
-    assert_eq!(
-        proj_ids,
-        vec![FIRST_PROJ_ID.to_vec(), SECOND_PROJ_ID.to_vec(),]
-    );
-
-    // set current week - week 9 - first project expires
-    mb_setup.b_mock.set_block_epoch(68);
+    // simulate the energy registered by the users during week 2
+    let owner_addr = mb_setup.owner_addr.clone();
+    mb_setup.set_current_epoch(20);
+    mb_setup
+        .b_mock
+        .execute_tx(&owner_addr, &mb_setup.mb_wrapper, &rust_biguint!(0), |sc| {
+            sc.user_week_energy(&managed_address!(&first_user_addr), 2)
+                .set(&managed_biguint!(500));
+            sc.week_registered_energy(2).set(&managed_biguint!(2_000));
+        })
+        .assert_ok();
 
-    mb_setup.call_clear_expired_projects().assert_ok();
+    mb_setup
+        .call_add_rewards_checkpoint(1, 100_000, 1_000)
+        .assert_ok();
+    mb_setup
+        .call_add_rewards_checkpoint(2, 100_000, 1_000)
+        .assert_ok();
+    mb_setup
+        .call_register_energy(&first_user_addr)
+        .assert_user_error("Energy already snapshotted for week");
 
-    // get project IDs after
-    let proj_ids = mb_setup.get_all_project_ids();
-    assert_eq!(proj_ids, vec![SECOND_PROJ_ID.to_vec(),]);
+    // the registered energy is used, even though no LKMEX amount was signed
+    mb_setup.call_unpause().assert_ok();
+    let sig_first_user_week_2 = hex_literal::hex!("8102a5359225c286e8cad26c6f886f4f09e4ef9b767b55945d8951bc84d77058c5f3427245d4fc074317c53fc22f1d35deeda6c8086ad072ae3f20b40adcc10a");
+    mb_setup
+        .call_claim_rewards(&first_user_addr, 2, 25_000, 0, &sig_first_user_week_2)
+        .assert_ok();
+    mb_setup
+        .b_mock
+        .check_esdt_balance(&first_user_addr, b"ENRG-123456", &rust_biguint!(125));
 }
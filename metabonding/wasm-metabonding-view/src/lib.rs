@@ -0,0 +1,36 @@
+// Code generated by the elrond-wasm multi-contract system. DO NOT EDIT.
+
+////////////////////////////////////////////////////
+////////////////// AUTO-GENERATED //////////////////
+////////////////////////////////////////////////////
+
+// Init:                                 1
+// Endpoints:                           16
+// Async Callback (empty):               1
+// Total number of exported functions:  18
+
+#![no_std]
+
+elrond_wasm_node::external_view_wasm_endpoints! {
+    metabonding
+    (
+        getProjects
+        getProjectsByCategory
+        getProjectsByRewardToken
+        getActiveProjects
+        getRemainingRewards
+        getProjectDetails
+        getRewardsForWeekMultiUser
+        getProjectApr
+        getRewardsCheckpoints
+        getRewardsForWeeks
+        getUserStats
+        getClaimedWeeks
+        getClaimStatuses
+        getTokenLiabilities
+        getContractStatus
+        getAuditLog
+    )
+}
+
+elrond_wasm_node::wasm_empty_callback! {}
@@ -5,9 +5,9 @@
 ////////////////////////////////////////////////////
 
 // Init:                                 1
-// Endpoints:                           18
-// Async Callback (empty):               1
-// Total number of exported functions:  20
+// Endpoints:                          257
+// Async Callback:                       1
+// Total number of exported functions: 259
 
 #![no_std]
 
@@ -19,20 +19,258 @@ elrond_wasm_node::wasm_endpoints! {
         unpause
         isPaused
         addProject
+        renewProject
+        addProjectWithDetails
+        setMaxActiveProjects
+        updateProjectRewardSupply
+        setProjectRewardSplit
+        setProjectEmissionCurve
+        setProjectEdgeOffsets
+        setProjectClaimFee
+        cancelProject
         removeProject
         clearExpiredProjects
+        clearExpiredProject
+        setCleanupBounty
+        fundCleanupBounty
+        withdrawCleanupBounty
+        pauseProjectClaims
+        resumeProjectClaims
+        updateProjectMetadata
+        setProjectCategory
+        setProjectMaxRewardPerUserPerWeek
+        reclaimProjectRoundingRemainders
+        setProjectStreakBonus
+        setProjectMinRewardPerUser
+        addProjectPartnerReward
+        setProjectRewardTokenNonce
+        setProjectClaimantWhitelistEnabled
+        addProjectClaimants
+        removeProjectClaimants
+        isProjectClaimantEligible
+        transferProjectOwnership
+        acceptProjectOwnership
+        setProjectEnergyWeighted
+        setProjectDistributionMode
+        setProjectLkmexFactor
         getAllProjectIds
         getProjectById
         getCurrentWeek
+        getProjectIdByName
+        getMaxActiveProjects
+        getProjectOwner
+        getPendingProjectOwner
+        getTotalClaimedProjectRewards
+        getTotalSweptProjectRewards
+        getProjectUniqueClaimers
+        getProjectClaimsForWeek
+        getCleanupBountyToken
+        getCleanupBountyAmount
+        getCleanupBountyPool
+        areProjectClaimsPaused
+        getProjectClaimsPausedBy
+        getPausedProjectRewards
+        getPausedPartnerRewards
+        getTotalPausedProjectRewards
+        getPendingLockedRewards
+        getTotalPendingLockedRewards
+        getProjectLkmexFactorBps
+        getProjectDistributionMode
+        isProjectEnergyWeighted
+        getProjectStartOffsetEpochs
+        getProjectEndOffsetEpochs
+        getProjectEmissionCurve
+        getProjectClaimFee
+        getProjectCollectedFees
+        getProjectMaxRewardPerUserPerWeek
+        getProjectStreakBonusBpsPerWeek
+        getProjectMaxStreakBonusBps
+        getProjectMinRewardPerUser
+        getProjectBonusPool
+        getProjectRoundingRemainders
+        getProjectWeekCappedRewards
+        getLastCappedRewardsRefundWeek
+        getProjectCappedRewards
+        getProjectCategory
+        getWeekActiveProjects
+        isProjectClaimantWhitelistEnabled
+        getProjectClaimants
+        getProjectCancelledFromWeek
+        getPendingProjectExtension
+        getDepositedAmount
+        getProjectRewardTokenNonce
+        getProjectPartnerRewards
+        getProjectRewardsLockEpochs
+        isProjectRewardTokenNonceFixed
+        getListingFeeEscrow
+        isOwnerDepositAllowed
+        getFundedProjects
+        setListingFee
+        proposeProject
+        approveProject
+        forfeitListingFee
+        rejectProject
+        getPendingProjectIds
+        getListingFeeToken
+        getListingFeeAmount
+        getTokenProperties
+        setRewardTokenWhitelistEnabled
+        addRewardTokenToWhitelist
+        removeRewardTokenFromWhitelist
+        isRewardTokenWhitelistEnabled
+        getRewardTokenWhitelist
         addRewardsCheckpoint
+        addRewardsCheckpoints
+        updateRewardsCheckpoint
+        setCheckpointDisputeEpochs
+        setWeekSchedule
+        getWeekSchedule
+        finalizeCheckpoint
+        getCheckpointStatus
+        setCheckpointTotalEnergy
+        registerSignedEnergy
+        setCheckpointSqrtSums
+        setBonusWeek
+        setWeekMerkleRoot
+        setWeekDataHash
         depositRewards
+        depositRewardsMulti
+        setOwnerDepositAllowed
+        setDepositDeadlineEpochs
+        deactivateUnfundedProject
+        getProjectDepositDeadline
+        extendProject
+        depositProjectBonusPool
+        depositPartnerRewards
+        depositProjectExtension
         getRewardsForWeek
+        getDepositDeadlineEpochs
+        getUserWeekEnergy
+        getWeekRegisteredEnergy
+        getBonusWeekMultiplierBps
+        getCheckpointUpdateNonce
+        getPrunedCheckpointsUpToWeek
+        getTotalActiveStake
+        getTotalLockedAssetSupply
+        setCheckpointSources
+        requestRewardsCheckpoint
+        getDelegationAddress
+        getLkmexStakingAddress
+        getPendingAutoCheckpointWeek
+        addKeepers
+        removeKeepers
+        setKeeperBounty
+        fundKeeperBounty
+        withdrawKeeperBounty
+        keeperAddRewardsCheckpoint
+        getKeepers
+        getKeeperBountyToken
+        getKeeperBountyAmount
+        getKeeperBountyPool
         claimRewards
+        setRewardDestination
+        setClaimDelegate
+        removeClaimDelegate
+        claimForUser
+        claimPending
+        relayedClaim
+        claimWithProof
+        claimPausedProjectRewards
+        claimLockedRewards
+        claimRewardsMultiSig
+        claimRewardsForProjects
+        claimRewardsBatch
+        setMaxProjectsPerClaim
+        getMaxProjectsPerClaim
+        setMinPayoutAmount
+        claimDust
+        setRewardsNrFirstGraceWeeks
+        getClaimDeadline
         getUserClaimableWeeks
+        getMinPayoutAmount
+        getUserDust
+        getUserProjectClaimStreak
+        getRewardDestination
+        getClaimDelegate
+        getClaimCursor
+        getUserActiveStake
+        addStakeProviders
+        removeStakeProviders
+        claimRewardsWithStakeQuery
+        getStakeProviders
+        getPendingStakeQueryEpoch
+        migrateClaimedWeeks
+        getRewardsClaimed
+        isProjectRewardsClaimed
+        enterFarm
+        addCompoundFarm
+        removeCompoundFarm
+        claimAndCompound
+        getCompoundFarmFarmingToken
+        lockTokens
+        getEnergyAmountForUser
+        getBaseAssetTokenId
+        getLockOptions
+        setEnergyFactoryAddress
+        setEnergyLookupEnabled
+        registerEnergy
+        setProjectRewardsLockEpochs
+        getUserEnergy
+        getEnergyFactoryAddress
+        isEnergyLookupEnabled
+        setClaimExpirationWeeks
+        setTreasuryAddress
+        rescueToken
+        reclaimFailedDeposit
+        emergencyWithdraw
+        pruneCheckpoints
+        sweepExpiredRewards
+        refundProjectCappedRewards
+        getFailedDeposits
+        setTimelockDelayEpochs
+        scheduleAction
+        executeAction
+        cancelAction
+        getScheduledAction
+        pauseAll
+        unpauseAll
+        isGloballyPaused
+        addSigner
+        removeSigner
+        setClaimSignatureThreshold
+        setSignerGracePeriodEpochs
+        addAdmins
+        removeAdmins
+        grantRole
+        revokeRole
+        getAdmins
+        getRoles
+        getAuditLogLength
+        getSigners
+        getRetiredSigners
+        getClaimSignatureThreshold
+        getSignerGracePeriodEpochs
+        getTimelockDelayEpochs
+        getTreasuryAddress
+        addOracles
+        removeOracles
+        setOracleQuorum
+        getOracles
+        getOracleQuorum
+        proposeNewOwner
+        claimOwnership
+        cancelOwnershipTransfer
+        getPendingOwner
+        setSigCheckDisabled
+        isSigCheckDisabled
+        getClaimNonce
+        getUserEnergyNonce
+        getRelayedClaimNonce
+        getWeekMerkleRoot
+        getWeekDataHash
         addSCAddressToWhitelist
         removeSCAddressFromWhitelist
         isSCAddressWhitelisted
+        callBack
     )
 }
-
-elrond_wasm_node::wasm_empty_callback! {}